@@ -1,5 +1,10 @@
 use std::fmt::{Display, Formatter};
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const MAX_CAPTURED_OUTPUT_BYTES: u64 = 64 * 1024;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LaunchError {
@@ -46,6 +51,134 @@ pub fn launch_path(path: &str) -> Result<(), LaunchError> {
     Ok(())
 }
 
+pub fn launch_command(path: &str, args: &[String]) -> Result<(), LaunchError> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err(LaunchError::EmptyPath);
+    }
+
+    let candidate = Path::new(trimmed);
+    if !candidate.exists() {
+        return Err(LaunchError::MissingPath(candidate.to_path_buf()));
+    }
+
+    std::process::Command::new(candidate)
+        .args(args)
+        .spawn()
+        .map_err(|error| LaunchError::LaunchFailed {
+            message: format!("failed to run '{trimmed}': {error}"),
+            code: error.raw_os_error(),
+        })?;
+    Ok(())
+}
+
+// Runs a command to completion and returns its stdout (capped at 64 KiB). The command is
+// resolved through PATH; a command still running after `timeout` is killed.
+pub fn run_command_capture(
+    command: &str,
+    args: &[String],
+    timeout: Duration,
+) -> Result<String, LaunchError> {
+    let trimmed = command.trim();
+    if trimmed.is_empty() {
+        return Err(LaunchError::EmptyPath);
+    }
+    let launch_failed =
+        |message: String, code: Option<i32>| LaunchError::LaunchFailed { message, code };
+    let mut child = Command::new(trimmed)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|error| {
+            launch_failed(
+                format!("failed to run '{trimmed}': {error}"),
+                error.raw_os_error(),
+            )
+        })?;
+
+    // Read on a separate thread so a chatty command cannot block on a full pipe.
+    let stdout = child.stdout.take().expect("command stdout should be piped");
+    let reader = std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = stdout
+            .take(MAX_CAPTURED_OUTPUT_BYTES)
+            .read_to_end(&mut bytes);
+        bytes
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(launch_failed(
+                    format!(
+                        "'{trimmed}' did not finish within {}ms",
+                        timeout.as_millis()
+                    ),
+                    None,
+                ));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+            Err(error) => {
+                return Err(launch_failed(
+                    format!("failed to wait for '{trimmed}': {error}"),
+                    error.raw_os_error(),
+                ))
+            }
+        }
+    };
+    let bytes = reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(launch_failed(
+            format!("'{trimmed}' exited with {status}"),
+            status.code(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&bytes).trim_end().to_string())
+}
+
+pub fn command_args_from_query(query: &str, command_name: &str) -> Vec<String> {
+    let tokens = split_command_line(query);
+    match tokens.split_first() {
+        Some((first, rest)) if first.eq_ignore_ascii_case(command_name.trim()) => rest.to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+fn split_command_line(input: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_token = false;
+    for ch in input.chars() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_token = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_token {
+                    out.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+    if has_token {
+        out.push(current);
+    }
+    out
+}
+
 pub fn launch_open_target(target: &str) -> Result<(), LaunchError> {
     let trimmed = target.trim();
     if trimmed.is_empty() {
@@ -118,7 +251,7 @@ fn to_wide(value: &str) -> Vec<u16> {
     value.encode_utf16().chain(std::iter::once(0)).collect()
 }
 
-fn is_non_filesystem_open_target(value: &str) -> bool {
+pub fn is_non_filesystem_open_target(value: &str) -> bool {
     let trimmed = value.trim();
     if trimmed.is_empty() {
         return false;
@@ -8,6 +8,7 @@ pub const ACTION_CLEAR_CLIPBOARD_ID: &str = "__nex_action_clear_clipboard__";
 pub const ACTION_OPEN_CONFIG_ID: &str = "__nex_action_open_config__";
 pub const ACTION_DIAGNOSTICS_BUNDLE_ID: &str = "__nex_action_diagnostics_bundle__";
 pub const ACTION_TRIM_MEMORY_ID: &str = "__nex_action_trim_memory__";
+pub const ACTION_REPAIR_INDEX_ID: &str = "__nex_action_repair_index__";
 pub const ACTION_WEB_SEARCH_PREFIX: &str = "__nex_action_web_search__:";
 
 #[derive(Debug, Clone, Copy)]
@@ -56,6 +57,12 @@ pub fn built_in_actions() -> &'static [BuiltInAction] {
             subtitle: "Clear overlay icon/query caches and log memory snapshot",
             keywords: &["memory", "trim", "cache", "compact"],
         },
+        BuiltInAction {
+            id: ACTION_REPAIR_INDEX_ID,
+            title: "Check and Repair Search Index",
+            subtitle: "Verify index integrity and compact the database",
+            keywords: &["repair", "index", "integrity", "vacuum", "corrupt"],
+        },
     ]
 }
 
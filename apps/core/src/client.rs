@@ -0,0 +1,158 @@
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Write};
+
+use crate::config::{Config, SearchMode};
+use crate::contract::{CoreRequest, CoreResponse, LaunchRequest, SearchRequest, SearchResultDto};
+use crate::core_service::{CoreService, ServiceError};
+use crate::ipc::{self, IpcStream};
+use crate::plugin_sdk::PluginRegistry;
+use crate::transport::{handle_request, ErrorCode, ErrorResponse, TransportResponse};
+
+#[derive(Debug)]
+pub enum ClientError {
+    Io(std::io::Error),
+    Service(ServiceError),
+    Remote(ErrorResponse),
+    Protocol(String),
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "client io error: {error}"),
+            Self::Service(error) => write!(f, "client service error: {error}"),
+            Self::Remote(error) => write!(f, "{:?}: {}", error.code, error.message),
+            Self::Protocol(error) => write!(f, "client protocol error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<std::io::Error> for ClientError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ServiceError> for ClientError {
+    fn from(value: ServiceError) -> Self {
+        Self::Service(value)
+    }
+}
+
+// Sends requests to the running instance when its IPC endpoint answers, otherwise
+// serves them from a read-only view of the index file.
+pub enum CoreClient {
+    Remote {
+        stream: BufReader<Box<dyn IpcStream>>,
+        next_id: u64,
+    },
+    Local(Box<CoreService>),
+}
+
+impl CoreClient {
+    pub fn connect_or_open(config: Config) -> Result<Self, ClientError> {
+        Self::connect_or_open_at(config, &ipc::default_endpoint())
+    }
+
+    pub fn connect_or_open_at(config: Config, endpoint: &str) -> Result<Self, ClientError> {
+        if config.ipc_enabled {
+            if let Ok(stream) = ipc::connect(endpoint) {
+                return Ok(Self::Remote {
+                    stream: BufReader::new(stream),
+                    next_id: 1,
+                });
+            }
+        }
+        let plugins = PluginRegistry::load_from_config(&config);
+        Ok(Self::Local(Box::new(
+            CoreService::open_read_only(config)?.with_plugins(plugins),
+        )))
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Self::Remote { .. })
+    }
+
+    pub fn request(&mut self, request: CoreRequest) -> Result<CoreResponse, ClientError> {
+        let response = match self {
+            Self::Local(service) => handle_request(service, request),
+            Self::Remote { stream, next_id } => {
+                let id = *next_id;
+                *next_id += 1;
+                let mut encoded = serde_json::to_value(&request)
+                    .map_err(|error| ClientError::Protocol(error.to_string()))?;
+                if let Some(object) = encoded.as_object_mut() {
+                    object.insert("id".to_string(), id.into());
+                }
+                let writer = stream.get_mut();
+                writer.write_all(encoded.to_string().as_bytes())?;
+                writer.write_all(b"\n")?;
+                writer.flush()?;
+
+                let mut line = String::new();
+                if stream.read_line(&mut line)? == 0 {
+                    return Err(ClientError::Protocol(
+                        "connection closed before a response arrived".to_string(),
+                    ));
+                }
+                serde_json::from_str::<TransportResponse>(line.trim())
+                    .map_err(|error| ClientError::Protocol(error.to_string()))?
+            }
+        };
+        match response {
+            TransportResponse::Ok { response } => Ok(response),
+            TransportResponse::Err { error } => Err(ClientError::Remote(error)),
+        }
+    }
+
+    pub fn search(
+        &mut self,
+        query: &str,
+        mode: Option<SearchMode>,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchResultDto>, ClientError> {
+        match self.request(CoreRequest::Search(SearchRequest {
+            query: query.to_string(),
+            limit,
+            mode,
+        }))? {
+            CoreResponse::Search(response) => Ok(response.results),
+            other => Err(unexpected_response(&other)),
+        }
+    }
+
+    pub fn launch_id(&mut self, id: &str) -> Result<(), ClientError> {
+        match self.request(CoreRequest::Launch(LaunchRequest {
+            id: Some(id.to_string()),
+            path: None,
+        }))? {
+            CoreResponse::Launch(_) => Ok(()),
+            other => Err(unexpected_response(&other)),
+        }
+    }
+
+    // Treats `target` as an item id first and falls back to the best search match.
+    // Returns the id of the launched item.
+    pub fn open(&mut self, target: &str) -> Result<String, ClientError> {
+        match self.launch_id(target) {
+            Ok(()) => return Ok(target.to_string()),
+            Err(ClientError::Remote(error)) if error.code == ErrorCode::ItemNotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        let Some(best) = self.search(target, None, Some(1))?.into_iter().next() else {
+            return Err(ClientError::Remote(ErrorResponse {
+                code: ErrorCode::ItemNotFound,
+                message: format!("no match for {target}"),
+            }));
+        };
+        self.launch_id(&best.id)?;
+        Ok(best.id)
+    }
+}
+
+fn unexpected_response(response: &CoreResponse) -> ClientError {
+    ClientError::Protocol(format!("unexpected response: {response:?}"))
+}
@@ -31,6 +31,39 @@ pub fn maybe_capture_latest(cfg: &Config) -> Result<bool, String> {
         return Ok(false);
     }
 
+    record_text(cfg, text)
+}
+
+// The system clipboard, or the newest history entry where it cannot be read.
+pub fn current_text(cfg: &Config) -> String {
+    if let Ok(Some(text)) = read_system_clipboard_text() {
+        return text;
+    }
+    if !cfg.clipboard_enabled {
+        return String::new();
+    }
+    load_entries(cfg)
+        .into_iter()
+        .next()
+        .map(|entry| entry.text)
+        .unwrap_or_default()
+}
+
+// Copies text produced by nex itself (e.g. plugin command output) and keeps it in the
+// history like a captured entry.
+pub fn copy_text_to_clipboard(cfg: &Config, text: &str) -> Result<(), String> {
+    write_system_clipboard_text(text)?;
+    let normalized = normalize_clipboard_text(text);
+    if !cfg.clipboard_enabled
+        || normalized.is_empty()
+        || is_sensitive_content(&normalized, &cfg.clipboard_exclude_sensitive_patterns)
+    {
+        return Ok(());
+    }
+    record_text(cfg, normalized).map(|_| ())
+}
+
+fn record_text(cfg: &Config, text: String) -> Result<bool, String> {
     let mut entries = load_entries(cfg);
     if entries.first().is_some_and(|entry| entry.text == text) {
         return Ok(false);
@@ -104,6 +137,29 @@ pub fn copy_result_to_clipboard(cfg: &Config, result_id: &str) -> Result<(), Str
     write_system_clipboard_text(&text)
 }
 
+pub fn load_history(cfg: &Config) -> Vec<ClipboardEntry> {
+    load_entries(cfg)
+}
+
+// Merges entries from another machine, keeping the newest first and applying local retention.
+pub fn import_entries(cfg: &Config, incoming: Vec<ClipboardEntry>) -> Result<usize, String> {
+    let mut entries = load_entries(cfg);
+    let before = entries.len();
+    for entry in incoming {
+        if entries
+            .iter()
+            .any(|existing| existing.id == entry.id || existing.text == entry.text)
+        {
+            continue;
+        }
+        entries.push(entry);
+    }
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.captured_epoch_secs));
+    prune_entries(cfg, &mut entries, now_epoch_secs());
+    save_entries(cfg, &entries)?;
+    Ok(entries.len().saturating_sub(before))
+}
+
 fn resolve_text_for_result(cfg: &Config, result_id: &str) -> Option<String> {
     let entry_id = result_id.strip_prefix("clipboard:")?;
     load_entries(cfg)
@@ -15,7 +15,7 @@ const LEGACY_APP_DIR_NAME_UNIX: &str = "swiftfind";
 const CONFIG_FILE_NAME: &str = "config.toml";
 const LEGACY_CONFIG_FILE_NAME: &str = "config.json";
 
-pub const CURRENT_CONFIG_VERSION: u32 = 11;
+pub const CURRENT_CONFIG_VERSION: u32 = 17;
 const LEGACY_IDLE_CACHE_TRIM_MS_V1: u32 = 1200;
 const LEGACY_ACTIVE_MEMORY_TARGET_MB_V1: u16 = 80;
 const TEMPLATE_REQUIRED_KEYS: &[&str] = &[
@@ -28,6 +28,14 @@ const TEMPLATE_REQUIRED_KEYS: &[&str] = &[
     "windows_search_fallback_filesystem",
     "show_files",
     "show_folders",
+    "browser_bookmarks_enabled",
+    "browser_profile_paths",
+    "recent_documents_enabled",
+    "path_executables_enabled",
+    "path_executables_extra_dirs",
+    "content_indexing_enabled",
+    "content_index_max_file_bytes",
+    "ipc_enabled",
     "search_mode_default",
     "search_dsl_enabled",
     "search_query_results_with_delay",
@@ -42,6 +50,7 @@ const TEMPLATE_REQUIRED_KEYS: &[&str] = &[
     "plugins_safe_mode",
     "game_mode_enabled",
     "plugin_paths",
+    "plugin_grants",
     "idle_cache_trim_ms",
     "active_memory_target_mb",
     "index_max_items_total",
@@ -58,6 +67,8 @@ pub enum SearchMode {
     Files,
     Actions,
     Clipboard,
+    Bookmarks,
+    Snippets,
 }
 
 impl SearchMode {
@@ -69,6 +80,8 @@ impl SearchMode {
             "files" | "file" => Some(Self::Files),
             "actions" | "action" => Some(Self::Actions),
             "clipboard" | "clip" => Some(Self::Clipboard),
+            "bookmarks" | "bookmark" => Some(Self::Bookmarks),
+            "snippets" | "snippet" => Some(Self::Snippets),
             _ => None,
         }
     }
@@ -116,6 +129,14 @@ pub struct Config {
     pub windows_search_fallback_filesystem: bool,
     pub show_files: bool,
     pub show_folders: bool,
+    pub browser_bookmarks_enabled: bool,
+    pub browser_profile_paths: Vec<PathBuf>,
+    pub recent_documents_enabled: bool,
+    pub path_executables_enabled: bool,
+    pub path_executables_extra_dirs: Vec<PathBuf>,
+    pub content_indexing_enabled: bool,
+    pub content_index_max_file_bytes: u64,
+    pub ipc_enabled: bool,
     pub hotkey: String,
     pub launch_at_startup: bool,
     pub hotkey_help: String,
@@ -133,6 +154,7 @@ pub struct Config {
     pub plugins_enabled: bool,
     pub plugin_paths: Vec<PathBuf>,
     pub plugins_safe_mode: bool,
+    pub plugin_grants: Vec<String>,
     pub game_mode_enabled: bool,
     pub idle_cache_trim_ms: u32,
     pub active_memory_target_mb: u16,
@@ -156,6 +178,14 @@ impl Default for Config {
             windows_search_fallback_filesystem: true,
             show_files: true,
             show_folders: true,
+            browser_bookmarks_enabled: true,
+            browser_profile_paths: default_browser_profile_paths(),
+            recent_documents_enabled: true,
+            path_executables_enabled: false,
+            path_executables_extra_dirs: Vec::new(),
+            content_indexing_enabled: false,
+            content_index_max_file_bytes: crate::content_index::DEFAULT_CONTENT_MAX_FILE_BYTES,
+            ipc_enabled: true,
             hotkey: "Ctrl+Space".to_string(),
             launch_at_startup: false,
             hotkey_help: format!(
@@ -190,6 +220,7 @@ impl Default for Config {
             plugins_enabled: true,
             plugin_paths: vec![app_dir.join("plugins")],
             plugins_safe_mode: true,
+            plugin_grants: Vec::new(),
             game_mode_enabled: false,
             idle_cache_trim_ms: 900,
             active_memory_target_mb: 72,
@@ -391,6 +422,8 @@ pub fn write_user_template(cfg: &Config, path: &Path) -> Result<(), ConfigError>
 
     let roots_section = json5_path_array_section(&cfg.discovery_roots);
     let excluded_roots_section = json5_path_array_section(&cfg.discovery_exclude_roots);
+    let browser_profiles_section = json5_path_array_section(&cfg.browser_profile_paths);
+    let path_executables_dirs_section = json5_path_array_section(&cfg.path_executables_extra_dirs);
 
     let mut text = String::new();
     text.push_str("{\n");
@@ -477,7 +510,60 @@ pub fn write_user_template(cfg: &Config, path: &Path) -> Result<(), ConfigError>
     text.push_str(if cfg.show_folders { "true" } else { "false" });
     text.push_str(",\n\n");
 
-    text.push_str("  // Search mode default: all | apps | files | actions | clipboard\n");
+    text.push_str("  // Index bookmarks from Chromium-family and Firefox browser profiles.\n");
+    text.push_str("  \"browser_bookmarks_enabled\": ");
+    text.push_str(if cfg.browser_bookmarks_enabled {
+        "true"
+    } else {
+        "false"
+    });
+    text.push_str(",\n");
+    text.push_str("  // Profile folders, or browser data folders that contain profiles.\n");
+    text.push_str("  \"browser_profile_paths\": ");
+    text.push_str(&browser_profiles_section);
+    text.push_str(",\n\n");
+    text.push_str(
+        "  // Index the OS recent-documents list (Windows Recent / XDG recently-used).\n",
+    );
+    text.push_str("  \"recent_documents_enabled\": ");
+    text.push_str(if cfg.recent_documents_enabled {
+        "true"
+    } else {
+        "false"
+    });
+    text.push_str(",\n\n");
+    text.push_str("  // Index executables on PATH as commands (type arguments after the name).\n");
+    text.push_str("  \"path_executables_enabled\": ");
+    text.push_str(if cfg.path_executables_enabled {
+        "true"
+    } else {
+        "false"
+    });
+    text.push_str(",\n");
+    text.push_str("  // Extra folders searched for executables after PATH.\n");
+    text.push_str("  \"path_executables_extra_dirs\": ");
+    text.push_str(&path_executables_dirs_section);
+    text.push_str(",\n\n");
+    text.push_str("  // Index text, Markdown and source file contents for `content:` queries.\n");
+    text.push_str("  \"content_indexing_enabled\": ");
+    text.push_str(if cfg.content_indexing_enabled {
+        "true"
+    } else {
+        "false"
+    });
+    text.push_str(",\n");
+    text.push_str("  // Files larger than this are skipped (1024..67108864).\n");
+    text.push_str("  \"content_index_max_file_bytes\": ");
+    text.push_str(&cfg.content_index_max_file_bytes.to_string());
+    text.push_str(",\n\n");
+    text.push_str("  // Serve core requests to local clients over a socket or named pipe.\n");
+    text.push_str("  \"ipc_enabled\": ");
+    text.push_str(if cfg.ipc_enabled { "true" } else { "false" });
+    text.push_str(",\n\n");
+
+    text.push_str(
+        "  // Search mode default: all | apps | files | actions | clipboard | bookmarks | snippets\n",
+    );
     text.push_str("  \"search_mode_default\": ");
     text.push_str(&json_string(match cfg.search_mode_default {
         SearchMode::All => "all",
@@ -485,6 +571,8 @@ pub fn write_user_template(cfg: &Config, path: &Path) -> Result<(), ConfigError>
         SearchMode::Files => "files",
         SearchMode::Actions => "actions",
         SearchMode::Clipboard => "clipboard",
+        SearchMode::Bookmarks => "bookmarks",
+        SearchMode::Snippets => "snippets",
     }));
     text.push_str(",\n");
     text.push_str(
@@ -595,6 +683,20 @@ pub fn write_user_template(cfg: &Config, path: &Path) -> Result<(), ConfigError>
         }
         text.push('\n');
     }
+    text.push_str("  ],\n");
+    text.push_str("  // Capabilities granted to plugins, as \"<plugin_id>:<capability>\" (or \"<plugin_id>:*\").\n");
+    text.push_str(
+        "  // Capabilities: run_commands | open_urls | read_clipboard | read_env | filesystem\n",
+    );
+    text.push_str("  \"plugin_grants\": [\n");
+    for (idx, grant) in cfg.plugin_grants.iter().enumerate() {
+        text.push_str("    ");
+        text.push_str(&json_string(grant));
+        if idx + 1 != cfg.plugin_grants.len() {
+            text.push(',');
+        }
+        text.push('\n');
+    }
     text.push_str("  ],\n\n");
 
     text.push_str("  // Runtime performance targets\n");
@@ -627,9 +729,12 @@ pub fn write_user_template(cfg: &Config, path: &Path) -> Result<(), ConfigError>
 fn write_user_template_toml(cfg: &Config, path: &Path) -> Result<(), ConfigError> {
     let roots_section = toml_path_array_section(&cfg.discovery_roots);
     let excluded_roots_section = toml_path_array_section(&cfg.discovery_exclude_roots);
+    let browser_profiles_section = toml_path_array_section(&cfg.browser_profile_paths);
+    let path_executables_dirs_section = toml_path_array_section(&cfg.path_executables_extra_dirs);
     let plugin_paths_section = toml_path_array_section(&cfg.plugin_paths);
     let clipboard_patterns_section =
         toml_string_array_section(&cfg.clipboard_exclude_sensitive_patterns);
+    let plugin_grants_section = toml_string_array_section(&cfg.plugin_grants);
 
     let mut text = String::new();
     text.push_str("# Nex config (TOML format).\n");
@@ -707,7 +812,58 @@ fn write_user_template_toml(cfg: &Config, path: &Path) -> Result<(), ConfigError
     text.push_str(if cfg.show_folders { "true" } else { "false" });
     text.push_str("\n\n");
 
-    text.push_str("# Search mode default: all | apps | files | actions | clipboard\n");
+    text.push_str("# Index bookmarks from Chromium-family and Firefox browser profiles.\n");
+    text.push_str("browser_bookmarks_enabled = ");
+    text.push_str(if cfg.browser_bookmarks_enabled {
+        "true"
+    } else {
+        "false"
+    });
+    text.push('\n');
+    text.push_str("# Profile folders, or browser data folders that contain profiles.\n");
+    text.push_str("browser_profile_paths = ");
+    text.push_str(&browser_profiles_section);
+    text.push_str("\n\n");
+    text.push_str("# Index the OS recent-documents list (Windows Recent / XDG recently-used).\n");
+    text.push_str("recent_documents_enabled = ");
+    text.push_str(if cfg.recent_documents_enabled {
+        "true"
+    } else {
+        "false"
+    });
+    text.push_str("\n\n");
+    text.push_str("# Index executables on PATH as commands (type arguments after the name).\n");
+    text.push_str("path_executables_enabled = ");
+    text.push_str(if cfg.path_executables_enabled {
+        "true"
+    } else {
+        "false"
+    });
+    text.push('\n');
+    text.push_str("# Extra folders searched for executables after PATH.\n");
+    text.push_str("path_executables_extra_dirs = ");
+    text.push_str(&path_executables_dirs_section);
+    text.push_str("\n\n");
+    text.push_str("# Index text, Markdown and source file contents for `content:` queries.\n");
+    text.push_str("content_indexing_enabled = ");
+    text.push_str(if cfg.content_indexing_enabled {
+        "true"
+    } else {
+        "false"
+    });
+    text.push('\n');
+    text.push_str("# Files larger than this are skipped (1024..67108864).\n");
+    text.push_str("content_index_max_file_bytes = ");
+    text.push_str(&cfg.content_index_max_file_bytes.to_string());
+    text.push_str("\n\n");
+    text.push_str("# Serve core requests to local clients over a socket or named pipe.\n");
+    text.push_str("ipc_enabled = ");
+    text.push_str(if cfg.ipc_enabled { "true" } else { "false" });
+    text.push_str("\n\n");
+
+    text.push_str(
+        "# Search mode default: all | apps | files | actions | clipboard | bookmarks | snippets\n",
+    );
     text.push_str("search_mode_default = ");
     text.push_str(&json_string(match cfg.search_mode_default {
         SearchMode::All => "all",
@@ -715,6 +871,8 @@ fn write_user_template_toml(cfg: &Config, path: &Path) -> Result<(), ConfigError
         SearchMode::Files => "files",
         SearchMode::Actions => "actions",
         SearchMode::Clipboard => "clipboard",
+        SearchMode::Bookmarks => "bookmarks",
+        SearchMode::Snippets => "snippets",
     }));
     text.push('\n');
     text.push_str(
@@ -810,6 +968,13 @@ fn write_user_template_toml(cfg: &Config, path: &Path) -> Result<(), ConfigError
     text.push('\n');
     text.push_str("plugin_paths = ");
     text.push_str(&plugin_paths_section);
+    text.push('\n');
+    text.push_str("# Capabilities granted to plugins, as \"<plugin_id>:<capability>\" (or \"<plugin_id>:*\").\n");
+    text.push_str(
+        "# Capabilities: run_commands | open_urls | read_clipboard | read_env | filesystem\n",
+    );
+    text.push_str("plugin_grants = ");
+    text.push_str(&plugin_grants_section);
     text.push_str("\n\n");
 
     text.push_str("# Runtime performance targets\n");
@@ -914,6 +1079,26 @@ pub fn validate(cfg: &Config) -> Result<(), String> {
         return Err("discovery_exclude_roots contains an empty path".into());
     }
 
+    if cfg
+        .browser_profile_paths
+        .iter()
+        .any(|path| path.as_os_str().is_empty())
+    {
+        return Err("browser_profile_paths contains an empty path".into());
+    }
+
+    if cfg
+        .path_executables_extra_dirs
+        .iter()
+        .any(|path| path.as_os_str().is_empty())
+    {
+        return Err("path_executables_extra_dirs contains an empty path".into());
+    }
+
+    if cfg.content_index_max_file_bytes < 1_024 || cfg.content_index_max_file_bytes > 67_108_864 {
+        return Err("content_index_max_file_bytes out of range".into());
+    }
+
     if cfg
         .plugin_paths
         .iter()
@@ -930,6 +1115,11 @@ pub fn validate(cfg: &Config) -> Result<(), String> {
         return Err("clipboard_exclude_sensitive_patterns contains an empty pattern".into());
     }
 
+    for grant in &cfg.plugin_grants {
+        crate::plugin_sdk::parse_plugin_grant(grant)
+            .map_err(|error| format!("plugin_grants entry '{grant}' is invalid: {error}"))?;
+    }
+
     crate::settings::validate_hotkey(&cfg.hotkey)
         .map_err(|error| format!("hotkey is invalid: {error}"))?;
 
@@ -1040,6 +1230,42 @@ fn default_discovery_exclude_roots() -> Vec<PathBuf> {
     Vec::new()
 }
 
+fn default_browser_profile_paths() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(profile_root) = windows_user_profile_root() {
+            let local = profile_root.join("AppData").join("Local");
+            let roaming = profile_root.join("AppData").join("Roaming");
+            return vec![
+                local.join("Google").join("Chrome").join("User Data"),
+                local.join("Microsoft").join("Edge").join("User Data"),
+                local
+                    .join("BraveSoftware")
+                    .join("Brave-Browser")
+                    .join("User Data"),
+                roaming.join("Mozilla").join("Firefox").join("Profiles"),
+            ];
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Ok(home) = std::env::var("HOME") {
+            let home = PathBuf::from(home);
+            let config_dir = home.join(".config");
+            return vec![
+                config_dir.join("google-chrome"),
+                config_dir.join("chromium"),
+                config_dir.join("microsoft-edge"),
+                config_dir.join("BraveSoftware").join("Brave-Browser"),
+                home.join(".mozilla").join("firefox"),
+            ];
+        }
+    }
+
+    Vec::new()
+}
+
 #[cfg(target_os = "windows")]
 fn windows_user_profile_root() -> Option<PathBuf> {
     if let Ok(user_profile) = std::env::var("USERPROFILE") {
@@ -1166,6 +1392,54 @@ fn apply_migrations(cfg: &mut Config, raw: &str) -> bool {
         }
     }
 
+    if source_version < 12 {
+        if !raw_has_key(raw, "browser_bookmarks_enabled") {
+            cfg.browser_bookmarks_enabled = Config::default().browser_bookmarks_enabled;
+            changed = true;
+        }
+        if !raw_has_key(raw, "browser_profile_paths") {
+            cfg.browser_profile_paths = Config::default().browser_profile_paths;
+            changed = true;
+        }
+    }
+
+    if source_version < 13 && !raw_has_key(raw, "recent_documents_enabled") {
+        cfg.recent_documents_enabled = Config::default().recent_documents_enabled;
+        changed = true;
+    }
+
+    if source_version < 14 {
+        if !raw_has_key(raw, "path_executables_enabled") {
+            cfg.path_executables_enabled = Config::default().path_executables_enabled;
+            changed = true;
+        }
+        if !raw_has_key(raw, "path_executables_extra_dirs") {
+            cfg.path_executables_extra_dirs = Config::default().path_executables_extra_dirs;
+            changed = true;
+        }
+    }
+
+    if source_version < 15 {
+        if !raw_has_key(raw, "content_indexing_enabled") {
+            cfg.content_indexing_enabled = Config::default().content_indexing_enabled;
+            changed = true;
+        }
+        if !raw_has_key(raw, "content_index_max_file_bytes") {
+            cfg.content_index_max_file_bytes = Config::default().content_index_max_file_bytes;
+            changed = true;
+        }
+    }
+
+    if source_version < 16 && !raw_has_key(raw, "ipc_enabled") {
+        cfg.ipc_enabled = Config::default().ipc_enabled;
+        changed = true;
+    }
+
+    if source_version < 17 && !raw_has_key(raw, "plugin_grants") {
+        cfg.plugin_grants = Config::default().plugin_grants;
+        changed = true;
+    }
+
     if TEMPLATE_REQUIRED_KEYS
         .iter()
         .any(|key| !raw_has_key(raw, key))
@@ -1222,7 +1496,7 @@ fn raw_has_key(raw: &str, key: &str) -> bool {
     raw.contains(&bare)
 }
 
-fn parse_text(raw: &str) -> Result<Config, ConfigError> {
+pub(crate) fn parse_text(raw: &str) -> Result<Config, ConfigError> {
     match toml::from_str::<Config>(raw) {
         Ok(cfg) => Ok(cfg),
         Err(toml_err) => match serde_json::from_str::<Config>(raw) {
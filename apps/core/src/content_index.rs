@@ -0,0 +1,229 @@
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::model::normalize_for_search;
+
+pub const DEFAULT_CONTENT_MAX_FILE_BYTES: u64 = 1_048_576;
+const SNIPPET_MAX_CHARS: usize = 160;
+
+const PLAIN_TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "text", "log", "csv", "tsv", "ini", "cfg", "conf", "env",
+];
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdown", "mkd"];
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "go", "java", "kt", "c", "h", "cc", "cpp", "hpp", "cs",
+    "rb", "php", "swift", "lua", "sh", "bash", "ps1", "bat", "cmd", "sql", "toml", "yaml", "yml",
+    "json", "json5", "xml", "html", "css", "scss",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    PlainText,
+    Markdown,
+    Source,
+}
+
+pub fn content_kind_for_path(path: &Path) -> Option<ContentKind> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    if PLAIN_TEXT_EXTENSIONS.contains(&extension.as_str()) {
+        Some(ContentKind::PlainText)
+    } else if MARKDOWN_EXTENSIONS.contains(&extension.as_str()) {
+        Some(ContentKind::Markdown)
+    } else if SOURCE_EXTENSIONS.contains(&extension.as_str()) {
+        Some(ContentKind::Source)
+    } else {
+        None
+    }
+}
+
+// Size + mtime is enough to notice edits without re-reading every file on each pass.
+pub fn content_stamp(path: &Path) -> Option<String> {
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() {
+        return None;
+    }
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|value| value.duration_since(UNIX_EPOCH).ok())
+        .map(|value| value.as_secs())
+        .unwrap_or(0);
+    Some(format!("{}:{modified}", meta.len()))
+}
+
+pub fn extract_content(path: &Path, max_file_bytes: u64) -> Option<String> {
+    let kind = content_kind_for_path(path)?;
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() || meta.len() == 0 || meta.len() > max_file_bytes {
+        return None;
+    }
+
+    let bytes = std::fs::read(path).ok()?;
+    let text = decode_text(&bytes)?;
+    let body = match kind {
+        ContentKind::Markdown => strip_markdown(&text),
+        ContentKind::PlainText | ContentKind::Source => text,
+    };
+    if body.trim().is_empty() {
+        return None;
+    }
+    Some(body)
+}
+
+pub fn decode_text(bytes: &[u8]) -> Option<String> {
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return Some(String::from_utf8_lossy(rest).into_owned());
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return Some(decode_utf16(rest, u16::from_le_bytes));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return Some(decode_utf16(rest, u16::from_be_bytes));
+    }
+
+    // Without a BOM, NUL bytes mean binary data (or BOM-less UTF-16, which is rare enough to skip).
+    if bytes.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Some(text.to_string()),
+        Err(_) => Some(decode_windows_1252(bytes)),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], read_unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| read_unit([pair[0], pair[1]]))
+        .collect::<Vec<_>>();
+    String::from_utf16_lossy(&units)
+}
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž',
+        '\u{8F}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}',
+        'ž', 'Ÿ',
+    ];
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+            _ => byte as char,
+        })
+        .collect()
+}
+
+fn strip_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            out.push('\n');
+            continue;
+        }
+        let without_marker = trimmed
+            .trim_start_matches('#')
+            .trim_start_matches('>')
+            .trim_start();
+        let without_marker = without_marker
+            .strip_prefix("- ")
+            .or_else(|| without_marker.strip_prefix("* "))
+            .or_else(|| without_marker.strip_prefix("+ "))
+            .unwrap_or(without_marker);
+        out.extend(
+            without_marker
+                .chars()
+                .filter(|ch| !matches!(ch, '*' | '_' | '`' | '[' | ']')),
+        );
+        out.push('\n');
+    }
+    out
+}
+
+pub fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split(|ch: char| !ch.is_alphanumeric())
+        .map(normalize_for_search)
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+pub fn fts_match_expression(terms: &[String]) -> Option<String> {
+    if terms.is_empty() {
+        return None;
+    }
+    // Quote every term so user input never reaches FTS5 operator syntax; prefix-match the last one.
+    let mut parts = terms
+        .iter()
+        .map(|term| format!("\"{term}\""))
+        .collect::<Vec<_>>();
+    if let Some(last) = parts.last_mut() {
+        last.push('*');
+    }
+    Some(parts.join(" "))
+}
+
+pub fn matching_line(body: &str, terms: &[String]) -> Option<String> {
+    if terms.is_empty() {
+        return None;
+    }
+    let mut best: Option<(usize, &str)> = None;
+    for line in body.lines() {
+        let normalized = normalize_for_search(line);
+        if normalized.is_empty() {
+            continue;
+        }
+        let hits = terms
+            .iter()
+            .filter(|term| normalized.contains(term.as_str()))
+            .count();
+        if hits == terms.len() {
+            best = Some((hits, line));
+            break;
+        }
+        if hits > 0 && best.is_none_or(|(best_hits, _)| hits > best_hits) {
+            best = Some((hits, line));
+        }
+    }
+    // FTS folds diacritics and prefixes differently, so fall back to the first non-blank line.
+    best.map(|(_, line)| line)
+        .or_else(|| body.lines().find(|line| !line.trim().is_empty()))
+        .map(|line| truncate_snippet(line.trim()))
+}
+
+fn truncate_snippet(line: &str) -> String {
+    if line.chars().count() <= SNIPPET_MAX_CHARS {
+        return line.to_string();
+    }
+    let mut out = line.chars().take(SNIPPET_MAX_CHARS - 1).collect::<String>();
+    out.push('…');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_text, fts_match_expression, matching_line, query_terms};
+
+    #[test]
+    fn decodes_utf16_and_legacy_text() {
+        let utf16 = [0xFF, 0xFE, b'h', 0, b'i', 0];
+        assert_eq!(decode_text(&utf16).as_deref(), Some("hi"));
+        assert_eq!(decode_text(b"caf\xE9").as_deref(), Some("café"));
+        assert_eq!(decode_text(b"\x7FELF\0\x01"), None);
+    }
+
+    #[test]
+    fn picks_line_matching_all_terms() {
+        let body = "intro\nthe deploy script\nrollback the deploy when health fails\n";
+        let terms = query_terms("deploy rollback");
+        assert_eq!(
+            matching_line(body, &terms).as_deref(),
+            Some("rollback the deploy when health fails")
+        );
+        assert_eq!(
+            fts_match_expression(&terms).as_deref(),
+            Some("\"deploy\" \"rollback\"*")
+        );
+    }
+}
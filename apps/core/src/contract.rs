@@ -1,11 +1,16 @@
 use serde::{Deserialize, Serialize};
 
-use crate::model::SearchItem;
+use crate::config::SearchMode;
+use crate::core_service::{IndexRefreshReport, ProviderHealth};
+use crate::index_store::IndexMaintenanceReport;
+use crate::model::{ItemAction, SearchItem};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SearchRequest {
     pub query: String,
     pub limit: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<SearchMode>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -14,6 +19,20 @@ pub struct SearchResultDto {
     pub kind: String,
     pub title: String,
     pub path: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub snippet: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub icon: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secondary_actions: Vec<ResultActionDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResultActionDto {
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub icon: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -32,11 +51,107 @@ pub struct LaunchResponse {
     pub launched: bool,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SubscribeRequest {
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SubscribeResponse {
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ItemDto {
+    pub id: String,
+    pub kind: String,
+    pub title: String,
+    pub path: String,
+    #[serde(default)]
+    pub subtitle: String,
+    #[serde(default)]
+    pub use_count: u32,
+    #[serde(default)]
+    pub last_accessed_epoch_secs: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RebuildIndexRequest {
+    #[serde(default)]
+    pub incremental: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ItemIdRequest {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ItemResponse {
+    pub item: ItemDto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UpsertItemRequest {
+    pub item: ItemDto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeleteItemResponse {
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordSelectionRequest {
+    pub query: String,
+    pub mode: SearchMode,
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordSelectionResponse {
+    pub recorded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProviderInfo {
+    pub name: String,
+    pub registered: bool,
+    #[serde(default)]
+    pub health: Option<ProviderHealth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ListProvidersResponse {
+    pub providers: Vec<ProviderInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StatusResponse {
+    pub version: String,
+    pub schema_version: i64,
+    pub indexed_items: usize,
+    pub index_db_path: String,
+    pub read_only: bool,
+    pub degraded_providers: Vec<String>,
+    #[serde(default)]
+    pub last_maintenance: Option<IndexMaintenanceReport>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "kind", content = "payload")]
 pub enum CoreRequest {
     Search(SearchRequest),
     Launch(LaunchRequest),
+    Subscribe(SubscribeRequest),
+    RebuildIndex(RebuildIndexRequest),
+    GetItem(ItemIdRequest),
+    UpsertItem(UpsertItemRequest),
+    DeleteItem(ItemIdRequest),
+    RecordSelection(RecordSelectionRequest),
+    ListProviders,
+    GetStatus,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -44,6 +159,31 @@ pub enum CoreRequest {
 pub enum CoreResponse {
     Search(SearchResponse),
     Launch(LaunchResponse),
+    Subscribe(SubscribeResponse),
+    RebuildIndex(IndexRefreshReport),
+    GetItem(ItemResponse),
+    UpsertItem(ItemResponse),
+    DeleteItem(DeleteItemResponse),
+    RecordSelection(RecordSelectionResponse),
+    ListProviders(ListProvidersResponse),
+    GetStatus(StatusResponse),
+}
+
+impl CoreRequest {
+    // Keep in sync with the variants above (contract_test checks it); drives JSON-RPC
+    // method discovery.
+    pub const KINDS: &'static [&'static str] = &[
+        "Search",
+        "Launch",
+        "Subscribe",
+        "RebuildIndex",
+        "GetItem",
+        "UpsertItem",
+        "DeleteItem",
+        "RecordSelection",
+        "ListProviders",
+        "GetStatus",
+    ];
 }
 
 impl From<SearchItem> for SearchResultDto {
@@ -53,6 +193,51 @@ impl From<SearchItem> for SearchResultDto {
             kind: value.kind,
             title: value.title,
             path: value.path,
+            snippet: value.snippet,
+            icon: value.icon,
+            secondary_actions: value
+                .secondary_actions
+                .into_iter()
+                .map(ResultActionDto::from)
+                .collect(),
         }
     }
 }
+
+impl From<ItemAction> for ResultActionDto {
+    fn from(value: ItemAction) -> Self {
+        Self {
+            id: value.id,
+            title: value.title,
+            icon: value.icon,
+        }
+    }
+}
+
+impl From<SearchItem> for ItemDto {
+    fn from(value: SearchItem) -> Self {
+        Self {
+            id: value.id,
+            kind: value.kind,
+            title: value.title,
+            path: value.path,
+            subtitle: value.subtitle,
+            use_count: value.use_count,
+            last_accessed_epoch_secs: value.last_accessed_epoch_secs,
+        }
+    }
+}
+
+impl From<ItemDto> for SearchItem {
+    fn from(value: ItemDto) -> Self {
+        SearchItem::from_owned_with_subtitle(
+            value.id,
+            value.kind,
+            value.title,
+            value.path,
+            value.subtitle,
+            value.use_count,
+            value.last_accessed_epoch_secs,
+        )
+    }
+}
@@ -1,22 +1,46 @@
 use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
 
-use crate::action_executor::{launch_path, LaunchError};
+use crate::action_executor::{command_args_from_query, launch_command, launch_path, LaunchError};
 use crate::config::{validate, Config, SearchMode};
-use crate::contract::{CoreRequest, CoreResponse, LaunchResponse, SearchResponse};
+use crate::content_index;
+use crate::contract::{
+    CoreRequest, CoreResponse, DeleteItemResponse, ItemResponse, LaunchResponse,
+    ListProvidersResponse, ProviderInfo, RecordSelectionResponse, SearchResponse, StatusResponse,
+    SubscribeResponse,
+};
 use crate::discovery::{
-    DiscoveryProvider, FileSystemDiscoveryProvider, ProviderError, StartMenuAppDiscoveryProvider,
+    BrowserBookmarksDiscoveryProvider, DiscoveryProvider, FileSystemDiscoveryProvider,
+    PathExecutablesDiscoveryProvider, ProviderError, RecentDocumentsDiscoveryProvider,
+    StartMenuAppDiscoveryProvider, RECENT_DOCUMENT_ID_PREFIX,
 };
-use crate::index_store::{self, StoreError};
+use crate::events::{resolve_event_names, CoreEvent, EventBus};
+use crate::index_store::{self, IndexMaintenanceReport, StoreError};
 use crate::model::SearchItem;
+use crate::plugin_actions::{capture_plugin_command, run_plugin_action, PluginActionOutcome};
+use crate::plugin_sdk::{
+    is_plugin_item_id, plugin_item_id_prefix, CommandOutput, PluginAction, PluginActionKind,
+    PluginRegistry, PLUGIN_PROVIDER_PREFIX,
+};
 use crate::search::SearchFilter;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::mpsc::Receiver;
 use std::sync::{Mutex, RwLock};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 const STALE_PRUNE_INTERVAL: Duration = Duration::from_secs(15);
 const PROVIDER_RECONCILE_INTERVAL_SECS: i64 = 30 * 60;
 const STALE_PRUNE_BATCH_SIZE: usize = 512;
+const PROVIDER_HEALTH_META_PREFIX: &str = "provider_health:";
+const PROVIDER_BACKOFF_BASE_SECS: i64 = 60;
+const PROVIDER_BACKOFF_MAX_SECS: i64 = 6 * 60 * 60;
+const PROVIDER_DURATION_SAMPLE_WINDOW: u32 = 20;
+const CONTENT_INDEX_CONFIG_META_KEY: &str = "content_index_config";
+const CONTENT_SEARCH_SEED_LIMIT: usize = 200;
+const CONTENT_RANK_MAX_BOOST: i64 = 1_200;
+const INDEX_OPTIMIZE_META_KEY: &str = "index_optimized_epoch_secs";
+const INDEX_OPTIMIZE_INTERVAL_SECS: i64 = 7 * 24 * 60 * 60;
 
 #[derive(Debug)]
 pub enum ServiceError {
@@ -26,6 +50,7 @@ pub enum ServiceError {
     Launch(LaunchError),
     InvalidRequest(String),
     ItemNotFound(String),
+    ReadOnly(String),
 }
 
 impl std::fmt::Display for ServiceError {
@@ -37,6 +62,7 @@ impl std::fmt::Display for ServiceError {
             Self::Launch(error) => write!(f, "launch error: {error}"),
             Self::InvalidRequest(error) => write!(f, "invalid request: {error}"),
             Self::ItemNotFound(id) => write!(f, "item not found: {id}"),
+            Self::ReadOnly(operation) => write!(f, "index is read-only: {operation}"),
         }
     }
 }
@@ -74,9 +100,14 @@ pub struct CoreService {
     cached_app_items: RwLock<Vec<SearchItem>>,
     last_stale_prune: Mutex<Option<Instant>>,
     stale_prune_cursor: Mutex<usize>,
+    observed_data_version: Mutex<i64>,
+    read_only: bool,
+    events: EventBus,
+    // Presents plugin items in `Search` replies and runs plugin action ids on `Launch`.
+    plugins: RwLock<PluginRegistry>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProviderRefreshReport {
     pub provider: String,
     pub discovered: usize,
@@ -84,9 +115,58 @@ pub struct ProviderRefreshReport {
     pub removed: usize,
     pub skipped: bool,
     pub elapsed_ms: u128,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderHealth {
+    pub provider: String,
+    pub last_success_epoch_secs: i64,
+    pub last_error: String,
+    pub last_error_epoch_secs: i64,
+    pub consecutive_failures: u32,
+    pub avg_duration_ms: u64,
+    pub duration_samples: u32,
+    pub backoff_until_epoch_secs: i64,
+}
+
+impl ProviderHealth {
+    pub fn is_degraded(&self) -> bool {
+        self.consecutive_failures > 0
+    }
+
+    fn record_duration(&mut self, elapsed_ms: u128) {
+        // Running mean over a bounded window so old scans stop dominating.
+        let samples = self
+            .duration_samples
+            .min(PROVIDER_DURATION_SAMPLE_WINDOW - 1) as u128;
+        let total = self.avg_duration_ms as u128 * samples + elapsed_ms;
+        self.avg_duration_ms = (total / (samples + 1)) as u64;
+        self.duration_samples = samples as u32 + 1;
+    }
+
+    fn record_success(&mut self, now_epoch_secs: i64, elapsed_ms: u128) {
+        self.record_duration(elapsed_ms);
+        self.last_success_epoch_secs = now_epoch_secs;
+        self.consecutive_failures = 0;
+        self.backoff_until_epoch_secs = 0;
+    }
+
+    fn record_failure(&mut self, error: &str, now_epoch_secs: i64, elapsed_ms: u128) {
+        self.record_duration(elapsed_ms);
+        self.last_error = error.to_string();
+        self.last_error_epoch_secs = now_epoch_secs;
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let exponent = self.consecutive_failures.saturating_sub(1).min(16);
+        let backoff_secs = PROVIDER_BACKOFF_BASE_SECS
+            .saturating_mul(1_i64 << exponent)
+            .min(PROVIDER_BACKOFF_MAX_SECS);
+        self.backoff_until_epoch_secs = now_epoch_secs + backoff_secs;
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IndexRefreshReport {
     pub indexed_total: usize,
     pub discovered_total: usize,
@@ -98,10 +178,31 @@ pub struct IndexRefreshReport {
 impl CoreService {
     pub fn new(config: Config) -> Result<Self, ServiceError> {
         validate(&config).map_err(ServiceError::Config)?;
-        let db = index_store::open_from_config(&config)?;
+        let (db, repair) = index_store::open_file_checked(&config.index_db_path)?;
+        if let Some(report) = repair {
+            crate::logging::warn(&format!(
+                "[nex] index database failed startup check; quarantined={} problems={}",
+                report.quarantined_path.as_deref().unwrap_or("none"),
+                report.problems.join("; "),
+            ));
+        }
         Self::with_loaded_cache(config, db)
     }
 
+    // For clients that only look things up: stale pruning and usage recording are skipped
+    // so the index file is never written.
+    pub fn open_read_only(config: Config) -> Result<Self, ServiceError> {
+        validate(&config).map_err(ServiceError::Config)?;
+        let db = index_store::open_file_read_only(&config.index_db_path)?;
+        let mut service = Self::with_loaded_cache(config, db)?;
+        service.read_only = true;
+        Ok(service)
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     pub fn with_connection(config: Config, db: Connection) -> Result<Self, ServiceError> {
         validate(&config).map_err(ServiceError::Config)?;
         Self::with_loaded_cache(config, db)
@@ -110,6 +211,7 @@ impl CoreService {
     fn with_loaded_cache(config: Config, db: Connection) -> Result<Self, ServiceError> {
         let cached = index_store::list_items(&db)?;
         let cached_apps = collect_app_items(&cached);
+        let data_version = index_store::data_version(&db)?;
         Ok(Self {
             config: RwLock::new(config),
             db,
@@ -118,9 +220,43 @@ impl CoreService {
             cached_app_items: RwLock::new(cached_apps),
             last_stale_prune: Mutex::new(None),
             stale_prune_cursor: Mutex::new(0),
+            observed_data_version: Mutex::new(data_version),
+            read_only: false,
+            events: EventBus::default(),
+            plugins: RwLock::new(PluginRegistry::default()),
         })
     }
 
+    // Shares another service's subscribers, e.g. for a worker thread's own service.
+    pub fn with_event_bus(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn with_plugins(self, plugins: PluginRegistry) -> Self {
+        self.replace_plugins(plugins);
+        self
+    }
+
+    // Takes over a reloaded registry; the caller owns loading so manifests are read once.
+    pub fn replace_plugins(&self, plugins: PluginRegistry) {
+        match self.plugins.write() {
+            Ok(mut guard) => *guard = plugins,
+            Err(poisoned) => {
+                let mut guard = poisoned.into_inner();
+                *guard = plugins;
+            }
+        }
+    }
+
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    pub fn subscribe(&self) -> Receiver<CoreEvent> {
+        self.events.subscribe()
+    }
+
     pub fn with_providers(self, providers: Vec<Box<dyn DiscoveryProvider>>) -> Self {
         self.replace_providers(providers);
         self
@@ -133,13 +269,50 @@ impl CoreService {
     }
 
     pub fn reconfigure_runtime_providers(&self, cfg: &Config) -> Result<(), ServiceError> {
+        self.adopt_config(cfg, true)?;
+        self.events.publish(CoreEvent::ConfigReloaded {
+            config_path: cfg.config_path.to_string_lossy().into_owned(),
+        });
+        Ok(())
+    }
+
+    // Swaps in a reloaded config that does not change discovery; providers stay as they are.
+    pub fn apply_config(&self, cfg: &Config) -> Result<(), ServiceError> {
+        self.adopt_config(cfg, false)?;
+        self.events.publish(CoreEvent::ConfigReloaded {
+            config_path: cfg.config_path.to_string_lossy().into_owned(),
+        });
+        Ok(())
+    }
+
+    // Like `apply_config`, but silent: for a config another service on the same event bus
+    // has already applied and announced.
+    pub fn adopt_config(
+        &self,
+        cfg: &Config,
+        reconfigure_providers: bool,
+    ) -> Result<(), ServiceError> {
         validate(cfg).map_err(ServiceError::Config)?;
-        let providers = runtime_providers_from_config(cfg);
-        self.replace_runtime_config(cfg.clone());
-        self.replace_providers(providers);
+        if reconfigure_providers {
+            let providers = runtime_providers_from_config(cfg);
+            self.replace_runtime_config(cfg.clone());
+            self.replace_providers(providers);
+        } else {
+            self.replace_runtime_config(cfg.clone());
+        }
         Ok(())
     }
 
+    pub fn capture_clipboard(&self) -> Result<bool, String> {
+        let captured = crate::clipboard_history::maybe_capture_latest(&self.config_snapshot())?;
+        if captured {
+            self.events.publish(CoreEvent::ClipboardCaptured {
+                captured_epoch_secs: now_epoch_secs(),
+            });
+        }
+        Ok(captured)
+    }
+
     fn replace_providers(&self, providers: Vec<Box<dyn DiscoveryProvider>>) {
         match self.providers.write() {
             Ok(mut guard) => *guard = providers,
@@ -206,6 +379,33 @@ fn runtime_providers_from_config(config: &Config) -> Vec<Box<dyn DiscoveryProvid
             config.index_max_items_per_root as usize,
         ),
     ));
+    let browser_profile_paths = if config.browser_bookmarks_enabled {
+        config.browser_profile_paths.clone()
+    } else {
+        Vec::new()
+    };
+    providers.push(Box::new(BrowserBookmarksDiscoveryProvider::new(
+        browser_profile_paths,
+    )));
+    let recent_documents = if config.recent_documents_enabled {
+        RecentDocumentsDiscoveryProvider::default()
+    } else {
+        RecentDocumentsDiscoveryProvider::with_sources(Vec::new())
+    };
+    providers.push(Box::new(
+        recent_documents
+            .with_excluded_roots(config.discovery_exclude_roots.clone())
+            .with_visibility(config.show_files, config.show_folders),
+    ));
+    let path_executables = if config.path_executables_enabled {
+        PathExecutablesDiscoveryProvider::new(config.path_executables_extra_dirs.clone())
+    } else {
+        PathExecutablesDiscoveryProvider::with_dirs(Vec::new())
+    };
+    providers.push(Box::new(path_executables));
+    for plugin_provider in crate::plugin_sdk::item_providers_from_config(config) {
+        providers.push(Box::new(plugin_provider));
+    }
     providers
 }
 
@@ -254,6 +454,10 @@ impl CoreService {
             limit
         };
 
+        if let Some(content_query) = filter.content_query.as_deref() {
+            return self.search_content(content_query, query, effective_limit, filter);
+        }
+
         if should_use_app_cache(filter) {
             let guard = match self.cached_app_items.read() {
                 Ok(guard) => guard,
@@ -293,6 +497,50 @@ impl CoreService {
         ))
     }
 
+    fn search_content(
+        &self,
+        content_query: &str,
+        query: &str,
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchItem>, ServiceError> {
+        let terms = content_index::query_terms(content_query);
+        let Some(match_expression) = content_index::fts_match_expression(&terms) else {
+            return Ok(Vec::new());
+        };
+        let hits = index_store::search_item_content(
+            &self.db,
+            &match_expression,
+            CONTENT_SEARCH_SEED_LIMIT,
+        )?;
+
+        // Full-text relevance rides along as a boost so the shared ranking pass keeps it.
+        let hit_count = hits.len() as i64;
+        let mut candidates = Vec::with_capacity(hits.len());
+        let mut rank_boosts = HashMap::with_capacity(hits.len());
+        for (rank, (item_id, body)) in hits.into_iter().enumerate() {
+            let Some(item) = index_store::get_item(&self.db, &item_id)? else {
+                continue;
+            };
+            let Some(snippet) = content_index::matching_line(&body, &terms) else {
+                continue;
+            };
+            rank_boosts.insert(
+                item.id.clone(),
+                CONTENT_RANK_MAX_BOOST * (hit_count - rank as i64) / hit_count,
+            );
+            candidates.push(item.with_snippet(&snippet));
+        }
+
+        Ok(crate::search::search_with_filter_with_boosts(
+            &candidates,
+            query,
+            limit,
+            filter,
+            Some(&rank_boosts),
+        ))
+    }
+
     pub fn cached_items_snapshot(&self) -> Vec<SearchItem> {
         let guard = match self.cached_items.read() {
             Ok(guard) => guard,
@@ -323,9 +571,32 @@ impl CoreService {
         match target {
             LaunchTarget::Path(path) => launch_path(path).map_err(ServiceError::from),
             LaunchTarget::Id(id) => {
+                let action = match self.plugins.read() {
+                    Ok(guard) => guard.action(id),
+                    Err(poisoned) => poisoned.into_inner().action(id),
+                };
+                if let Some(action) = action {
+                    return self.launch_plugin_action(&action, query.unwrap_or(""));
+                }
                 let item = index_store::get_item(&self.db, id)?
                     .ok_or_else(|| ServiceError::ItemNotFound(id.to_string()))?;
-                match launch_path(&item.path) {
+                let launched = if item.kind.eq_ignore_ascii_case("command") {
+                    let args = query
+                        .map(|query| command_args_from_query(query, &item.title))
+                        .unwrap_or_default();
+                    launch_command(&item.path, &args)
+                } else {
+                    launch_path(&item.path)
+                };
+                if launched.is_ok() {
+                    self.events.publish(CoreEvent::ItemLaunched {
+                        id: item.id.clone(),
+                        kind: item.kind.clone(),
+                        title: item.title.clone(),
+                    });
+                }
+                match launched {
+                    Ok(()) if self.read_only => Ok(()),
                     Ok(()) => {
                         self.record_successful_launch(&item)?;
                         if let (Some(query), Some(mode)) = (query, mode) {
@@ -333,7 +604,9 @@ impl CoreService {
                         }
                         Ok(())
                     }
-                    Err(error) if should_prune_after_launch_error(&item, &error) => {
+                    Err(error)
+                        if !self.read_only && should_prune_after_launch_error(&item, &error) =>
+                    {
                         index_store::delete_item(&self.db, &item.id)?;
                         self.remove_cached_item_by_id(&item.id);
                         Err(ServiceError::from(error))
@@ -344,18 +617,64 @@ impl CoreService {
         }
     }
 
+    // Plugin actions never reach the index, so nothing is recorded for them. A command
+    // whose output is meant to stay on screen only makes sense in the overlay.
+    fn launch_plugin_action(&self, action: &PluginAction, query: &str) -> Result<(), ServiceError> {
+        if let PluginActionKind::Command {
+            output: CommandOutput::Result,
+            ..
+        } = action.kind
+        {
+            return Err(ServiceError::InvalidRequest(format!(
+                "plugin action {} shows its output in the overlay and cannot be launched",
+                action.result_id
+            )));
+        }
+        let cfg = self.config_snapshot();
+        let outcome = {
+            let plugins = match self.plugins.read() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            run_plugin_action(&cfg, &plugins, action, query, &[])
+        };
+        let launched = outcome.and_then(|outcome| match outcome {
+            PluginActionOutcome::Done => Ok(()),
+            PluginActionOutcome::Capture { command, args, .. } => {
+                let text = capture_plugin_command(&command, &args)?;
+                crate::clipboard_history::copy_text_to_clipboard(&cfg, &text)
+                    .map_err(|error| format!("copy plugin output failed: {error}"))
+            }
+        });
+        launched.map_err(|message| {
+            ServiceError::Launch(LaunchError::LaunchFailed {
+                message,
+                code: None,
+            })
+        })?;
+        self.events.publish(CoreEvent::ItemLaunched {
+            id: action.result_id.clone(),
+            kind: "action".to_string(),
+            title: action.title.clone(),
+        });
+        Ok(())
+    }
+
     pub fn record_query_selection_hint(
         &self,
         query: &str,
         mode: SearchMode,
         item_id: &str,
-    ) -> Result<(), ServiceError> {
+    ) -> Result<bool, ServiceError> {
         let query_norm = crate::model::normalize_for_search(query);
         if query_norm.is_empty() {
-            return Ok(());
+            return Ok(false);
         }
-        if matches!(mode, SearchMode::Actions | SearchMode::Clipboard) {
-            return Ok(());
+        if matches!(
+            mode,
+            SearchMode::Actions | SearchMode::Clipboard | SearchMode::Snippets
+        ) {
+            return Ok(false);
         }
         index_store::record_query_selection(
             &self.db,
@@ -364,7 +683,7 @@ impl CoreService {
             item_id,
             now_epoch_secs(),
         )?;
-        Ok(())
+        Ok(true)
     }
 
     pub fn rebuild_index(&self) -> Result<usize, ServiceError> {
@@ -385,6 +704,19 @@ impl CoreService {
     fn rebuild_index_internal(
         &self,
         incremental_mode: bool,
+    ) -> Result<IndexRefreshReport, ServiceError> {
+        self.events.publish(CoreEvent::IndexRefreshStarted {
+            incremental: incremental_mode,
+        });
+        let report = self.refresh_index_from_providers(incremental_mode)?;
+        self.events
+            .publish(CoreEvent::IndexRefreshFinished(report.clone()));
+        Ok(report)
+    }
+
+    fn refresh_index_from_providers(
+        &self,
+        incremental_mode: bool,
     ) -> Result<IndexRefreshReport, ServiceError> {
         let providers_guard = match self.providers.read() {
             Ok(guard) => guard,
@@ -412,10 +744,25 @@ impl CoreService {
         let mut removed_total = 0_usize;
         let mut provider_reports = Vec::with_capacity(providers_guard.len());
         let now_epoch_secs = now_epoch_secs();
+        let mut any_provider_discovered = false;
 
         for provider in providers_guard.iter() {
             let started = Instant::now();
             let provider_name = provider.provider_name().to_string();
+            let mut health = load_provider_health(&self.db, &provider_name)?;
+            if incremental_mode && health.backoff_until_epoch_secs > now_epoch_secs {
+                provider_reports.push(ProviderRefreshReport {
+                    provider: provider_name,
+                    discovered: 0,
+                    upserted: 0,
+                    removed: 0,
+                    skipped: true,
+                    elapsed_ms: started.elapsed().as_millis(),
+                    error: None,
+                });
+                continue;
+            }
+
             let provider_stamp = if incremental_mode {
                 provider.change_stamp()
             } else {
@@ -436,18 +783,68 @@ impl CoreService {
                     removed: 0,
                     skipped: true,
                     elapsed_ms: started.elapsed().as_millis(),
+                    error: None,
                 });
                 continue;
             }
 
-            let discovered = provider.discover()?;
+            // A failing provider keeps its previous entries; the rest of the refresh proceeds.
+            let discovered = match provider.discover() {
+                Ok(discovered) => discovered,
+                Err(error) => {
+                    let elapsed_ms = started.elapsed().as_millis();
+                    health.record_failure(&error.to_string(), now_epoch_secs, elapsed_ms);
+                    persist_provider_health(&self.db, &health)?;
+                    crate::logging::warn(&format!(
+                        "[nex] provider {provider_name} discovery failed failures={} backoff_until={}: {error}",
+                        health.consecutive_failures, health.backoff_until_epoch_secs
+                    ));
+                    self.events.publish(CoreEvent::ProviderFailed {
+                        provider: provider_name.clone(),
+                        error: error.to_string(),
+                    });
+                    provider_reports.push(ProviderRefreshReport {
+                        provider: provider_name,
+                        discovered: 0,
+                        upserted: 0,
+                        removed: 0,
+                        skipped: false,
+                        elapsed_ms,
+                        error: Some(error.to_string()),
+                    });
+                    continue;
+                }
+            };
+            any_provider_discovered = true;
             let discovered_count = discovered.len();
             discovered_total += discovered_count;
 
-            let mut upserted = 0_usize;
             let mut discovered_ids = HashSet::with_capacity(discovered_count);
+            // One transaction per provider: an interrupted refresh keeps the provider's
+            // previous snapshot instead of a half-applied one.
+            let mut writer = index_store::IndexBatchWriter::begin(&self.db)?;
 
             for mut item in discovered {
+                // A recent document another provider already indexes only lends that item
+                // its recency, so the same file is not listed twice.
+                if let Some(indexed) = indexed_file_id(&item)
+                    .and_then(|indexed_id| existing_by_id.get_mut(&indexed_id))
+                {
+                    if item.last_accessed_epoch_secs > indexed.last_accessed_epoch_secs {
+                        indexed.last_accessed_epoch_secs = item.last_accessed_epoch_secs;
+                        writer.upsert_item(indexed)?;
+                    }
+                    continue;
+                }
+                if let Some(recent) = superseded_recent_id(&item)
+                    .and_then(|recent_id| existing_by_id.remove(&recent_id))
+                {
+                    item.use_count = item.use_count.max(recent.use_count);
+                    item.last_accessed_epoch_secs = item
+                        .last_accessed_epoch_secs
+                        .max(recent.last_accessed_epoch_secs);
+                    writer.delete_item(&recent.id)?;
+                }
                 if let Some(previous) = existing_by_id.get(&item.id) {
                     // Discovery providers do not carry usage metrics; preserve learned
                     // launch signals across incremental/full refreshes.
@@ -465,48 +862,90 @@ impl CoreService {
                     .map(|previous| previous != &item)
                     .unwrap_or(true);
                 if changed {
-                    index_store::upsert_item(&self.db, &item)?;
-                    upserted += 1;
-                    upserted_total += 1;
+                    writer.upsert_item(&item)?;
                 }
                 existing_by_id.insert(item.id.clone(), item);
             }
 
             // Kind-based ownership is safe for current runtime provider composition:
-            // start-menu apps own kind=app, filesystem owns kind=file/folder.
+            // start-menu apps own kind=app, filesystem owns kind=file/folder,
+            // browser bookmarks own kind=bookmark. Recent documents share file/folder
+            // kinds, so they are owned by id prefix instead.
             let removable_ids: Vec<String> = existing_by_id
                 .values()
-                .filter(|item| provider_manages_kind(provider.provider_name(), &item.kind))
+                .filter(|item| provider_manages_item(provider.provider_name(), item))
                 .filter(|item| !discovered_ids.contains(&item.id))
                 .map(|item| item.id.clone())
                 .collect();
 
             for id in &removable_ids {
-                index_store::delete_item(&self.db, id)?;
+                writer.delete_item(id)?;
                 existing_by_id.remove(id);
             }
 
-            removed_total += removable_ids.len();
+            health.record_success(now_epoch_secs, started.elapsed().as_millis());
+            persist_provider_health(writer.connection(), &health)?;
+            if incremental_mode {
+                persist_provider_discovery_state(
+                    writer.connection(),
+                    &provider_name,
+                    provider_stamp.as_deref(),
+                    now_epoch_secs,
+                )?;
+            }
+            let upserted = writer.upserted();
+            let removed = writer.deleted();
+            writer.commit()?;
+
+            upserted_total += upserted;
+            removed_total += removed;
             provider_reports.push(ProviderRefreshReport {
                 provider: provider_name.clone(),
                 discovered: discovered_count,
                 upserted,
-                removed: removable_ids.len(),
+                removed,
                 skipped: false,
                 elapsed_ms: started.elapsed().as_millis(),
+                error: None,
             });
+        }
 
-            if incremental_mode {
-                persist_provider_discovery_state(
-                    &self.db,
-                    &provider_name,
-                    provider_stamp.as_deref(),
-                    now_epoch_secs,
-                )?;
+        // Items of removed or disabled plugins have no provider left to prune them. The
+        // plugin folders are checked again first, so a plugin whose manifest is briefly
+        // unreadable is not taken for a removed one.
+        let mut orphaned_plugin_ids: Vec<String> = existing_by_id
+            .values()
+            .filter(|item| is_plugin_item_id(&item.id))
+            .filter(|item| {
+                !providers_guard
+                    .iter()
+                    .any(|provider| provider_manages_item(provider.provider_name(), item))
+            })
+            .map(|item| item.id.clone())
+            .collect();
+        if !orphaned_plugin_ids.is_empty() {
+            match crate::plugin_sdk::retained_plugin_ids(&self.config_snapshot()) {
+                Some(retained) => orphaned_plugin_ids.retain(|id| {
+                    id.strip_prefix(PLUGIN_PROVIDER_PREFIX)
+                        .and_then(|rest| rest.split(':').next())
+                        .is_none_or(|plugin_id| !retained.contains(plugin_id))
+                }),
+                None => orphaned_plugin_ids.clear(),
+            }
+        }
+        if !orphaned_plugin_ids.is_empty() {
+            let mut writer = index_store::IndexBatchWriter::begin(&self.db)?;
+            for id in &orphaned_plugin_ids {
+                writer.delete_item(id)?;
+                existing_by_id.remove(id);
             }
+            removed_total += writer.deleted();
+            writer.commit()?;
         }
 
+        self.refresh_content_index(&existing_by_id, any_provider_discovered)?;
         self.refresh_cache_from_store()?;
+        self.optimize_index_if_due(now_epoch_secs);
         let indexed_total = self.cached_len();
         Ok(IndexRefreshReport {
             indexed_total,
@@ -517,6 +956,98 @@ impl CoreService {
         })
     }
 
+    // Runs a full integrity check on the live connection. A damaged file cannot be swapped
+    // out while it is open, so repair is deferred to the next startup.
+    pub fn check_index(&self) -> Result<IndexMaintenanceReport, ServiceError> {
+        let mut report = IndexMaintenanceReport {
+            operation: "check".to_string(),
+            epoch_secs: now_epoch_secs(),
+            problems: index_store::integrity_check(&self.db)?,
+            ..IndexMaintenanceReport::default()
+        };
+        if report.problems.is_empty() {
+            index_store::optimize(&self.db, true)?;
+            index_store::set_meta(
+                &self.db,
+                INDEX_OPTIMIZE_META_KEY,
+                &report.epoch_secs.to_string(),
+            )?;
+            report.vacuumed = true;
+        } else {
+            index_store::schedule_repair(&self.config_snapshot().index_db_path)?;
+        }
+        index_store::record_maintenance(&self.db, &report)?;
+        Ok(report)
+    }
+
+    pub fn last_index_maintenance(&self) -> Result<Option<IndexMaintenanceReport>, ServiceError> {
+        Ok(index_store::last_maintenance(&self.db)?)
+    }
+
+    fn optimize_index_if_due(&self, now_epoch_secs: i64) {
+        let last = index_store::get_meta(&self.db, INDEX_OPTIMIZE_META_KEY)
+            .ok()
+            .flatten()
+            .and_then(|value| value.parse::<i64>().ok());
+        let outcome = match last {
+            Some(last) if now_epoch_secs - last < INDEX_OPTIMIZE_INTERVAL_SECS => return,
+            // A fresh database has nothing to compact; just start the interval.
+            None => Ok(()),
+            Some(_) => {
+                let report = IndexMaintenanceReport {
+                    operation: "optimize".to_string(),
+                    epoch_secs: now_epoch_secs,
+                    vacuumed: true,
+                    ..IndexMaintenanceReport::default()
+                };
+                index_store::optimize(&self.db, true)
+                    .and_then(|()| index_store::record_maintenance(&self.db, &report))
+            }
+        };
+        let outcome = outcome.and_then(|()| {
+            index_store::set_meta(
+                &self.db,
+                INDEX_OPTIMIZE_META_KEY,
+                &now_epoch_secs.to_string(),
+            )
+        });
+        if let Err(error) = outcome {
+            crate::logging::warn(&format!("[nex] index optimize failed: {error}"));
+        }
+    }
+
+    // For services sharing the database with another process or connection (IPC server,
+    // background workers): reload the cache only when someone else committed.
+    pub fn refresh_cache_if_changed(&self) -> Result<bool, ServiceError> {
+        let current = index_store::data_version(&self.db)?;
+        {
+            let mut observed = match self.observed_data_version.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            if *observed == current {
+                return Ok(false);
+            }
+            *observed = current;
+        }
+        self.refresh_cache_from_store()?;
+        Ok(true)
+    }
+
+    pub fn provider_health(&self) -> Result<Vec<ProviderHealth>, ServiceError> {
+        provider_health_snapshot(&self.db)
+    }
+
+    pub fn degraded_providers(&self) -> Result<Vec<String>, ServiceError> {
+        let configured = self.configured_provider_names();
+        Ok(self
+            .provider_health()?
+            .into_iter()
+            .filter(|health| health.is_degraded() && configured.contains(&health.provider))
+            .map(|health| health.provider)
+            .collect())
+    }
+
     pub fn rebuild_index_incremental(&self) -> Result<usize, ServiceError> {
         let report = self.rebuild_index_incremental_with_report()?;
         Ok(report.indexed_total)
@@ -531,7 +1062,24 @@ impl CoreService {
     pub fn handle_command(&self, request: CoreRequest) -> Result<CoreResponse, ServiceError> {
         match request {
             CoreRequest::Search(search) => {
-                let results = self.search(&search.query, search.limit.unwrap_or(0))?;
+                let limit = search.limit.unwrap_or(0);
+                let mut results = match search.mode {
+                    Some(mode) => {
+                        let config_snapshot = self.config_snapshot();
+                        let filter = SearchFilter {
+                            mode,
+                            include_files: config_snapshot.show_files,
+                            include_folders: config_snapshot.show_folders,
+                            ..SearchFilter::default()
+                        };
+                        self.search_with_filter(&search.query, limit, &filter)?
+                    }
+                    None => self.search(&search.query, limit)?,
+                };
+                match self.plugins.read() {
+                    Ok(guard) => guard.present(&mut results),
+                    Err(poisoned) => poisoned.into_inner().present(&mut results),
+                }
                 Ok(CoreResponse::Search(SearchResponse {
                     results: results.into_iter().map(Into::into).collect(),
                 }))
@@ -555,7 +1103,102 @@ impl CoreService {
                     "launch requires non-empty id or path".into(),
                 ))
             }
+            // Only validates the filter; the transport attaches the event stream.
+            CoreRequest::Subscribe(subscribe) => {
+                let events =
+                    resolve_event_names(&subscribe.events).map_err(ServiceError::InvalidRequest)?;
+                Ok(CoreResponse::Subscribe(SubscribeResponse { events }))
+            }
+            CoreRequest::RebuildIndex(rebuild) => {
+                self.ensure_writable("rebuild index")?;
+                let report = self.rebuild_index_internal(rebuild.incremental)?;
+                Ok(CoreResponse::RebuildIndex(report))
+            }
+            CoreRequest::GetItem(request) => {
+                let item = index_store::get_item(&self.db, &request.id)?
+                    .ok_or_else(|| ServiceError::ItemNotFound(request.id.clone()))?;
+                Ok(CoreResponse::GetItem(ItemResponse { item: item.into() }))
+            }
+            CoreRequest::UpsertItem(request) => {
+                self.ensure_writable("upsert item")?;
+                let item = request.item;
+                if item.id.trim().is_empty()
+                    || item.kind.trim().is_empty()
+                    || item.title.trim().is_empty()
+                {
+                    return Err(ServiceError::InvalidRequest(
+                        "upsert requires non-empty id, kind and title".into(),
+                    ));
+                }
+                let item = SearchItem::from(item);
+                self.upsert_item(&item)?;
+                Ok(CoreResponse::UpsertItem(ItemResponse { item: item.into() }))
+            }
+            CoreRequest::DeleteItem(request) => {
+                self.ensure_writable("delete item")?;
+                let deleted = index_store::get_item(&self.db, &request.id)?.is_some();
+                if deleted {
+                    index_store::delete_item(&self.db, &request.id)?;
+                    self.remove_cached_item_by_id(&request.id);
+                }
+                Ok(CoreResponse::DeleteItem(DeleteItemResponse { deleted }))
+            }
+            CoreRequest::RecordSelection(request) => {
+                self.ensure_writable("record selection")?;
+                if index_store::get_item(&self.db, &request.id)?.is_none() {
+                    return Err(ServiceError::ItemNotFound(request.id));
+                }
+                let recorded =
+                    self.record_query_selection_hint(&request.query, request.mode, &request.id)?;
+                Ok(CoreResponse::RecordSelection(RecordSelectionResponse {
+                    recorded,
+                }))
+            }
+            CoreRequest::ListProviders => {
+                let mut health = self.provider_health()?;
+                let mut providers = self
+                    .runtime_providers()
+                    .into_iter()
+                    .map(|name| {
+                        let position = health.iter().position(|entry| entry.provider == name);
+                        ProviderInfo {
+                            health: position.map(|index| health.remove(index)),
+                            name,
+                            registered: true,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                // Health rows left over from providers that are no longer configured.
+                providers.extend(health.into_iter().map(|entry| ProviderInfo {
+                    name: entry.provider.clone(),
+                    registered: false,
+                    health: Some(entry),
+                }));
+                Ok(CoreResponse::ListProviders(ListProvidersResponse {
+                    providers,
+                }))
+            }
+            CoreRequest::GetStatus => Ok(CoreResponse::GetStatus(StatusResponse {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                schema_version: index_store::schema_version(&self.db)?,
+                indexed_items: self.cached_len(),
+                index_db_path: self
+                    .config_snapshot()
+                    .index_db_path
+                    .to_string_lossy()
+                    .into_owned(),
+                read_only: self.read_only,
+                degraded_providers: self.degraded_providers()?,
+                last_maintenance: self.last_index_maintenance()?,
+            })),
+        }
+    }
+
+    fn ensure_writable(&self, operation: &str) -> Result<(), ServiceError> {
+        if self.read_only {
+            return Err(ServiceError::ReadOnly(operation.to_string()));
         }
+        Ok(())
     }
 }
 
@@ -580,7 +1223,10 @@ impl CoreService {
         if trimmed.is_empty() {
             return Ok(Vec::new());
         }
-        if matches!(mode, SearchMode::Actions | SearchMode::Clipboard) {
+        if matches!(
+            mode,
+            SearchMode::Actions | SearchMode::Clipboard | SearchMode::Snippets
+        ) {
             return Ok(Vec::new());
         }
 
@@ -601,6 +1247,14 @@ impl CoreService {
                  ORDER BY use_count DESC, last_accessed_epoch_secs DESC, id
                  LIMIT ?2"
             }
+            SearchMode::Bookmarks => {
+                "SELECT id, kind, title, path, subtitle, use_count, last_accessed_epoch_secs
+                 FROM item
+                 WHERE (title LIKE ?1 COLLATE NOCASE OR path LIKE ?1 COLLATE NOCASE)
+                   AND kind = 'bookmark'
+                 ORDER BY use_count DESC, last_accessed_epoch_secs DESC, id
+                 LIMIT ?2"
+            }
             SearchMode::All => {
                 "SELECT id, kind, title, path, subtitle, use_count, last_accessed_epoch_secs
                  FROM item
@@ -608,7 +1262,7 @@ impl CoreService {
                  ORDER BY use_count DESC, last_accessed_epoch_secs DESC, id
                  LIMIT ?2"
             }
-            SearchMode::Actions | SearchMode::Clipboard => unreachable!(),
+            SearchMode::Actions | SearchMode::Clipboard | SearchMode::Snippets => unreachable!(),
         };
 
         let pattern = format!("%{trimmed}%");
@@ -664,7 +1318,12 @@ impl CoreService {
         mode: SearchMode,
     ) -> Result<HashMap<String, i64>, ServiceError> {
         let query_norm = crate::model::normalize_for_search(query);
-        if query_norm.is_empty() || matches!(mode, SearchMode::Actions | SearchMode::Clipboard) {
+        if query_norm.is_empty()
+            || matches!(
+                mode,
+                SearchMode::Actions | SearchMode::Clipboard | SearchMode::Snippets
+            )
+        {
             return Ok(HashMap::new());
         }
 
@@ -683,6 +1342,65 @@ impl CoreService {
         Ok(boosts)
     }
 
+    fn refresh_content_index(
+        &self,
+        items_by_id: &HashMap<String, SearchItem>,
+        any_provider_discovered: bool,
+    ) -> Result<(), ServiceError> {
+        let cfg = self.config_snapshot();
+        let config_stamp = format!(
+            "v1;enabled={};max_bytes={}",
+            cfg.content_indexing_enabled, cfg.content_index_max_file_bytes
+        );
+        let previous_config_stamp = index_store::get_meta(&self.db, CONTENT_INDEX_CONFIG_META_KEY)?;
+        let config_changed = previous_config_stamp.as_deref() != Some(config_stamp.as_str());
+        if !any_provider_discovered && !config_changed {
+            return Ok(());
+        }
+
+        let writer = index_store::IndexBatchWriter::begin(&self.db)?;
+        let db = writer.connection();
+        if !cfg.content_indexing_enabled {
+            index_store::clear_item_content(db)?;
+            index_store::set_meta(db, CONTENT_INDEX_CONFIG_META_KEY, &config_stamp)?;
+            writer.commit()?;
+            return Ok(());
+        }
+
+        let mut known_stamps: HashMap<String, String> = index_store::list_item_content_stamps(db)?
+            .into_iter()
+            .collect();
+        for item in items_by_id.values() {
+            if !item.kind.eq_ignore_ascii_case("file") {
+                continue;
+            }
+            let path = Path::new(&item.path);
+            if content_index::content_kind_for_path(path).is_none() {
+                continue;
+            }
+            let Some(stamp) = content_index::content_stamp(path) else {
+                continue;
+            };
+            let previous_stamp = known_stamps.remove(&item.id);
+            if !config_changed && previous_stamp.as_deref() == Some(stamp.as_str()) {
+                continue;
+            }
+
+            // Unreadable, binary or oversized files keep an empty body so their stamp
+            // still spares a re-read on the next pass.
+            let body = content_index::extract_content(path, cfg.content_index_max_file_bytes)
+                .unwrap_or_default();
+            index_store::upsert_item_content(db, &item.id, &stamp, &body)?;
+        }
+
+        for stale_id in known_stamps.keys() {
+            index_store::delete_item_content(db, stale_id)?;
+        }
+        index_store::set_meta(db, CONTENT_INDEX_CONFIG_META_KEY, &config_stamp)?;
+        writer.commit()?;
+        Ok(())
+    }
+
     fn refresh_cache_from_store(&self) -> Result<(), ServiceError> {
         let config_snapshot = self.config_snapshot();
         let latest_full = index_store::list_items(&self.db)?;
@@ -791,7 +1509,7 @@ impl CoreService {
             }
         };
 
-        if !should_prune {
+        if !should_prune || self.read_only {
             return Ok(());
         }
 
@@ -806,9 +1524,11 @@ impl CoreService {
             return Ok(());
         }
 
+        let mut writer = index_store::IndexBatchWriter::begin(&self.db)?;
         for stale_id in &stale_ids {
-            index_store::delete_item(&self.db, stale_id)?;
+            writer.delete_item(stale_id)?;
         }
+        writer.commit()?;
 
         match self.cached_items.write() {
             Ok(mut guard) => {
@@ -906,6 +1626,13 @@ fn is_file_or_folder_kind(kind: &str) -> bool {
     kind.eq_ignore_ascii_case("file") || kind.eq_ignore_ascii_case("folder")
 }
 
+fn is_filesystem_backed_kind(kind: &str) -> bool {
+    kind.eq_ignore_ascii_case("app")
+        || kind.eq_ignore_ascii_case("file")
+        || kind.eq_ignore_ascii_case("folder")
+        || kind.eq_ignore_ascii_case("command")
+}
+
 fn should_use_app_cache(filter: &SearchFilter) -> bool {
     filter.mode == SearchMode::Apps
 }
@@ -921,6 +1648,8 @@ fn search_mode_key(mode: SearchMode) -> &'static str {
         SearchMode::Files => "files",
         SearchMode::Actions => "actions",
         SearchMode::Clipboard => "clipboard",
+        SearchMode::Bookmarks => "bookmarks",
+        SearchMode::Snippets => "snippets",
     }
 }
 
@@ -953,10 +1682,7 @@ fn merge_seed_candidates(seed_items: &mut Vec<SearchItem>, extra: Vec<SearchItem
 }
 
 fn is_stale_index_entry(item: &SearchItem) -> bool {
-    if !(item.kind.eq_ignore_ascii_case("app")
-        || item.kind.eq_ignore_ascii_case("file")
-        || item.kind.eq_ignore_ascii_case("folder"))
-    {
+    if !is_filesystem_backed_kind(&item.kind) {
         return false;
     }
 
@@ -983,11 +1709,38 @@ fn looks_like_filesystem_path(path: &str) -> bool {
     bytes.len() >= 3 && bytes[1] == b':' && (bytes[2] == b'\\' || bytes[2] == b'/')
 }
 
+fn provider_manages_item(provider_name: &str, item: &SearchItem) -> bool {
+    // Plugin items may use any kind, so they are owned by their plugin's id prefix only.
+    if is_plugin_item_id(&item.id) || provider_name.starts_with(PLUGIN_PROVIDER_PREFIX) {
+        return item.id.starts_with(&plugin_item_id_prefix(provider_name));
+    }
+    let is_recent_document = item.id.starts_with(RECENT_DOCUMENT_ID_PREFIX);
+    match provider_name {
+        "recent-documents" => is_recent_document,
+        _ => !is_recent_document && provider_manages_kind(provider_name, &item.kind),
+    }
+}
+
+// The id the filesystem provider gives the file or folder behind a recent document.
+fn indexed_file_id(item: &SearchItem) -> Option<String> {
+    item.id.strip_prefix(RECENT_DOCUMENT_ID_PREFIX)?;
+    Some(format!("{}:{}", item.kind, item.path))
+}
+
+// The recent-document entry a newly indexed file or folder replaces.
+fn superseded_recent_id(item: &SearchItem) -> Option<String> {
+    let path = item.id.strip_prefix(&format!("{}:", item.kind))?;
+    matches!(item.kind.as_str(), "file" | "folder")
+        .then(|| format!("{RECENT_DOCUMENT_ID_PREFIX}{path}"))
+}
+
 fn provider_manages_kind(provider_name: &str, kind: &str) -> bool {
     let kind = kind.to_ascii_lowercase();
     match provider_name {
         "start-menu-apps" | "app" => kind == "app",
         "filesystem" | "file" => kind == "file" || kind == "folder",
+        "browser-bookmarks" => kind == "bookmark",
+        "path-executables" => kind == "command",
         _ => false,
     }
 }
@@ -996,10 +1749,7 @@ fn should_prune_after_launch_error(item: &SearchItem, error: &LaunchError) -> bo
     let is_filesystem_target = looks_like_filesystem_path(item.path.trim());
     match error {
         LaunchError::MissingPath(_) => {
-            is_filesystem_target
-                && (item.kind.eq_ignore_ascii_case("app")
-                    || item.kind.eq_ignore_ascii_case("file")
-                    || item.kind.eq_ignore_ascii_case("folder"))
+            is_filesystem_target && is_filesystem_backed_kind(&item.kind)
         }
         LaunchError::LaunchFailed {
             code: Some(code), ..
@@ -1007,9 +1757,7 @@ fn should_prune_after_launch_error(item: &SearchItem, error: &LaunchError) -> bo
             // ShellExecute missing-file/path errors: remove stale entries immediately.
             (*code == 2 || *code == 3)
                 && is_filesystem_target
-                && (item.kind.eq_ignore_ascii_case("app")
-                    || item.kind.eq_ignore_ascii_case("file")
-                    || item.kind.eq_ignore_ascii_case("folder"))
+                && is_filesystem_backed_kind(&item.kind)
         }
         LaunchError::LaunchFailed { .. } | LaunchError::EmptyPath => false,
     }
@@ -1058,6 +1806,38 @@ fn persist_provider_discovery_state(
     Ok(())
 }
 
+pub fn provider_health_snapshot(db: &Connection) -> Result<Vec<ProviderHealth>, ServiceError> {
+    Ok(
+        index_store::list_meta_with_prefix(db, PROVIDER_HEALTH_META_PREFIX)?
+            .into_iter()
+            .filter_map(|(_, value)| serde_json::from_str::<ProviderHealth>(&value).ok())
+            .collect(),
+    )
+}
+
+fn load_provider_health(
+    db: &Connection,
+    provider_name: &str,
+) -> Result<ProviderHealth, ServiceError> {
+    let stored = index_store::get_meta(db, &provider_health_meta_key(provider_name))?
+        .and_then(|value| serde_json::from_str::<ProviderHealth>(&value).ok())
+        .unwrap_or_default();
+    Ok(ProviderHealth {
+        provider: provider_name.to_string(),
+        ..stored
+    })
+}
+
+fn persist_provider_health(db: &Connection, health: &ProviderHealth) -> Result<(), ServiceError> {
+    let encoded = serde_json::to_string(health).map_err(StoreError::from)?;
+    index_store::set_meta(db, &provider_health_meta_key(&health.provider), &encoded)?;
+    Ok(())
+}
+
+fn provider_health_meta_key(provider_name: &str) -> String {
+    format!("{PROVIDER_HEALTH_META_PREFIX}{provider_name}")
+}
+
 fn provider_stamp_meta_key(provider_name: &str) -> String {
     format!("provider_stamp:{provider_name}")
 }
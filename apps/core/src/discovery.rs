@@ -1,13 +1,20 @@
 #[cfg(target_os = "windows")]
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
-use std::time::UNIX_EPOCH;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::Connection;
 
 use crate::model::SearchItem;
 
 const DEFAULT_INDEX_MAX_ITEMS_TOTAL: usize = 120_000;
 const DEFAULT_INDEX_MAX_ITEMS_PER_ROOT: usize = 40_000;
+const CHROMIUM_BOOKMARKS_FILE_NAME: &str = "Bookmarks";
+const FIREFOX_PLACES_FILE_NAME: &str = "places.sqlite";
+const DEFAULT_RECENT_DOCUMENTS_MAX_ITEMS: usize = 400;
+pub const RECENT_DOCUMENT_ID_PREFIX: &str = "recent:";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProviderError {
@@ -31,7 +38,7 @@ impl Display for ProviderError {
 impl std::error::Error for ProviderError {}
 
 pub trait DiscoveryProvider: Send + Sync {
-    fn provider_name(&self) -> &'static str;
+    fn provider_name(&self) -> &str;
     fn discover(&self) -> Result<Vec<SearchItem>, ProviderError>;
     fn change_stamp(&self) -> Option<String> {
         None
@@ -315,6 +322,250 @@ impl DiscoveryProvider for FileSystemDiscoveryProvider {
     }
 }
 
+pub struct BrowserBookmarksDiscoveryProvider {
+    profile_paths: Vec<PathBuf>,
+}
+
+impl BrowserBookmarksDiscoveryProvider {
+    pub fn new(profile_paths: Vec<PathBuf>) -> Self {
+        Self { profile_paths }
+    }
+}
+
+impl DiscoveryProvider for BrowserBookmarksDiscoveryProvider {
+    fn provider_name(&self) -> &'static str {
+        "browser-bookmarks"
+    }
+
+    fn discover(&self) -> Result<Vec<SearchItem>, ProviderError> {
+        let mut items = Vec::new();
+        let mut seen_ids = HashSet::new();
+        for source in bookmark_sources(&self.profile_paths) {
+            let discovered = match &source {
+                BookmarkSource::Chromium(path) => read_chromium_bookmarks(path),
+                BookmarkSource::Firefox(path) => read_firefox_bookmarks(path),
+            };
+            match discovered {
+                Ok(bookmarks) => {
+                    for item in bookmarks {
+                        if seen_ids.insert(item.id.clone()) {
+                            items.push(item);
+                        }
+                    }
+                }
+                // One unreadable profile should not hide bookmarks from the others.
+                Err(error) => crate::logging::warn(&format!(
+                    "[nex] browser bookmarks unavailable path={} error={}",
+                    source.path().display(),
+                    error
+                )),
+            }
+        }
+        Ok(items)
+    }
+
+    fn change_stamp(&self) -> Option<String> {
+        let parts = bookmark_sources(&self.profile_paths)
+            .iter()
+            .map(bookmark_source_stamp)
+            .collect::<Vec<_>>();
+        Some(format!("v1;{}", parts.join("|")))
+    }
+}
+
+pub struct RecentDocumentsDiscoveryProvider {
+    sources: Vec<PathBuf>,
+    excluded_roots: Vec<PathBuf>,
+    show_files: bool,
+    show_folders: bool,
+    max_items: usize,
+}
+
+impl Default for RecentDocumentsDiscoveryProvider {
+    fn default() -> Self {
+        Self::with_sources(default_recent_document_sources())
+    }
+}
+
+impl RecentDocumentsDiscoveryProvider {
+    pub fn with_sources(sources: Vec<PathBuf>) -> Self {
+        Self {
+            sources,
+            excluded_roots: Vec::new(),
+            show_files: true,
+            show_folders: true,
+            max_items: DEFAULT_RECENT_DOCUMENTS_MAX_ITEMS,
+        }
+    }
+
+    pub fn with_visibility(mut self, show_files: bool, show_folders: bool) -> Self {
+        self.show_files = show_files;
+        self.show_folders = show_folders;
+        self
+    }
+
+    pub fn with_excluded_roots(mut self, excluded_roots: Vec<PathBuf>) -> Self {
+        self.excluded_roots = excluded_roots;
+        self
+    }
+
+    pub fn with_max_items(mut self, max_items: usize) -> Self {
+        self.max_items = max_items.max(1);
+        self
+    }
+}
+
+impl DiscoveryProvider for RecentDocumentsDiscoveryProvider {
+    fn provider_name(&self) -> &'static str {
+        "recent-documents"
+    }
+
+    fn discover(&self) -> Result<Vec<SearchItem>, ProviderError> {
+        let mut entries = Vec::new();
+        for source in &self.sources {
+            if source.is_dir() {
+                entries.extend(read_recent_shortcut_dir(source));
+            } else if source.is_file() {
+                match read_recently_used_xbel(source) {
+                    Ok(found) => entries.extend(found),
+                    Err(error) => crate::logging::warn(&format!(
+                        "[nex] recent documents unavailable path={} error={}",
+                        source.display(),
+                        error
+                    )),
+                }
+            }
+        }
+
+        // Newest first so the item cap keeps the most recently touched documents.
+        entries.sort_by(|left, right| right.1.cmp(&left.1).then_with(|| left.0.cmp(&right.0)));
+
+        let excluded = normalized_exclusion_roots(&self.excluded_roots);
+        let mut seen_paths = HashSet::new();
+        let mut out = Vec::new();
+        for (path, last_accessed_epoch_secs) in entries {
+            if out.len() >= self.max_items {
+                break;
+            }
+            if is_path_under_any_excluded_root(&path, &excluded) {
+                continue;
+            }
+            let Some(path_key) = normalize_path_for_compare(&path) else {
+                continue;
+            };
+            if !seen_paths.insert(path_key) {
+                continue;
+            }
+            let Ok(meta) = std::fs::metadata(&path) else {
+                continue;
+            };
+
+            let is_dir = meta.is_dir();
+            if (is_dir && !self.show_folders) || (!is_dir && !self.show_files) {
+                continue;
+            }
+            let kind = if is_dir { "folder" } else { "file" };
+            let title = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string());
+            let id = format!("{RECENT_DOCUMENT_ID_PREFIX}{}", path.to_string_lossy());
+            out.push(
+                SearchItem::new(&id, kind, &title, &path.to_string_lossy())
+                    .with_usage(0, last_accessed_epoch_secs),
+            );
+        }
+        Ok(out)
+    }
+
+    fn change_stamp(&self) -> Option<String> {
+        Some(format!(
+            "v1;sources={};exclude={};show_files={};show_folders={};cap={}",
+            roots_change_stamp(&self.sources),
+            roots_change_stamp(&self.excluded_roots),
+            self.show_files,
+            self.show_folders,
+            self.max_items
+        ))
+    }
+}
+
+pub struct PathExecutablesDiscoveryProvider {
+    dirs: Vec<PathBuf>,
+}
+
+impl PathExecutablesDiscoveryProvider {
+    pub fn new(extra_dirs: Vec<PathBuf>) -> Self {
+        let mut dirs = std::env::var_os("PATH")
+            .map(|value| std::env::split_paths(&value).collect::<Vec<_>>())
+            .unwrap_or_default();
+        dirs.extend(extra_dirs);
+        Self::with_dirs(dirs)
+    }
+
+    pub fn with_dirs(dirs: Vec<PathBuf>) -> Self {
+        let mut seen = HashSet::new();
+        let dirs = dirs
+            .into_iter()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .filter(|dir| normalize_path_for_compare(dir).is_some_and(|key| seen.insert(key)))
+            .collect();
+        Self { dirs }
+    }
+}
+
+impl DiscoveryProvider for PathExecutablesDiscoveryProvider {
+    fn provider_name(&self) -> &'static str {
+        "path-executables"
+    }
+
+    fn discover(&self) -> Result<Vec<SearchItem>, ProviderError> {
+        let executable_extensions = executable_extensions();
+        let mut seen_names = HashSet::new();
+        let mut out = Vec::new();
+
+        // Directory order mirrors PATH lookup, so the first hit shadows later ones.
+        for dir in &self.dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            let mut paths = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .collect::<Vec<_>>();
+            paths.sort();
+
+            for path in paths {
+                let Some(name) = executable_command_name(&path, &executable_extensions) else {
+                    continue;
+                };
+                let name_key = if cfg!(target_os = "windows") {
+                    name.to_ascii_lowercase()
+                } else {
+                    name.clone()
+                };
+                if !seen_names.insert(name_key.clone()) {
+                    continue;
+                }
+                out.push(
+                    SearchItem::new(
+                        &format!("command:{name_key}"),
+                        "command",
+                        &name,
+                        &path.to_string_lossy(),
+                    )
+                    .with_subtitle(&dir.to_string_lossy()),
+                );
+            }
+        }
+        Ok(out)
+    }
+
+    fn change_stamp(&self) -> Option<String> {
+        Some(format!("v1;{}", roots_change_stamp(&self.dirs)))
+    }
+}
+
 fn discover_filesystem_walk(
     roots: &[PathBuf],
     excluded_roots: &[PathBuf],
@@ -408,6 +659,462 @@ fn discover_filesystem_walk(
     Ok(out)
 }
 
+enum BookmarkSource {
+    Chromium(PathBuf),
+    Firefox(PathBuf),
+}
+
+impl BookmarkSource {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Chromium(path) | Self::Firefox(path) => path,
+        }
+    }
+}
+
+fn bookmark_sources(profile_paths: &[PathBuf]) -> Vec<BookmarkSource> {
+    let mut out = Vec::new();
+    for path in profile_paths {
+        if path.is_file() {
+            out.extend(bookmark_source_for_file(path));
+            continue;
+        }
+        if !path.is_dir() {
+            continue;
+        }
+
+        let direct = profile_dir_bookmark_sources(path);
+        if !direct.is_empty() {
+            out.extend(direct);
+            continue;
+        }
+
+        // Browser data roots (Chromium `User Data`, Firefox `Profiles`) hold one
+        // directory per profile.
+        let mut profile_dirs = Vec::new();
+        if let Ok(entries) = std::fs::read_dir(path) {
+            for entry in entries.flatten() {
+                let entry_path = entry.path();
+                if entry_path.is_dir() {
+                    profile_dirs.push(entry_path);
+                }
+            }
+        }
+        profile_dirs.sort();
+        for profile_dir in profile_dirs {
+            out.extend(profile_dir_bookmark_sources(&profile_dir));
+        }
+    }
+    out
+}
+
+fn profile_dir_bookmark_sources(dir: &Path) -> Vec<BookmarkSource> {
+    let mut out = Vec::new();
+    let chromium = dir.join(CHROMIUM_BOOKMARKS_FILE_NAME);
+    if chromium.is_file() {
+        out.push(BookmarkSource::Chromium(chromium));
+    }
+    let firefox = dir.join(FIREFOX_PLACES_FILE_NAME);
+    if firefox.is_file() {
+        out.push(BookmarkSource::Firefox(firefox));
+    }
+    out
+}
+
+fn bookmark_source_for_file(path: &Path) -> Option<BookmarkSource> {
+    let name = path.file_name()?.to_str()?;
+    if name.eq_ignore_ascii_case(CHROMIUM_BOOKMARKS_FILE_NAME) {
+        Some(BookmarkSource::Chromium(path.to_path_buf()))
+    } else if name.eq_ignore_ascii_case(FIREFOX_PLACES_FILE_NAME) {
+        Some(BookmarkSource::Firefox(path.to_path_buf()))
+    } else {
+        None
+    }
+}
+
+fn bookmark_source_stamp(source: &BookmarkSource) -> String {
+    let path = source.path();
+    let file_modified_secs = |path: &Path| {
+        std::fs::metadata(path)
+            .map(|meta| modified_secs(&meta))
+            .unwrap_or(0)
+    };
+    match source {
+        BookmarkSource::Chromium(_) => format!(
+            "{}:{}",
+            normalize_root_for_stamp(path),
+            file_modified_secs(path)
+        ),
+        // Firefox appends recent writes to the WAL before checkpointing places.sqlite.
+        BookmarkSource::Firefox(_) => format!(
+            "{}:{}:{}",
+            normalize_root_for_stamp(path),
+            file_modified_secs(path),
+            file_modified_secs(&sqlite_sidecar_path(path, "-wal"))
+        ),
+    }
+}
+
+fn read_chromium_bookmarks(path: &Path) -> Result<Vec<SearchItem>, ProviderError> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|error| ProviderError::new(format!("read failed: {error}")))?;
+    let value: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|error| ProviderError::new(format!("invalid bookmarks json: {error}")))?;
+
+    let mut out = Vec::new();
+    if let Some(roots) = value.get("roots").and_then(|roots| roots.as_object()) {
+        for root in roots.values() {
+            collect_chromium_bookmark_nodes(root, "", &mut out);
+        }
+    }
+    Ok(out)
+}
+
+fn collect_chromium_bookmark_nodes(
+    node: &serde_json::Value,
+    trail: &str,
+    out: &mut Vec<SearchItem>,
+) {
+    let name = node
+        .get("name")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .trim();
+    match node.get("type").and_then(|value| value.as_str()) {
+        Some("url") => {
+            let url = node
+                .get("url")
+                .and_then(|value| value.as_str())
+                .unwrap_or_default();
+            out.extend(bookmark_item(name, url, trail));
+        }
+        Some("folder") => {
+            let child_trail = join_bookmark_trail(trail, name);
+            if let Some(children) = node.get("children").and_then(|value| value.as_array()) {
+                for child in children {
+                    collect_chromium_bookmark_nodes(child, &child_trail, out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn read_firefox_bookmarks(path: &Path) -> Result<Vec<SearchItem>, ProviderError> {
+    // Firefox holds a lock on places.sqlite while running; read a private copy.
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_nanos())
+        .unwrap_or(0);
+    let snapshot =
+        std::env::temp_dir().join(format!("nex-places-{}-{unique}.sqlite", std::process::id()));
+    std::fs::copy(path, &snapshot)
+        .map_err(|error| ProviderError::new(format!("copy failed: {error}")))?;
+    let wal = sqlite_sidecar_path(path, "-wal");
+    if wal.is_file() {
+        let _ = std::fs::copy(&wal, sqlite_sidecar_path(&snapshot, "-wal"));
+    }
+
+    let result = query_firefox_bookmarks(&snapshot)
+        .map_err(|error| ProviderError::new(format!("places query failed: {error}")));
+
+    let _ = std::fs::remove_file(&snapshot);
+    let _ = std::fs::remove_file(sqlite_sidecar_path(&snapshot, "-wal"));
+    let _ = std::fs::remove_file(sqlite_sidecar_path(&snapshot, "-shm"));
+    result
+}
+
+fn query_firefox_bookmarks(path: &Path) -> Result<Vec<SearchItem>, rusqlite::Error> {
+    let conn = Connection::open(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT b.title, p.url, parent.title
+         FROM moz_bookmarks b
+         JOIN moz_places p ON p.id = b.fk
+         LEFT JOIN moz_bookmarks parent ON parent.id = b.parent
+         WHERE b.type = 1
+         ORDER BY b.id",
+    )?;
+    let mut rows = stmt.query([])?;
+
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        let title: Option<String> = row.get(0)?;
+        let url: String = row.get(1)?;
+        let folder: Option<String> = row.get(2)?;
+        out.extend(bookmark_item(
+            title.as_deref().unwrap_or_default(),
+            &url,
+            folder.as_deref().unwrap_or_default().trim(),
+        ));
+    }
+    Ok(out)
+}
+
+fn bookmark_item(title: &str, url: &str, folder: &str) -> Option<SearchItem> {
+    let url = url.trim();
+    // Skip bookmarklets and browser-internal `place:` queries.
+    if !url.contains("://") {
+        return None;
+    }
+    let title = if title.trim().is_empty() {
+        url
+    } else {
+        title.trim()
+    };
+    Some(SearchItem::new(&format!("bookmark:{url}"), "bookmark", title, url).with_subtitle(folder))
+}
+
+fn join_bookmark_trail(trail: &str, name: &str) -> String {
+    if trail.is_empty() {
+        name.to_string()
+    } else if name.is_empty() {
+        trail.to_string()
+    } else {
+        format!("{trail}/{name}")
+    }
+}
+
+fn sqlite_sidecar_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut raw = path.as_os_str().to_os_string();
+    raw.push(suffix);
+    PathBuf::from(raw)
+}
+
+#[cfg(target_os = "windows")]
+fn default_recent_document_sources() -> Vec<PathBuf> {
+    if let Ok(appdata) = std::env::var("APPDATA") {
+        let trimmed = appdata.trim();
+        if !trimmed.is_empty() {
+            return vec![PathBuf::from(trimmed)
+                .join("Microsoft")
+                .join("Windows")
+                .join("Recent")];
+        }
+    }
+    Vec::new()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn default_recent_document_sources() -> Vec<PathBuf> {
+    if let Ok(data_home) = std::env::var("XDG_DATA_HOME") {
+        if !data_home.trim().is_empty() {
+            return vec![PathBuf::from(data_home).join("recently-used.xbel")];
+        }
+    }
+    if let Ok(home) = std::env::var("HOME") {
+        return vec![PathBuf::from(home)
+            .join(".local")
+            .join("share")
+            .join("recently-used.xbel")];
+    }
+    Vec::new()
+}
+
+fn read_recently_used_xbel(path: &Path) -> Result<Vec<(PathBuf, i64)>, ProviderError> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|error| ProviderError::new(format!("read failed: {error}")))?;
+    Ok(parse_recently_used_xbel(&raw))
+}
+
+fn parse_recently_used_xbel(raw: &str) -> Vec<(PathBuf, i64)> {
+    let mut out = Vec::new();
+    // `<bookmark:...>` metadata children never match the trailing space.
+    for chunk in raw.split("<bookmark ").skip(1) {
+        let Some(tag) = chunk.split('>').next() else {
+            continue;
+        };
+        let Some(path) = xml_attribute(tag, "href").and_then(|href| file_uri_to_path(&href)) else {
+            continue;
+        };
+        let last_accessed_epoch_secs = ["visited", "modified", "added"]
+            .iter()
+            .filter_map(|name| xml_attribute(tag, name))
+            .filter_map(|value| parse_rfc3339_epoch_secs(&value))
+            .max()
+            .unwrap_or(0);
+        out.push((path, last_accessed_epoch_secs));
+    }
+    out
+}
+
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let needle = format!("{name}=\"");
+    let mut search_from = 0;
+    while let Some(found) = tag[search_from..].find(&needle) {
+        let start = search_from + found;
+        let value_start = start + needle.len();
+        if start == 0 || tag[..start].ends_with(char::is_whitespace) {
+            let end = value_start + tag[value_start..].find('"')?;
+            return Some(decode_xml_entities(&tag[value_start..end]));
+        }
+        search_from = value_start;
+    }
+    None
+}
+
+fn decode_xml_entities(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entities = [
+            ("&amp;", '&'),
+            ("&lt;", '<'),
+            ("&gt;", '>'),
+            ("&quot;", '"'),
+            ("&apos;", '\''),
+        ];
+        match entities.iter().find(|(entity, _)| rest.starts_with(entity)) {
+            Some((entity, ch)) => {
+                out.push(*ch);
+                rest = &rest[entity.len()..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.trim().strip_prefix("file://")?;
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !rest.starts_with('/') {
+        return None;
+    }
+
+    let bytes = rest.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' && idx + 2 < bytes.len() {
+            let high = (bytes[idx + 1] as char).to_digit(16);
+            let low = (bytes[idx + 2] as char).to_digit(16);
+            if let (Some(high), Some(low)) = (high, low) {
+                decoded.push((high * 16 + low) as u8);
+                idx += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&decoded).to_string()))
+}
+
+fn parse_rfc3339_epoch_secs(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (date, time) = value.split_once('T')?;
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+
+    let clock = time.get(..8)?;
+    let mut clock_parts = clock.splitn(3, ':');
+    let hour: i64 = clock_parts.next()?.parse().ok()?;
+    let minute: i64 = clock_parts.next()?.parse().ok()?;
+    let second: i64 = clock_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    // Days since 1970-01-01 for a proleptic Gregorian date.
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let mut epoch_secs = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    let zone = time[8..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    if let Some(sign) = zone.chars().next().filter(|c| *c == '+' || *c == '-') {
+        let offset = &zone[1..];
+        let offset_hours: i64 = offset.get(..2)?.parse().ok()?;
+        let offset_minutes: i64 = offset.get(3..5).and_then(|v| v.parse().ok()).unwrap_or(0);
+        let offset_secs = offset_hours * 3_600 + offset_minutes * 60;
+        epoch_secs += if sign == '+' {
+            -offset_secs
+        } else {
+            offset_secs
+        };
+    }
+    Some(epoch_secs)
+}
+
+#[cfg(target_os = "windows")]
+fn read_recent_shortcut_dir(dir: &Path) -> Vec<(PathBuf, i64)> {
+    let mut out = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return out;
+    };
+    for entry in entries.flatten() {
+        let shortcut = entry.path();
+        let is_lnk = shortcut
+            .extension()
+            .and_then(|v| v.to_str())
+            .is_some_and(|v| v.eq_ignore_ascii_case("lnk"));
+        if !is_lnk {
+            continue;
+        }
+        let Some(target) = resolve_shortcut_target_for_discovery(&shortcut) else {
+            continue;
+        };
+        // Explorer rewrites the Recent shortcut whenever the target is reopened.
+        let last_accessed_epoch_secs = entry
+            .metadata()
+            .map(|meta| modified_secs(&meta) as i64)
+            .unwrap_or(0);
+        out.push((PathBuf::from(target), last_accessed_epoch_secs));
+    }
+    out
+}
+
+#[cfg(not(target_os = "windows"))]
+fn read_recent_shortcut_dir(_dir: &Path) -> Vec<(PathBuf, i64)> {
+    Vec::new()
+}
+
+#[cfg(target_os = "windows")]
+fn executable_extensions() -> Vec<String> {
+    let raw = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    raw.split(';')
+        .map(|ext| ext.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn executable_extensions() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(target_os = "windows")]
+fn executable_command_name(path: &Path, executable_extensions: &[String]) -> Option<String> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    if !executable_extensions.contains(&extension) || !path.is_file() {
+        return None;
+    }
+    Some(path.file_stem()?.to_string_lossy().to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn executable_command_name(path: &Path, _executable_extensions: &[String]) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() || meta.permissions().mode() & 0o111 == 0 {
+        return None;
+    }
+    Some(path.file_name()?.to_string_lossy().to_string())
+}
+
 fn roots_change_stamp(roots: &[PathBuf]) -> String {
     let mut parts = Vec::with_capacity(roots.len());
     for root in roots {
@@ -0,0 +1,130 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use crate::core_service::IndexRefreshReport;
+
+pub const EVENT_INDEX_REFRESH_STARTED: &str = "index_refresh_started";
+pub const EVENT_INDEX_REFRESH_FINISHED: &str = "index_refresh_finished";
+pub const EVENT_PROVIDER_FAILED: &str = "provider_failed";
+pub const EVENT_ITEM_LAUNCHED: &str = "item_launched";
+pub const EVENT_CONFIG_RELOADED: &str = "config_reloaded";
+pub const EVENT_CLIPBOARD_CAPTURED: &str = "clipboard_captured";
+
+pub const EVENT_NAMES: &[&str] = &[
+    EVENT_INDEX_REFRESH_STARTED,
+    EVENT_INDEX_REFRESH_FINISHED,
+    EVENT_PROVIDER_FAILED,
+    EVENT_ITEM_LAUNCHED,
+    EVENT_CONFIG_RELOADED,
+    EVENT_CLIPBOARD_CAPTURED,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum CoreEvent {
+    IndexRefreshStarted {
+        incremental: bool,
+    },
+    IndexRefreshFinished(IndexRefreshReport),
+    ProviderFailed {
+        provider: String,
+        error: String,
+    },
+    ItemLaunched {
+        id: String,
+        kind: String,
+        title: String,
+    },
+    ConfigReloaded {
+        config_path: String,
+    },
+    ClipboardCaptured {
+        captured_epoch_secs: i64,
+    },
+}
+
+impl CoreEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IndexRefreshStarted { .. } => EVENT_INDEX_REFRESH_STARTED,
+            Self::IndexRefreshFinished(_) => EVENT_INDEX_REFRESH_FINISHED,
+            Self::ProviderFailed { .. } => EVENT_PROVIDER_FAILED,
+            Self::ItemLaunched { .. } => EVENT_ITEM_LAUNCHED,
+            Self::ConfigReloaded { .. } => EVENT_CONFIG_RELOADED,
+            Self::ClipboardCaptured { .. } => EVENT_CLIPBOARD_CAPTURED,
+        }
+    }
+}
+
+// Cloning shares the subscriber list, so a worker thread's own `CoreService` can publish
+// to the same subscribers as the runtime's.
+#[derive(Debug, Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Sender<CoreEvent>>>>,
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> Receiver<CoreEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.lock().push(sender);
+        receiver
+    }
+
+    pub fn publish(&self, event: CoreEvent) {
+        // Dropped receivers are pruned on the next publish.
+        self.lock()
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Sender<CoreEvent>>> {
+        match self.subscribers.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+// Resolves a subscription filter; an empty list means every event.
+pub fn resolve_event_names(requested: &[String]) -> Result<Vec<String>, String> {
+    if requested.is_empty() {
+        return Ok(EVENT_NAMES.iter().map(|name| name.to_string()).collect());
+    }
+    let mut resolved = Vec::with_capacity(requested.len());
+    for name in requested {
+        let normalized = name.trim().to_ascii_lowercase();
+        if !EVENT_NAMES.contains(&normalized.as_str()) {
+            return Err(format!("unknown event: {name}"));
+        }
+        if !resolved.contains(&normalized) {
+            resolved.push(normalized);
+        }
+    }
+    Ok(resolved)
+}
+
+pub struct EventSubscription {
+    receiver: Receiver<CoreEvent>,
+    events: Vec<String>,
+}
+
+impl EventSubscription {
+    pub fn new(receiver: Receiver<CoreEvent>, events: Vec<String>) -> Self {
+        Self { receiver, events }
+    }
+
+    // Blocks until the next event this subscription asked for; `None` once the bus is gone.
+    pub fn next_event(&self) -> Option<CoreEvent> {
+        loop {
+            let event = self.receiver.recv().ok()?;
+            if self.events.iter().any(|name| name == event.name()) {
+                return Some(event);
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use std::fmt::{Display, Formatter};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, ErrorCode, OpenFlags, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
 
 use crate::config::Config;
 use crate::model::SearchItem;
@@ -10,6 +12,9 @@ use crate::model::SearchItem;
 pub enum StoreError {
     Io(std::io::Error),
     Db(rusqlite::Error),
+    Encode(serde_json::Error),
+    SchemaTooNew { found: i64, supported: i64 },
+    UnknownSchemaVersion(i64),
 }
 
 impl Display for StoreError {
@@ -17,6 +22,14 @@ impl Display for StoreError {
         match self {
             Self::Io(error) => write!(f, "io error: {error}"),
             Self::Db(error) => write!(f, "db error: {error}"),
+            Self::Encode(error) => write!(f, "encode error: {error}"),
+            Self::SchemaTooNew { found, supported } => write!(
+                f,
+                "index schema v{found} is newer than this build supports (v{supported}); upgrade nex or downgrade the index with a newer build"
+            ),
+            Self::UnknownSchemaVersion(version) => {
+                write!(f, "unknown index schema version: v{version}")
+            }
         }
     }
 }
@@ -35,9 +48,109 @@ impl From<rusqlite::Error> for StoreError {
     }
 }
 
+impl From<serde_json::Error> for StoreError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Encode(value)
+    }
+}
+
+const UPSERT_ITEM_SQL: &str = "INSERT INTO item (id, kind, title, path, subtitle, use_count, last_accessed_epoch_secs) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET kind=excluded.kind, title=excluded.title, path=excluded.path, subtitle=excluded.subtitle,
+         use_count=excluded.use_count, last_accessed_epoch_secs=excluded.last_accessed_epoch_secs";
+const DELETE_ITEM_SQL: &str = "DELETE FROM item WHERE id = ?1";
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const INDEX_MAINTENANCE_META_KEY: &str = "index_maintenance";
+const REPAIR_MARKER_SUFFIX: &str = "repair-pending";
+
+pub const SCHEMA_VERSION: i64 = 5;
+
+struct Migration {
+    version: i64,
+    up: fn(&Connection) -> Result<(), StoreError>,
+    down: fn(&Connection) -> Result<(), StoreError>,
+}
+
+// Ordered by version. `up` moves from `version - 1` to `version`; `down` undoes it.
+// New schema changes append an entry here and bump SCHEMA_VERSION.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        up: migration_v1,
+        down: migration_v1_down,
+    },
+    Migration {
+        version: 2,
+        up: migration_v2,
+        down: migration_v2_down,
+    },
+    Migration {
+        version: 3,
+        up: migration_v3,
+        down: migration_v3_down,
+    },
+    Migration {
+        version: 4,
+        up: migration_v4,
+        down: migration_v4_down,
+    },
+    Migration {
+        version: 5,
+        up: migration_v5,
+        down: migration_v5_down,
+    },
+];
+
+// Groups writes into one transaction with cached statements. Dropping the writer
+// without `commit` rolls everything back, so a crash never leaves a half-applied batch.
+pub struct IndexBatchWriter<'conn> {
+    tx: Transaction<'conn>,
+    upserted: usize,
+    deleted: usize,
+}
+
+impl<'conn> IndexBatchWriter<'conn> {
+    pub fn begin(db: &'conn Connection) -> Result<Self, StoreError> {
+        Ok(Self {
+            tx: db.unchecked_transaction()?,
+            upserted: 0,
+            deleted: 0,
+        })
+    }
+
+    pub fn upsert_item(&mut self, item: &SearchItem) -> Result<(), StoreError> {
+        upsert_item(&self.tx, item)?;
+        self.upserted += 1;
+        Ok(())
+    }
+
+    pub fn delete_item(&mut self, id: &str) -> Result<(), StoreError> {
+        delete_item(&self.tx, id)?;
+        self.deleted += 1;
+        Ok(())
+    }
+
+    // For meta and content writes that must land in the same transaction.
+    pub fn connection(&self) -> &Connection {
+        &self.tx
+    }
+
+    pub fn upserted(&self) -> usize {
+        self.upserted
+    }
+
+    pub fn deleted(&self) -> usize {
+        self.deleted
+    }
+
+    pub fn commit(self) -> Result<(), StoreError> {
+        self.tx.commit()?;
+        Ok(())
+    }
+}
+
 pub fn open_memory() -> Result<Connection, StoreError> {
     let conn = Connection::open_in_memory()?;
-    init_schema(&conn)?;
+    migrate_schema(&conn, SCHEMA_VERSION)?;
     Ok(conn)
 }
 
@@ -47,29 +160,240 @@ pub fn open_file(path: &Path) -> Result<Connection, StoreError> {
     }
 
     let conn = Connection::open(path)?;
-    init_schema(&conn)?;
+    apply_file_pragmas(&conn)?;
+    init_schema(&conn, path)?;
+    Ok(conn)
+}
+
+// Used to hand the index back to an older build: migrates down without first
+// upgrading, and keeps a backup of the current file.
+pub fn downgrade_file(path: &Path, target_version: i64) -> Result<Option<PathBuf>, StoreError> {
+    let conn = Connection::open(path)?;
+    apply_file_pragmas(&conn)?;
+    migrate_file_schema(&conn, path, target_version)
+}
+
+// Inspection-only access (diagnostics, status) that works even when the on-disk
+// schema is newer than this build, because nothing is migrated or written.
+pub fn open_file_read_only(path: &Path) -> Result<Connection, StoreError> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
     Ok(conn)
 }
 
+fn apply_file_pragmas(conn: &Connection) -> Result<(), StoreError> {
+    // WAL lets the overlay keep reading while a background refresh writes.
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    let _mode: String =
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    conn.pragma_update(None, "temp_store", "MEMORY")?;
+    conn.pragma_update(None, "cache_size", -8_192_i64)?;
+    Ok(())
+}
+
 pub fn open_from_config(cfg: &Config) -> Result<Connection, StoreError> {
     open_file(&cfg.index_db_path)
 }
 
-pub fn upsert_item(db: &Connection, item: &SearchItem) -> Result<(), StoreError> {
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexMaintenanceReport {
+    pub operation: String,
+    pub epoch_secs: i64,
+    pub problems: Vec<String>,
+    pub quarantined_path: Option<String>,
+    pub vacuumed: bool,
+}
+
+impl IndexMaintenanceReport {
+    fn new(operation: &str) -> Self {
+        Self {
+            operation: operation.to_string(),
+            epoch_secs: now_epoch_secs(),
+            ..Self::default()
+        }
+    }
+
+    pub fn quarantined(&self) -> bool {
+        self.quarantined_path.is_some()
+    }
+}
+
+// Startup variant of `open_file`: a cheap quick_check decides whether the database
+// is usable. A corrupt file is moved aside and replaced with an empty one so the
+// next refresh can rebuild it instead of the service failing to start.
+pub fn open_file_checked(
+    path: &Path,
+) -> Result<(Connection, Option<IndexMaintenanceReport>), StoreError> {
+    if !path.exists() {
+        let _ = std::fs::remove_file(repair_marker_path(path));
+        return Ok((open_file(path)?, None));
+    }
+
+    let mut report = IndexMaintenanceReport::new("startup_check");
+    if repair_marker_path(path).exists() {
+        report
+            .problems
+            .push("repair requested on previous run".to_string());
+    } else {
+        match open_file(path).and_then(|conn| quick_check(&conn).map(|problems| (conn, problems))) {
+            Ok((conn, problems)) if problems.is_empty() => return Ok((conn, None)),
+            Ok((_, problems)) => report.problems = problems,
+            Err(error) if is_corruption_error(&error) => report.problems.push(error.to_string()),
+            Err(error) => return Err(error),
+        }
+    }
+
+    let conn = quarantine_and_reopen(path, &mut report)?;
+    Ok((conn, Some(report)))
+}
+
+// Full integrity check for explicit repair requests. A healthy database is compacted;
+// a damaged one is quarantined and recreated empty.
+pub fn repair_file(path: &Path) -> Result<IndexMaintenanceReport, StoreError> {
+    let mut report = IndexMaintenanceReport::new("repair");
+    if path.exists() {
+        match open_file(path)
+            .and_then(|conn| integrity_check(&conn).map(|problems| (conn, problems)))
+        {
+            Ok((conn, problems)) if problems.is_empty() => {
+                optimize(&conn, true)?;
+                report.vacuumed = true;
+                record_maintenance(&conn, &report)?;
+                return Ok(report);
+            }
+            Ok((_, problems)) => report.problems = problems,
+            Err(error) if is_corruption_error(&error) => report.problems.push(error.to_string()),
+            Err(error) => return Err(error),
+        }
+    }
+
+    quarantine_and_reopen(path, &mut report)?;
+    Ok(report)
+}
+
+// Read-only integrity check, safe while another process has the database open.
+pub fn check_file(path: &Path) -> Result<Vec<String>, StoreError> {
+    match open_file_read_only(path).and_then(|conn| integrity_check(&conn)) {
+        Err(error) if is_corruption_error(&error) => Ok(vec![error.to_string()]),
+        outcome => outcome,
+    }
+}
+
+// Used while the database is held open elsewhere: the next `open_file_checked`
+// quarantines the file before anything reads it.
+pub fn schedule_repair(path: &Path) -> Result<(), StoreError> {
+    std::fs::write(repair_marker_path(path), now_epoch_secs().to_string())?;
+    Ok(())
+}
+
+pub fn integrity_check(db: &Connection) -> Result<Vec<String>, StoreError> {
+    check_pragma(db, "integrity_check")
+}
+
+pub fn quick_check(db: &Connection) -> Result<Vec<String>, StoreError> {
+    check_pragma(db, "quick_check")
+}
+
+fn check_pragma(db: &Connection, pragma: &str) -> Result<Vec<String>, StoreError> {
+    let mut stmt = db.prepare(&format!("PRAGMA {pragma}"))?;
+    let mut rows = stmt.query([])?;
+    let mut problems = Vec::new();
+    while let Some(row) = rows.next()? {
+        let line: String = row.get(0)?;
+        if line != "ok" {
+            problems.push(line);
+        }
+    }
+    Ok(problems)
+}
+
+pub fn optimize(db: &Connection, vacuum: bool) -> Result<(), StoreError> {
     db.execute(
-        "INSERT INTO item (id, kind, title, path, subtitle, use_count, last_accessed_epoch_secs) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-         ON CONFLICT(id) DO UPDATE SET kind=excluded.kind, title=excluded.title, path=excluded.path, subtitle=excluded.subtitle,
-         use_count=excluded.use_count, last_accessed_epoch_secs=excluded.last_accessed_epoch_secs",
-        params![
-            item.id,
-            item.kind,
-            item.title,
-            item.path,
-            item.subtitle,
-            item.use_count,
-            item.last_accessed_epoch_secs,
-        ],
+        "INSERT INTO item_content(item_content) VALUES('optimize')",
+        [],
     )?;
+    if vacuum {
+        db.execute_batch("VACUUM")?;
+    }
+    db.execute_batch("PRAGMA optimize")?;
+    Ok(())
+}
+
+pub fn record_maintenance(
+    db: &Connection,
+    report: &IndexMaintenanceReport,
+) -> Result<(), StoreError> {
+    let encoded = serde_json::to_string(report)?;
+    set_meta(db, INDEX_MAINTENANCE_META_KEY, &encoded)
+}
+
+pub fn last_maintenance(db: &Connection) -> Result<Option<IndexMaintenanceReport>, StoreError> {
+    Ok(get_meta(db, INDEX_MAINTENANCE_META_KEY)?
+        .and_then(|encoded| serde_json::from_str(&encoded).ok()))
+}
+
+fn quarantine_and_reopen(
+    path: &Path,
+    report: &mut IndexMaintenanceReport,
+) -> Result<Connection, StoreError> {
+    if path.exists() {
+        let quarantined = sibling_path(path, &format!("corrupt-{}", report.epoch_secs));
+        std::fs::rename(path, &quarantined)?;
+        for suffix in ["wal", "shm"] {
+            let side_file = sibling_path(path, suffix);
+            if side_file.exists() {
+                let _ = std::fs::rename(&side_file, sibling_path(&quarantined, suffix));
+            }
+        }
+        report.quarantined_path = Some(quarantined.to_string_lossy().into_owned());
+    }
+    let _ = std::fs::remove_file(repair_marker_path(path));
+
+    let conn = open_file(path)?;
+    record_maintenance(&conn, report)?;
+    Ok(conn)
+}
+
+fn is_corruption_error(error: &StoreError) -> bool {
+    matches!(
+        error,
+        StoreError::Db(rusqlite::Error::SqliteFailure(failure, _))
+            if matches!(failure.code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+    )
+}
+
+fn repair_marker_path(path: &Path) -> PathBuf {
+    sibling_path(path, REPAIR_MARKER_SUFFIX)
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!("-{suffix}"));
+    PathBuf::from(name)
+}
+
+fn now_epoch_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+pub fn upsert_item(db: &Connection, item: &SearchItem) -> Result<(), StoreError> {
+    db.prepare_cached(UPSERT_ITEM_SQL)?.execute(params![
+        item.id,
+        item.kind,
+        item.title,
+        item.path,
+        item.subtitle,
+        item.use_count,
+        item.last_accessed_epoch_secs,
+    ])?;
     Ok(())
 }
 
@@ -131,14 +455,96 @@ pub fn list_items(db: &Connection) -> Result<Vec<SearchItem>, StoreError> {
 
 pub fn clear_items(db: &Connection) -> Result<(), StoreError> {
     db.execute("DELETE FROM item", [])?;
+    clear_item_content(db)?;
     Ok(())
 }
 
 pub fn delete_item(db: &Connection, id: &str) -> Result<(), StoreError> {
-    db.execute("DELETE FROM item WHERE id = ?1", params![id])?;
+    db.prepare_cached(DELETE_ITEM_SQL)?.execute(params![id])?;
+    delete_item_content(db, id)?;
     Ok(())
 }
 
+pub fn upsert_item_content(
+    db: &Connection,
+    item_id: &str,
+    stamp: &str,
+    body: &str,
+) -> Result<(), StoreError> {
+    // The FTS row shares its rowid with the state row so deletes never scan the FTS table.
+    db.prepare_cached(
+        "INSERT INTO item_content_state (item_id, stamp) VALUES (?1, ?2)
+         ON CONFLICT(item_id) DO UPDATE SET stamp=excluded.stamp",
+    )?
+    .execute(params![item_id, stamp])?;
+    let content_rowid: i64 = db
+        .prepare_cached("SELECT rowid FROM item_content_state WHERE item_id = ?1")?
+        .query_row(params![item_id], |row| row.get(0))?;
+    db.prepare_cached("DELETE FROM item_content WHERE rowid = ?1")?
+        .execute(params![content_rowid])?;
+    db.prepare_cached("INSERT INTO item_content (rowid, item_id, body) VALUES (?1, ?2, ?3)")?
+        .execute(params![content_rowid, item_id, body])?;
+    Ok(())
+}
+
+pub fn delete_item_content(db: &Connection, item_id: &str) -> Result<(), StoreError> {
+    let content_rowid: Option<i64> = db
+        .prepare_cached("SELECT rowid FROM item_content_state WHERE item_id = ?1")?
+        .query_row(params![item_id], |row| row.get(0))
+        .optional()?;
+    let Some(content_rowid) = content_rowid else {
+        return Ok(());
+    };
+    db.prepare_cached("DELETE FROM item_content WHERE rowid = ?1")?
+        .execute(params![content_rowid])?;
+    db.prepare_cached("DELETE FROM item_content_state WHERE rowid = ?1")?
+        .execute(params![content_rowid])?;
+    Ok(())
+}
+
+pub fn clear_item_content(db: &Connection) -> Result<(), StoreError> {
+    db.execute("DELETE FROM item_content", [])?;
+    db.execute("DELETE FROM item_content_state", [])?;
+    Ok(())
+}
+
+pub fn list_item_content_stamps(db: &Connection) -> Result<Vec<(String, String)>, StoreError> {
+    let mut stmt = db.prepare("SELECT item_id, stamp FROM item_content_state ORDER BY item_id")?;
+    let mut rows = stmt.query([])?;
+
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        out.push((row.get(0)?, row.get(1)?));
+    }
+    Ok(out)
+}
+
+// Returns (item_id, body) ordered by FTS relevance; `match_expression` must already be
+// valid FTS5 syntax.
+pub fn search_item_content(
+    db: &Connection,
+    match_expression: &str,
+    limit: usize,
+) -> Result<Vec<(String, String)>, StoreError> {
+    if match_expression.trim().is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = db.prepare(
+        "SELECT item_id, body FROM item_content
+         WHERE item_content MATCH ?1
+         ORDER BY rank
+         LIMIT ?2",
+    )?;
+    let mut rows = stmt.query(params![match_expression, limit as i64])?;
+
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        out.push((row.get(0)?, row.get(1)?));
+    }
+    Ok(out)
+}
+
 pub fn get_meta(db: &Connection, key: &str) -> Result<Option<String>, StoreError> {
     let mut stmt = db.prepare("SELECT value FROM index_meta WHERE key = ?1")?;
     let mut rows = stmt.query(params![key])?;
@@ -150,6 +556,22 @@ pub fn get_meta(db: &Connection, key: &str) -> Result<Option<String>, StoreError
     }
 }
 
+pub fn list_meta_with_prefix(
+    db: &Connection,
+    prefix: &str,
+) -> Result<Vec<(String, String)>, StoreError> {
+    let mut stmt = db.prepare(
+        "SELECT key, value FROM index_meta WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key",
+    )?;
+    let mut rows = stmt.query(params![prefix])?;
+
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        out.push((row.get(0)?, row.get(1)?));
+    }
+    Ok(out)
+}
+
 pub fn set_meta(db: &Connection, key: &str, value: &str) -> Result<(), StoreError> {
     db.execute(
         "INSERT INTO index_meta (key, value) VALUES (?1, ?2)
@@ -177,6 +599,58 @@ pub fn record_query_selection(
     Ok(())
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuerySelectionRecord {
+    pub query_norm: String,
+    pub mode: String,
+    pub item_id: String,
+    pub selected_count: i64,
+    pub last_selected_epoch_secs: i64,
+}
+
+pub fn list_all_query_selections(db: &Connection) -> Result<Vec<QuerySelectionRecord>, StoreError> {
+    let mut stmt = db.prepare(
+        "SELECT query_norm, mode, item_id, selected_count, last_selected_epoch_secs
+         FROM item_query_memory
+         ORDER BY query_norm, mode, item_id",
+    )?;
+    let mut rows = stmt.query([])?;
+
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        out.push(QuerySelectionRecord {
+            query_norm: row.get(0)?,
+            mode: row.get(1)?,
+            item_id: row.get(2)?,
+            selected_count: row.get(3)?,
+            last_selected_epoch_secs: row.get(4)?,
+        });
+    }
+    Ok(out)
+}
+
+// Keeps the stronger signal when the same selection already exists locally.
+pub fn merge_query_selection(
+    db: &Connection,
+    record: &QuerySelectionRecord,
+) -> Result<(), StoreError> {
+    db.execute(
+        "INSERT INTO item_query_memory (query_norm, mode, item_id, selected_count, last_selected_epoch_secs)
+         VALUES (?1, ?2, ?3, MIN(?4, 1000), ?5)
+         ON CONFLICT(query_norm, mode, item_id) DO UPDATE SET
+         selected_count = MAX(item_query_memory.selected_count, excluded.selected_count),
+         last_selected_epoch_secs = MAX(item_query_memory.last_selected_epoch_secs, excluded.last_selected_epoch_secs)",
+        params![
+            record.query_norm,
+            record.mode,
+            record.item_id,
+            record.selected_count,
+            record.last_selected_epoch_secs
+        ],
+    )?;
+    Ok(())
+}
+
 pub fn list_query_selections(
     db: &Connection,
     query_norm: &str,
@@ -203,29 +677,117 @@ pub fn list_query_selections(
     Ok(out)
 }
 
-fn init_schema(conn: &Connection) -> Result<(), StoreError> {
-    let current_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+// Changes whenever another connection commits to the database.
+pub fn data_version(db: &Connection) -> Result<i64, StoreError> {
+    Ok(db.query_row("PRAGMA data_version", [], |row| row.get(0))?)
+}
 
-    if current_version < 1 {
-        migration_v1(conn)?;
+pub fn schema_version(db: &Connection) -> Result<i64, StoreError> {
+    Ok(db.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+// Moves the schema up or down one registered step at a time, each in its own
+// transaction so an interrupted migration leaves a consistent version behind.
+pub fn migrate_schema(db: &Connection, target_version: i64) -> Result<(), StoreError> {
+    if !(0..=SCHEMA_VERSION).contains(&target_version) {
+        return Err(StoreError::UnknownSchemaVersion(target_version));
     }
-    if current_version < 2 {
-        migration_v2(conn)?;
+    let current_version = schema_version(db)?;
+    if current_version > SCHEMA_VERSION {
+        return Err(StoreError::SchemaTooNew {
+            found: current_version,
+            supported: SCHEMA_VERSION,
+        });
     }
-    if current_version < 3 {
-        migration_v3(conn)?;
+    if current_version < 0 {
+        return Err(StoreError::UnknownSchemaVersion(current_version));
     }
-    if current_version < 4 {
-        migration_v4(conn)?;
+
+    if current_version < target_version {
+        for migration in MIGRATIONS
+            .iter()
+            .filter(|migration| migration.version > current_version)
+            .filter(|migration| migration.version <= target_version)
+        {
+            apply_migration_step(db, migration.up, migration.version)?;
+        }
+    } else {
+        for migration in MIGRATIONS
+            .iter()
+            .rev()
+            .filter(|migration| migration.version <= current_version)
+            .filter(|migration| migration.version > target_version)
+        {
+            apply_migration_step(db, migration.down, migration.version - 1)?;
+        }
     }
+    Ok(())
+}
+
+fn apply_migration_step(
+    db: &Connection,
+    step: fn(&Connection) -> Result<(), StoreError>,
+    resulting_version: i64,
+) -> Result<(), StoreError> {
+    let tx = db.unchecked_transaction()?;
+    step(&tx)?;
+    tx.pragma_update(None, "user_version", resulting_version)?;
+    tx.commit()?;
+    Ok(())
+}
 
-    if current_version < 4 {
-        conn.pragma_update(None, "user_version", 4_i64)?;
+// Backs up a populated database before changing its schema in either direction.
+pub fn migrate_file_schema(
+    db: &Connection,
+    path: &Path,
+    target_version: i64,
+) -> Result<Option<PathBuf>, StoreError> {
+    let current_version = schema_version(db)?;
+    if current_version == target_version {
+        return Ok(None);
     }
+    let backup = if current_version <= SCHEMA_VERSION && has_user_tables(db)? {
+        Some(backup_before_migration(db, path, current_version)?)
+    } else {
+        None
+    };
+    migrate_schema(db, target_version)?;
+    Ok(backup)
+}
 
+fn init_schema(conn: &Connection, path: &Path) -> Result<(), StoreError> {
+    if let Some(backup) = migrate_file_schema(conn, path, SCHEMA_VERSION)? {
+        crate::logging::info(&format!(
+            "[nex] index schema migrated to v{SCHEMA_VERSION}; backup at {}",
+            backup.display()
+        ));
+    }
     Ok(())
 }
 
+fn has_user_tables(db: &Connection) -> Result<bool, StoreError> {
+    let count: i64 = db.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn backup_before_migration(
+    db: &Connection,
+    path: &Path,
+    from_version: i64,
+) -> Result<PathBuf, StoreError> {
+    let backup = sibling_path(path, &format!("backup-v{from_version}"));
+    if backup.exists() {
+        std::fs::remove_file(&backup)?;
+    }
+    // VACUUM INTO produces a consistent copy even with pending WAL frames.
+    db.execute("VACUUM INTO ?1", params![backup.to_string_lossy()])?;
+    Ok(backup)
+}
+
 fn migration_v1(conn: &Connection) -> Result<(), StoreError> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS item (
@@ -294,3 +856,49 @@ fn migration_v4(conn: &Connection) -> Result<(), StoreError> {
     }
     Ok(())
 }
+
+fn migration_v5(conn: &Connection) -> Result<(), StoreError> {
+    conn.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS item_content USING fts5(
+            item_id UNINDEXED,
+            body,
+            tokenize = 'unicode61 remove_diacritics 2'
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_content_state (
+            item_id TEXT PRIMARY KEY,
+            stamp TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_v1_down(conn: &Connection) -> Result<(), StoreError> {
+    conn.execute("DROP TABLE IF EXISTS item", [])?;
+    Ok(())
+}
+
+fn migration_v2_down(conn: &Connection) -> Result<(), StoreError> {
+    conn.execute("DROP TABLE IF EXISTS index_meta", [])?;
+    Ok(())
+}
+
+fn migration_v3_down(conn: &Connection) -> Result<(), StoreError> {
+    conn.execute("DROP INDEX IF EXISTS idx_item_query_memory_lookup", [])?;
+    conn.execute("DROP TABLE IF EXISTS item_query_memory", [])?;
+    Ok(())
+}
+
+fn migration_v4_down(conn: &Connection) -> Result<(), StoreError> {
+    conn.execute("ALTER TABLE item DROP COLUMN subtitle", [])?;
+    Ok(())
+}
+
+fn migration_v5_down(conn: &Connection) -> Result<(), StoreError> {
+    conn.execute("DROP TABLE IF EXISTS item_content", [])?;
+    conn.execute("DROP TABLE IF EXISTS item_content_state", [])?;
+    Ok(())
+}
@@ -0,0 +1,498 @@
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::mpsc::{self, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::core_service::{CoreService, ServiceError};
+use crate::events::EventBus;
+use crate::plugin_sdk::PluginRegistry;
+use crate::transport::{handle_line, LineReply};
+
+const MAX_REQUEST_LINE_BYTES: u64 = 1024 * 1024;
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+#[derive(Debug)]
+pub enum IpcError {
+    Io(std::io::Error),
+    Service(ServiceError),
+}
+
+impl Display for IpcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "ipc io error: {error}"),
+            Self::Service(error) => write!(f, "ipc service error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for IpcError {}
+
+impl From<std::io::Error> for IpcError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ServiceError> for IpcError {
+    fn from(value: ServiceError) -> Self {
+        Self::Service(value)
+    }
+}
+
+pub trait IpcStream: Read + Write + Send {}
+
+impl<T: Read + Write + Send> IpcStream for T {}
+
+// One endpoint abstraction over Unix domain sockets and Windows named pipes.
+pub trait IpcListener: Send {
+    fn accept(&self) -> std::io::Result<Box<dyn IpcStream>>;
+}
+
+pub fn default_endpoint() -> String {
+    platform::default_endpoint()
+}
+
+pub fn bind(endpoint: &str) -> std::io::Result<Box<dyn IpcListener>> {
+    platform::bind(endpoint)
+}
+
+pub fn connect(endpoint: &str) -> std::io::Result<Box<dyn IpcStream>> {
+    platform::connect(endpoint)
+}
+
+struct IpcCall {
+    line: String,
+    reply: Sender<LineReply>,
+}
+
+// Reloads travel the request channel, so they land between requests in arrival order.
+enum IpcMessage {
+    Call(IpcCall),
+    Config {
+        config: Box<Config>,
+        reconfigure_providers: bool,
+    },
+    Plugins(Box<PluginRegistry>),
+}
+
+pub struct IpcServer {
+    endpoint: String,
+    accept_thread: JoinHandle<()>,
+    messages: Sender<IpcMessage>,
+}
+
+impl IpcServer {
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    // Passes on a config reload the caller has already applied and announced on the
+    // shared event bus; the server's service adopts it without publishing again.
+    pub fn apply_config(&self, config: &Config, reconfigure_providers: bool) {
+        let _ = self.messages.send(IpcMessage::Config {
+            config: Box::new(config.clone()),
+            reconfigure_providers,
+        });
+    }
+
+    // Manifest reloads change what search presents and which action ids `Launch` accepts.
+    pub fn replace_plugins(&self, plugins: &PluginRegistry) {
+        let _ = self
+            .messages
+            .send(IpcMessage::Plugins(Box::new(plugins.clone())));
+    }
+
+    pub fn wait(self) {
+        let _ = self.accept_thread.join();
+    }
+}
+
+// `events` is shared with the in-process service so subscribers see its launches,
+// reloads and refreshes as well as the server's own. `plugins` is the caller's registry,
+// so manifests are not loaded a second time.
+pub fn start_server(
+    config: Config,
+    plugins: PluginRegistry,
+    endpoint: &str,
+    events: EventBus,
+) -> Result<IpcServer, IpcError> {
+    let listener = bind(endpoint)?;
+    serve(listener, endpoint, move || {
+        Ok(CoreService::new(config)?
+            .with_runtime_providers()
+            .with_plugins(plugins)
+            .with_event_bus(events))
+    })
+}
+
+// The service lives on its own thread because SQLite connections are not shareable;
+// client threads only parse lines and wait for their reply, so clients never block
+// each other on I/O and requests are applied one at a time.
+pub fn serve<F>(
+    listener: Box<dyn IpcListener>,
+    endpoint: &str,
+    make_service: F,
+) -> Result<IpcServer, IpcError>
+where
+    F: FnOnce() -> Result<CoreService, ServiceError> + Send + 'static,
+{
+    let (call_tx, call_rx) = mpsc::channel::<IpcMessage>();
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<(), ServiceError>>();
+    std::thread::spawn(move || {
+        let service = match make_service() {
+            Ok(service) => {
+                let _ = ready_tx.send(Ok(()));
+                service
+            }
+            Err(error) => {
+                let _ = ready_tx.send(Err(error));
+                return;
+            }
+        };
+        for message in call_rx {
+            let call = match message {
+                IpcMessage::Call(call) => call,
+                IpcMessage::Config {
+                    config,
+                    reconfigure_providers,
+                } => {
+                    if let Err(error) = service.adopt_config(&config, reconfigure_providers) {
+                        crate::logging::warn(&format!("[nex] ipc config reload failed: {error}"));
+                    }
+                    continue;
+                }
+                IpcMessage::Plugins(plugins) => {
+                    service.replace_plugins(*plugins);
+                    continue;
+                }
+            };
+            if let Err(error) = service.refresh_cache_if_changed() {
+                crate::logging::warn(&format!("[nex] ipc cache refresh failed: {error}"));
+            }
+            let _ = call.reply.send(handle_line(&service, &call.line));
+        }
+    });
+    match ready_rx.recv() {
+        Ok(Ok(())) => {}
+        Ok(Err(error)) => return Err(IpcError::Service(error)),
+        Err(_) => {
+            return Err(IpcError::Io(std::io::Error::other(
+                "ipc service thread exited during startup",
+            )))
+        }
+    }
+
+    let accept_endpoint = endpoint.to_string();
+    let messages = call_tx.clone();
+    let accept_thread = std::thread::spawn(move || loop {
+        match listener.accept() {
+            Ok(stream) => {
+                let calls = call_tx.clone();
+                std::thread::spawn(move || serve_client(stream, calls));
+            }
+            Err(error) => {
+                crate::logging::warn(&format!(
+                    "[nex] ipc accept failed on {accept_endpoint}: {error}"
+                ));
+                std::thread::sleep(ACCEPT_RETRY_DELAY);
+            }
+        }
+    });
+
+    crate::logging::info(&format!("[nex] ipc server listening on {endpoint}"));
+    Ok(IpcServer {
+        endpoint: endpoint.to_string(),
+        accept_thread,
+        messages,
+    })
+}
+
+fn serve_client(stream: Box<dyn IpcStream>, calls: Sender<IpcMessage>) {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader
+            .by_ref()
+            .take(MAX_REQUEST_LINE_BYTES)
+            .read_line(&mut line)
+        {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        // An unterminated line at the size cap is an oversized request, not EOF.
+        if !line.ends_with('\n') && line.len() as u64 >= MAX_REQUEST_LINE_BYTES {
+            return;
+        }
+        let request = line.trim();
+        if request.is_empty() {
+            continue;
+        }
+
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let call = IpcCall {
+            line: request.to_string(),
+            reply: reply_tx,
+        };
+        if calls.send(IpcMessage::Call(call)).is_err() {
+            return;
+        }
+        let Ok(reply) = reply_rx.recv() else {
+            return;
+        };
+        if let Some(response) = reply.response {
+            if write_line(reader.get_mut(), &response).is_err() {
+                return;
+            }
+        }
+        if let Some(events) = reply.events {
+            // The client goes away when a write fails; its receiver is then dropped.
+            while let Some(event) = events.next_line() {
+                if write_line(reader.get_mut(), &event).is_err() {
+                    return;
+                }
+            }
+            return;
+        }
+    }
+}
+
+fn write_line(stream: &mut Box<dyn IpcStream>, line: &str) -> std::io::Result<()> {
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\n")?;
+    stream.flush()
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::io::ErrorKind;
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+
+    use super::{IpcListener, IpcStream};
+
+    pub fn default_endpoint() -> String {
+        crate::config::stable_app_data_dir()
+            .join("nex.sock")
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    struct UnixIpcListener(UnixListener);
+
+    impl IpcListener for UnixIpcListener {
+        fn accept(&self) -> std::io::Result<Box<dyn IpcStream>> {
+            let (stream, _) = self.0.accept()?;
+            Ok(Box::new(stream))
+        }
+    }
+
+    pub fn bind(endpoint: &str) -> std::io::Result<Box<dyn IpcListener>> {
+        let path = Path::new(endpoint);
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(std::io::Error::new(
+                    ErrorKind::AddrInUse,
+                    format!("another instance is serving {endpoint}"),
+                ));
+            }
+            // Left behind by a process that exited without cleanup.
+            std::fs::remove_file(path)?;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(Box::new(UnixIpcListener(listener)))
+    }
+
+    pub fn connect(endpoint: &str) -> std::io::Result<Box<dyn IpcStream>> {
+        Ok(Box::new(UnixStream::connect(endpoint)?))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::sync::Mutex;
+
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_BROKEN_PIPE, ERROR_PIPE_CONNECTED, GENERIC_READ,
+        GENERIC_WRITE, HANDLE, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, ReadFile, WriteFile, FILE_FLAG_FIRST_PIPE_INSTANCE, OPEN_EXISTING,
+        PIPE_ACCESS_DUPLEX,
+    };
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_READMODE_BYTE,
+        PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    use super::{IpcListener, IpcStream};
+
+    const PIPE_BUFFER_BYTES: u32 = 64 * 1024;
+
+    pub fn default_endpoint() -> String {
+        let user = std::env::var("USERNAME").unwrap_or_else(|_| "default".to_string());
+        format!(r"\\.\pipe\nex-core-{user}")
+    }
+
+    struct PipeHandle {
+        handle: HANDLE,
+        server: bool,
+    }
+
+    // Pipe handles are plain kernel handles; each one is used by a single thread at a time.
+    unsafe impl Send for PipeHandle {}
+
+    impl Drop for PipeHandle {
+        fn drop(&mut self) {
+            unsafe {
+                if self.server {
+                    DisconnectNamedPipe(self.handle);
+                }
+                CloseHandle(self.handle);
+            }
+        }
+    }
+
+    impl std::io::Read for PipeHandle {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let mut read = 0_u32;
+            let ok = unsafe {
+                ReadFile(
+                    self.handle,
+                    buf.as_mut_ptr(),
+                    buf.len().min(u32::MAX as usize) as u32,
+                    &mut read,
+                    std::ptr::null_mut(),
+                )
+            };
+            if ok == 0 {
+                let error = unsafe { GetLastError() };
+                if error == ERROR_BROKEN_PIPE {
+                    return Ok(0);
+                }
+                return Err(std::io::Error::from_raw_os_error(error as i32));
+            }
+            Ok(read as usize)
+        }
+    }
+
+    impl std::io::Write for PipeHandle {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let mut written = 0_u32;
+            let ok = unsafe {
+                WriteFile(
+                    self.handle,
+                    buf.as_ptr(),
+                    buf.len().min(u32::MAX as usize) as u32,
+                    &mut written,
+                    std::ptr::null_mut(),
+                )
+            };
+            if ok == 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(written as usize)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct PipeListener {
+        name: Vec<u16>,
+        // The first instance is created in `bind` so a second server fails fast.
+        pending: Mutex<Option<PipeHandle>>,
+    }
+
+    impl IpcListener for PipeListener {
+        fn accept(&self) -> std::io::Result<Box<dyn IpcStream>> {
+            let pending = match self.pending.lock() {
+                Ok(mut guard) => guard.take(),
+                Err(poisoned) => poisoned.into_inner().take(),
+            };
+            let pipe = match pending {
+                Some(pipe) => pipe,
+                None => create_instance(&self.name, false)?,
+            };
+            let connected = unsafe { ConnectNamedPipe(pipe.handle, std::ptr::null_mut()) };
+            if connected == 0 {
+                let error = unsafe { GetLastError() };
+                if error != ERROR_PIPE_CONNECTED {
+                    return Err(std::io::Error::from_raw_os_error(error as i32));
+                }
+            }
+            Ok(Box::new(pipe))
+        }
+    }
+
+    fn create_instance(name: &[u16], first: bool) -> std::io::Result<PipeHandle> {
+        let open_mode = if first {
+            PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE
+        } else {
+            PIPE_ACCESS_DUPLEX
+        };
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                open_mode,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                PIPE_BUFFER_BYTES,
+                PIPE_BUFFER_BYTES,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(PipeHandle {
+            handle,
+            server: true,
+        })
+    }
+
+    pub fn bind(endpoint: &str) -> std::io::Result<Box<dyn IpcListener>> {
+        let name = to_wide(endpoint);
+        let first = create_instance(&name, true)?;
+        Ok(Box::new(PipeListener {
+            name,
+            pending: Mutex::new(Some(first)),
+        }))
+    }
+
+    pub fn connect(endpoint: &str) -> std::io::Result<Box<dyn IpcStream>> {
+        let name = to_wide(endpoint);
+        let handle = unsafe {
+            CreateFileW(
+                name.as_ptr(),
+                GENERIC_READ | GENERIC_WRITE,
+                0,
+                std::ptr::null(),
+                OPEN_EXISTING,
+                0,
+                std::ptr::null_mut(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Box::new(PipeHandle {
+            handle,
+            server: false,
+        }))
+    }
+
+    fn to_wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+}
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::contract::CoreRequest;
+use crate::core_service::CoreService;
+use crate::events::{CoreEvent, EVENT_NAMES};
+use crate::transport::{map_service_error, reply, ErrorCode, EventFraming, EventStream, LineReply};
+
+pub const JSONRPC_VERSION: &str = "2.0";
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const INITIALIZE_METHOD: &str = "initialize";
+pub const SUBSCRIBE_METHOD: &str = "subscribe";
+pub const EVENT_METHOD: &str = "event";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ServerInfo {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ServerCapabilities {
+    pub batch: bool,
+    pub notifications: bool,
+    pub legacy_envelope: bool,
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InitializeResult {
+    pub protocol_version: u32,
+    pub server: ServerInfo,
+    pub request_kinds: Vec<String>,
+    pub methods: Vec<String>,
+    pub capabilities: ServerCapabilities,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct InitializeParams {
+    protocol_version: Option<u32>,
+}
+
+// Server-defined errors live in the JSON-RPC reserved -32000..-32099 range; the
+// transport `ErrorCode` string is kept in `data.code` so existing clients can match on it.
+pub fn error_code(code: &ErrorCode) -> i64 {
+    match code {
+        ErrorCode::InvalidJson => PARSE_ERROR,
+        ErrorCode::InvalidRequest => INVALID_PARAMS,
+        ErrorCode::ItemNotFound => -32001,
+        ErrorCode::Launch => -32002,
+        ErrorCode::Store => -32003,
+        ErrorCode::Config => -32004,
+        ErrorCode::Provider => -32005,
+        ErrorCode::ReadOnly => -32006,
+    }
+}
+
+// `Search` -> `search`, `RebuildIndex` -> `rebuild_index`.
+pub fn method_name(kind: &str) -> String {
+    let mut method = String::with_capacity(kind.len() + 4);
+    for (index, ch) in kind.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if index > 0 {
+                method.push('_');
+            }
+            method.push(ch.to_ascii_lowercase());
+        } else {
+            method.push(ch);
+        }
+    }
+    method
+}
+
+pub fn initialize_result(protocol_version: u32) -> InitializeResult {
+    let mut methods = vec![INITIALIZE_METHOD.to_string()];
+    methods.extend(CoreRequest::KINDS.iter().map(|kind| method_name(kind)));
+    InitializeResult {
+        protocol_version,
+        server: ServerInfo {
+            name: "nex".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+        request_kinds: CoreRequest::KINDS
+            .iter()
+            .map(|kind| kind.to_string())
+            .collect(),
+        methods,
+        capabilities: ServerCapabilities {
+            batch: true,
+            notifications: true,
+            legacy_envelope: true,
+            events: EVENT_NAMES.iter().map(|name| name.to_string()).collect(),
+        },
+    }
+}
+
+pub fn is_jsonrpc_message(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => object.contains_key("jsonrpc"),
+        Value::Array(items) => items.first().is_some_and(is_jsonrpc_message),
+        _ => false,
+    }
+}
+
+pub fn parse_error_response(message: String) -> String {
+    encode(&error_response(Value::Null, PARSE_ERROR, message, None))
+}
+
+// `{"jsonrpc":"2.0","method":"event","params":{"event":"<name>","data":{...}}}`
+pub fn event_notification(event: &CoreEvent) -> String {
+    serde_json::json!({
+        "jsonrpc": JSONRPC_VERSION,
+        "method": EVENT_METHOD,
+        "params": event,
+    })
+    .to_string()
+}
+
+// The reply has no response when nothing should be written back (notifications, or a
+// batch of them). Only a single `subscribe` request with an id opens an event stream.
+pub fn handle_message(service: &CoreService, message: Value) -> LineReply {
+    match message {
+        Value::Array(items) => {
+            if items.is_empty() {
+                return reply(Some(encode(&error_response(
+                    Value::Null,
+                    INVALID_REQUEST,
+                    "empty batch".to_string(),
+                    None,
+                ))));
+            }
+            let responses = items
+                .into_iter()
+                .filter_map(|item| handle_single(service, item))
+                .collect::<Vec<_>>();
+            if responses.is_empty() {
+                return reply(None);
+            }
+            reply(Some(
+                serde_json::to_string(&responses).expect("json-rpc batch should serialize"),
+            ))
+        }
+        single => {
+            let subscribing =
+                single.get("method").and_then(Value::as_str) == Some(SUBSCRIBE_METHOD);
+            let Some(response) = handle_single(service, single) else {
+                return reply(None);
+            };
+            let events = response
+                .result
+                .as_ref()
+                .filter(|_| subscribing)
+                .and_then(|result| result.get("events"))
+                .and_then(|events| serde_json::from_value::<Vec<String>>(events.clone()).ok())
+                .map(|events| EventStream::new(service, events, EventFraming::JsonRpc));
+            LineReply {
+                response: Some(encode(&response)),
+                events,
+            }
+        }
+    }
+}
+
+fn handle_single(service: &CoreService, message: Value) -> Option<JsonRpcResponse> {
+    let Value::Object(mut object) = message else {
+        return Some(error_response(
+            Value::Null,
+            INVALID_REQUEST,
+            "request must be an object".to_string(),
+            None,
+        ));
+    };
+    let id = object.remove("id");
+    let reply_id = id.clone().unwrap_or(Value::Null);
+    if object.get("jsonrpc").and_then(Value::as_str) != Some(JSONRPC_VERSION) {
+        return Some(error_response(
+            reply_id,
+            INVALID_REQUEST,
+            format!("jsonrpc must be \"{JSONRPC_VERSION}\""),
+            None,
+        ));
+    }
+    let Some(method) = object
+        .get("method")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+    else {
+        return Some(error_response(
+            reply_id,
+            INVALID_REQUEST,
+            "method must be a string".to_string(),
+            None,
+        ));
+    };
+    let params = object.remove("params").unwrap_or(Value::Null);
+
+    let outcome = dispatch(service, &method, params);
+    // Notifications are executed but never answered.
+    id.as_ref()?;
+    Some(match outcome {
+        Ok(result) => JsonRpcResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: reply_id,
+            result: Some(result),
+            error: None,
+        },
+        Err(error) => JsonRpcResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: reply_id,
+            result: None,
+            error: Some(error),
+        },
+    })
+}
+
+fn dispatch(service: &CoreService, method: &str, params: Value) -> Result<Value, JsonRpcError> {
+    if method == INITIALIZE_METHOD {
+        let params = if params.is_null() {
+            InitializeParams::default()
+        } else {
+            serde_json::from_value::<InitializeParams>(params)
+                .map_err(|error| rpc_error(INVALID_PARAMS, error.to_string(), None))?
+        };
+        // Clients newer than the server are answered with the server's version.
+        let negotiated = params
+            .protocol_version
+            .map_or(PROTOCOL_VERSION, |requested| {
+                requested.min(PROTOCOL_VERSION)
+            });
+        if negotiated == 0 {
+            return Err(rpc_error(
+                INVALID_PARAMS,
+                "unsupported protocol_version 0".to_string(),
+                None,
+            ));
+        }
+        let result = initialize_result(negotiated);
+        return Ok(serde_json::to_value(result).expect("initialize should serialize"));
+    }
+
+    let Some(kind) = CoreRequest::KINDS
+        .iter()
+        .find(|kind| method_name(kind) == method)
+    else {
+        return Err(rpc_error(
+            METHOD_NOT_FOUND,
+            format!("unknown method: {method}"),
+            None,
+        ));
+    };
+    let request = serde_json::from_value::<CoreRequest>(serde_json::json!({
+        "kind": kind,
+        "payload": params,
+    }))
+    .map_err(|error| rpc_error(INVALID_PARAMS, error.to_string(), None))?;
+
+    match service.handle_command(request) {
+        Ok(response) => {
+            let mut encoded = serde_json::to_value(response)
+                .map_err(|error| rpc_error(INTERNAL_ERROR, error.to_string(), None))?;
+            Ok(encoded
+                .as_object_mut()
+                .and_then(|object| object.remove("payload"))
+                .unwrap_or(Value::Null))
+        }
+        Err(error) => {
+            let error = map_service_error(error);
+            let data = serde_json::json!({ "code": error.code });
+            Err(rpc_error(
+                error_code(&error.code),
+                error.message,
+                Some(data),
+            ))
+        }
+    }
+}
+
+fn rpc_error(code: i64, message: String, data: Option<Value>) -> JsonRpcError {
+    JsonRpcError {
+        code,
+        message,
+        data,
+    }
+}
+
+fn error_response(id: Value, code: i64, message: String, data: Option<Value>) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: JSONRPC_VERSION.to_string(),
+        id,
+        result: None,
+        error: Some(rpc_error(code, message, data)),
+    }
+}
+
+fn encode(response: &JsonRpcResponse) -> String {
+    serde_json::to_string(response).expect("json-rpc response should serialize")
+}
@@ -1,22 +1,33 @@
 pub mod action_executor;
 pub mod action_registry;
+pub mod client;
 pub mod clipboard_history;
 pub mod config;
+pub mod content_index;
 pub mod contract;
 pub mod core_service;
 pub mod discovery;
+pub mod events;
 pub mod hotkey;
 pub mod hotkey_runtime;
 pub mod index_store;
+pub mod ipc;
+pub mod jsonrpc;
 pub mod logging;
 pub mod model;
+pub mod overlay_search;
 pub mod overlay_state;
+pub mod plugin_actions;
+pub mod plugin_host;
+pub mod plugin_manifest;
+pub mod plugin_placeholders;
 pub mod plugin_sdk;
 pub mod query_dsl;
 pub mod runtime;
 pub mod search;
 pub mod settings;
 pub mod startup;
+pub mod state_archive;
 pub mod transport;
 pub mod uninstall_registry;
 #[cfg(target_os = "windows")]
@@ -1,3 +1,12 @@
+// An extra thing a front-end can offer for a result besides its default action. `id` is
+// selected like a result id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemAction {
+    pub id: String,
+    pub title: String,
+    pub icon: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SearchItem {
     pub id: String,
@@ -7,6 +16,10 @@ pub struct SearchItem {
     pub subtitle: String,
     pub use_count: u32,
     pub last_accessed_epoch_secs: i64,
+    pub snippet: String,
+    // A file path or a single glyph; empty lets the front-end pick one by `kind`.
+    pub icon: String,
+    pub secondary_actions: Vec<ItemAction>,
     normalized_title: String,
     normalized_search_text: String,
 }
@@ -61,6 +74,9 @@ impl SearchItem {
             subtitle,
             use_count,
             last_accessed_epoch_secs,
+            snippet: String::new(),
+            icon: String::new(),
+            secondary_actions: Vec::new(),
             normalized_title,
             normalized_search_text,
         }
@@ -79,6 +95,21 @@ impl SearchItem {
         self
     }
 
+    pub fn with_snippet(mut self, snippet: &str) -> Self {
+        self.snippet = snippet.to_string();
+        self
+    }
+
+    pub fn with_icon(mut self, icon: &str) -> Self {
+        self.icon = icon.to_string();
+        self
+    }
+
+    pub fn with_secondary_actions(mut self, actions: Vec<ItemAction>) -> Self {
+        self.secondary_actions = actions;
+        self
+    }
+
     pub fn normalized_title(&self) -> &str {
         &self.normalized_title
     }
@@ -0,0 +1,1148 @@
+use crate::action_registry::search_actions_with_mode;
+use crate::clipboard_history;
+use crate::config::Config;
+use crate::core_service::CoreService;
+use crate::plugin_sdk::{PluginRegistry, ProcessQuery, PROCESS_QUERY_BUDGET};
+use crate::query_dsl::ParsedQuery;
+use crate::search::SearchFilter;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+const QUERY_PROFILE_LOG_THRESHOLD_MS: u128 = 35;
+const MAX_PROCESS_PLUGIN_RESULTS: usize = 3;
+pub(crate) const SHORT_QUERY_APP_BIAS_MAX_LEN: usize = 2;
+const INDEXED_PREFIX_CACHE_MIN_QUERY_LEN: usize = 1;
+const INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT: usize = 120;
+const INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT: usize = 480;
+const FINAL_QUERY_CACHE_MAX_ENTRIES: usize = 32;
+const ADAPTIVE_INDEXED_LATENCY_WINDOW: usize = 24;
+
+#[derive(Debug, Default)]
+pub(crate) struct OverlaySearchSession {
+    indexed_prefix_cache: Option<IndexedPrefixCache>,
+    final_query_cache: HashMap<String, Vec<crate::model::SearchItem>>,
+    final_query_cache_lru: VecDeque<String>,
+    indexed_latency_ms: VecDeque<u128>,
+    process_answers: Option<(String, ProcessQuery)>,
+    // Called when a process plugin answers; without it the search waits for the answers.
+    pub(crate) process_wake: Option<fn()>,
+    // Survives `clear()`; only refreshed when an index refresh completes.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) degraded_providers: Vec<String>,
+}
+
+impl OverlaySearchSession {
+    pub(crate) fn clear(&mut self) {
+        self.clear_query_caches();
+        self.process_answers = None;
+    }
+
+    fn clear_query_caches(&mut self) {
+        self.indexed_prefix_cache = None;
+        self.final_query_cache.clear();
+        self.final_query_cache_lru.clear();
+        self.indexed_latency_ms.clear();
+    }
+
+    // True when process plugins answered the current query since the last search, so it
+    // is worth running again.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) fn poll_process_answers(&mut self) -> bool {
+        self.process_answers
+            .as_mut()
+            .is_some_and(|(_, pending)| pending.poll())
+    }
+
+    fn process_results(
+        &mut self,
+        key: &str,
+        start: impl FnOnce(fn()) -> ProcessQuery,
+    ) -> (Vec<crate::model::SearchItem>, bool) {
+        let Some(wake) = self.process_wake else {
+            let mut pending = start(|| {});
+            pending.wait(PROCESS_QUERY_BUDGET);
+            return (pending.items().to_vec(), true);
+        };
+        if self.process_answers.as_ref().map(|(k, _)| k.as_str()) != Some(key) {
+            self.process_answers = Some((key.to_string(), start(wake)));
+        }
+        let (_, pending) = self
+            .process_answers
+            .as_mut()
+            .expect("process answers were set");
+        pending.poll();
+        (pending.items().to_vec(), pending.is_complete())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IndexedPrefixCache {
+    normalized_query: String,
+    indexed_filter: SearchFilter,
+    seed_items: Vec<crate::model::SearchItem>,
+}
+
+// The overlay's search, shared with `plugin_sdk::PluginTestHarness`.
+pub(crate) fn search_overlay_results(
+    service: &CoreService,
+    cfg: &Config,
+    plugins: &PluginRegistry,
+    parsed_query: &ParsedQuery,
+    result_limit: usize,
+) -> Result<Vec<crate::model::SearchItem>, String> {
+    let mut session = OverlaySearchSession::default();
+    search_overlay_results_with_session(
+        service,
+        cfg,
+        plugins,
+        parsed_query,
+        result_limit,
+        &mut session,
+    )
+}
+
+pub(crate) fn search_overlay_results_with_session(
+    service: &CoreService,
+    cfg: &Config,
+    plugins: &PluginRegistry,
+    parsed_query: &ParsedQuery,
+    result_limit: usize,
+    session: &mut OverlaySearchSession,
+) -> Result<Vec<crate::model::SearchItem>, String> {
+    let mut results =
+        collect_overlay_results(service, cfg, plugins, parsed_query, result_limit, session)?;
+    plugins.present(&mut results);
+    Ok(results)
+}
+
+fn collect_overlay_results(
+    service: &CoreService,
+    cfg: &Config,
+    plugins: &PluginRegistry,
+    parsed_query: &ParsedQuery,
+    result_limit: usize,
+    session: &mut OverlaySearchSession,
+) -> Result<Vec<crate::model::SearchItem>, String> {
+    if result_limit == 0 {
+        return Ok(Vec::new());
+    }
+    if let Some(route) = plugins.route(parsed_query.raw.as_str()) {
+        session.clear_query_caches();
+        let (process_results, _) = session.process_results(&parsed_query.raw, |wake| {
+            ProcessQuery::start(
+                route.trigger.process.as_slice(),
+                &route.argument,
+                result_limit,
+                wake,
+            )
+        });
+        return Ok(plugins.scoped_results_with_answers(&route, process_results, result_limit));
+    }
+
+    let filter = build_search_filter(cfg, parsed_query);
+    let text_query = parsed_query.free_text.trim();
+    let normalized_query = crate::model::normalize_for_search(text_query);
+    if should_skip_non_searchable_query(parsed_query, &normalized_query) {
+        crate::logging::info(&format!(
+            "[nex] query_guard skip=non_searchable_symbol_only q=\"{}\"",
+            sanitize_query_for_profile_log(parsed_query.raw.as_str())
+        ));
+        session.clear();
+        return Ok(Vec::new());
+    }
+    if filter.content_query.is_some() {
+        // Content hits arrive ranked by the full-text index; re-ranking them alongside
+        // title-only sources would bury the best matches.
+        session.clear();
+        return service
+            .search_with_filter_uncapped(text_query, result_limit, &filter)
+            .map_err(|error| format!("content search failed: {error}"));
+    }
+    let cache_key = final_query_cache_key(parsed_query, &filter, &normalized_query, result_limit);
+    if let Some(cached) = cached_final_query_results(session, &cache_key) {
+        return Ok(cached);
+    }
+    let candidate_limit = candidate_limit_for_query(
+        result_limit,
+        &filter,
+        &normalized_query,
+        parsed_query.command_mode,
+    );
+    let base_indexed_seed_limit = indexed_seed_limit(candidate_limit, normalized_query.len());
+    let seed_cap = (cfg.index_max_items_per_query_seed as usize).max(candidate_limit);
+    let indexed_seed_limit = adaptive_indexed_seed_limit(
+        session,
+        candidate_limit,
+        normalized_query.len(),
+        base_indexed_seed_limit,
+    )
+    .min(seed_cap);
+    let short_query_app_bias =
+        should_use_short_query_app_mode(parsed_query, &filter, &normalized_query);
+    let mut indexed_filter = filter.clone();
+    if short_query_app_bias {
+        indexed_filter.mode = crate::config::SearchMode::Apps;
+    }
+
+    let search_started = Instant::now();
+    let mut merged = Vec::new();
+    let indexed_started = Instant::now();
+    let mut indexed_cache_hit = false;
+    let prefix_cache_eligible = is_prefix_cache_eligible_query(parsed_query, short_query_app_bias);
+    let indexed_seed_items = if let Some(cache) =
+        session.indexed_prefix_cache.as_ref().filter(|cache| {
+            can_use_indexed_prefix_cache(
+                cache,
+                prefix_cache_eligible,
+                &normalized_query,
+                &indexed_filter,
+            )
+        }) {
+        indexed_cache_hit = true;
+        crate::search::search_with_filter(
+            &cache.seed_items,
+            text_query,
+            indexed_seed_limit,
+            &indexed_filter,
+        )
+    } else {
+        service
+            .search_with_filter_uncapped(text_query, indexed_seed_limit, &indexed_filter)
+            .map_err(|error| format!("indexed search failed: {error}"))?
+    };
+    let indexed_ms = indexed_started.elapsed().as_millis();
+    if !indexed_cache_hit {
+        record_indexed_latency_sample(session, indexed_ms);
+    }
+    let indexed_count = indexed_seed_items.len();
+    merged.extend(indexed_seed_items.iter().take(candidate_limit).cloned());
+    if prefix_cache_eligible && normalized_query.len() >= INDEXED_PREFIX_CACHE_MIN_QUERY_LEN {
+        session.indexed_prefix_cache = Some(IndexedPrefixCache {
+            normalized_query: normalized_query.clone(),
+            indexed_filter: indexed_filter.clone(),
+            seed_items: indexed_seed_items,
+        });
+    } else {
+        session.clear_query_caches();
+    }
+
+    let actions_started = Instant::now();
+    let mut action_items =
+        search_actions_with_mode(text_query, candidate_limit, parsed_query.command_mode, cfg);
+    let built_in_actions_count = action_items.len();
+    let mut plugin_action_count = 0_usize;
+    if !plugins.action_items.is_empty() {
+        let plugin_actions = crate::search::search_with_filter(
+            &plugins.action_items,
+            text_query,
+            candidate_limit,
+            &SearchFilter {
+                mode: crate::config::SearchMode::Actions,
+                ..SearchFilter::default()
+            },
+        );
+        plugin_action_count = plugin_actions.len();
+        action_items.extend(plugin_actions);
+    }
+    let action_results =
+        crate::search::search_with_filter(&action_items, text_query, candidate_limit, &filter);
+    let actions_ms = actions_started.elapsed().as_millis();
+    let action_count = action_results.len();
+    merged.extend(action_results);
+
+    if matches!(
+        filter.mode,
+        crate::config::SearchMode::All | crate::config::SearchMode::Snippets
+    ) {
+        merged.extend(crate::search::search_with_filter(
+            &plugins.snippet_items,
+            text_query,
+            candidate_limit,
+            &filter,
+        ));
+    }
+
+    let mut clipboard_ms = 0_u128;
+    let mut clipboard_count = 0_usize;
+    if !short_query_app_bias {
+        let clipboard_started = Instant::now();
+        let clipboard_results =
+            clipboard_history::search_history(cfg, text_query, &filter, candidate_limit.min(120));
+        clipboard_ms = clipboard_started.elapsed().as_millis();
+        clipboard_count = clipboard_results.len();
+        merged.extend(clipboard_results);
+    }
+
+    let process_started = Instant::now();
+    let (process_results, process_complete) = if matches!(
+        filter.mode,
+        crate::config::SearchMode::All | crate::config::SearchMode::Actions
+    ) {
+        session.process_results(&parsed_query.raw, |wake| {
+            plugins.start_process_query(text_query, MAX_PROCESS_PLUGIN_RESULTS, wake)
+        })
+    } else {
+        (Vec::new(), true)
+    };
+    let process_ms = process_started.elapsed().as_millis();
+    let process_count = process_results.len();
+
+    let rank_started = Instant::now();
+    merged.extend(process_results.iter().cloned());
+    let mut ranked = crate::search::search_with_filter(&merged, text_query, result_limit, &filter);
+    pin_command_with_arguments(service, cfg, parsed_query, &mut ranked, result_limit);
+    append_process_plugin_results(&mut ranked, process_results, result_limit);
+    let rank_ms = rank_started.elapsed().as_millis();
+    let total_ms = search_started.elapsed().as_millis();
+    if total_ms >= QUERY_PROFILE_LOG_THRESHOLD_MS {
+        crate::logging::info(&format!(
+            "[nex] query_profile q=\"{}\" mode={} candidate_limit={} indexed_seed_limit={} short_app_bias={} indexed_cache_hit={} indexed_count={} indexed_ms={} action_count={} action_ms={} built_in_actions={} plugin_actions={} process_count={} process_ms={} clipboard_count={} clipboard_ms={} rank_ms={} total_ms={}",
+            sanitize_query_for_profile_log(text_query),
+            format!("{:?}", filter.mode).to_ascii_lowercase(),
+            candidate_limit,
+            indexed_seed_limit,
+            short_query_app_bias,
+            indexed_cache_hit,
+            indexed_count,
+            indexed_ms,
+            action_count,
+            actions_ms,
+            built_in_actions_count,
+            plugin_action_count,
+            process_count,
+            process_ms,
+            clipboard_count,
+            clipboard_ms,
+            rank_ms,
+            total_ms
+        ));
+    }
+    if process_complete {
+        store_final_query_results(session, cache_key, ranked.as_slice());
+    }
+    Ok(ranked)
+}
+
+// Process answers the ranking left out (a calculator's `= 42`) take the tail of the list.
+fn append_process_plugin_results(
+    ranked: &mut Vec<crate::model::SearchItem>,
+    process_results: Vec<crate::model::SearchItem>,
+    result_limit: usize,
+) {
+    let unranked: Vec<_> = process_results
+        .into_iter()
+        .filter(|item| !ranked.iter().any(|ranked_item| ranked_item.id == item.id))
+        .take(MAX_PROCESS_PLUGIN_RESULTS)
+        .collect();
+    if unranked.is_empty() {
+        return;
+    }
+    let reserved = unranked.len().min(result_limit);
+    ranked.truncate(result_limit - reserved);
+    ranked.extend(unranked.into_iter().take(reserved));
+}
+
+// "code ." should still surface the `code` command; its arguments are not search terms.
+fn pin_command_with_arguments(
+    service: &CoreService,
+    cfg: &Config,
+    parsed_query: &ParsedQuery,
+    ranked: &mut Vec<crate::model::SearchItem>,
+    result_limit: usize,
+) {
+    if !cfg.path_executables_enabled || parsed_query.command_mode {
+        return;
+    }
+    let Some((name, rest)) = parsed_query.raw.trim().split_once(char::is_whitespace) else {
+        return;
+    };
+    if rest.trim().is_empty() {
+        return;
+    }
+    let filter = SearchFilter {
+        kind_filter: Some("command".to_string()),
+        ..SearchFilter::default()
+    };
+    let Ok(candidates) = service.search_with_filter_uncapped(name, 8, &filter) else {
+        return;
+    };
+    let Some(command) = candidates
+        .into_iter()
+        .find(|item| item.title.eq_ignore_ascii_case(name))
+    else {
+        return;
+    };
+    ranked.retain(|item| item.id != command.id);
+    ranked.insert(0, command);
+    ranked.truncate(result_limit);
+}
+
+fn build_search_filter(cfg: &Config, parsed_query: &ParsedQuery) -> SearchFilter {
+    let mode = resolved_mode_for_query(cfg, parsed_query);
+    SearchFilter {
+        mode,
+        kind_filter: parsed_query.kind_filter.clone(),
+        extension_filter: parsed_query.extension_filter.clone(),
+        include_files: cfg.show_files,
+        include_folders: cfg.show_folders,
+        include_groups: parsed_query.include_groups.clone(),
+        exclude_terms: parsed_query.exclude_terms.clone(),
+        modified_within: parsed_query.modified_within,
+        created_within: parsed_query.created_within,
+        content_query: parsed_query.content_query.clone(),
+    }
+}
+
+pub(crate) fn resolved_mode_for_query(
+    cfg: &Config,
+    parsed_query: &ParsedQuery,
+) -> crate::config::SearchMode {
+    let mut mode = parsed_query
+        .mode_override
+        .unwrap_or(cfg.search_mode_default);
+    if parsed_query.command_mode {
+        mode = crate::config::SearchMode::Actions;
+    }
+    mode
+}
+
+fn should_use_short_query_app_mode(
+    parsed_query: &ParsedQuery,
+    filter: &SearchFilter,
+    normalized_query: &str,
+) -> bool {
+    if normalized_query.is_empty() || normalized_query.len() > SHORT_QUERY_APP_BIAS_MAX_LEN {
+        return false;
+    }
+    if parsed_query.command_mode {
+        return false;
+    }
+    if filter.mode != crate::config::SearchMode::All {
+        return false;
+    }
+    parsed_query.kind_filter.is_none()
+        && parsed_query.extension_filter.is_none()
+        && parsed_query.exclude_terms.is_empty()
+        && parsed_query.modified_within.is_none()
+        && parsed_query.created_within.is_none()
+        && parsed_query.content_query.is_none()
+}
+
+fn should_skip_non_searchable_query(parsed_query: &ParsedQuery, normalized_query: &str) -> bool {
+    if !normalized_query.is_empty() {
+        return false;
+    }
+    if parsed_query.command_mode {
+        return false;
+    }
+    if parsed_query.mode_override.is_some() {
+        return false;
+    }
+    parsed_query.kind_filter.is_none()
+        && parsed_query.extension_filter.is_none()
+        && parsed_query.include_groups.is_empty()
+        && parsed_query.exclude_terms.is_empty()
+        && parsed_query.modified_within.is_none()
+        && parsed_query.created_within.is_none()
+}
+
+fn candidate_limit_for_query(
+    result_limit: usize,
+    filter: &SearchFilter,
+    normalized_query: &str,
+    command_mode: bool,
+) -> usize {
+    if result_limit == 0 {
+        return 0;
+    }
+
+    let base = result_limit.saturating_mul(6).max(60);
+    if command_mode || filter.mode == crate::config::SearchMode::Actions {
+        return result_limit
+            .saturating_mul(4)
+            .max(48)
+            .min(160)
+            .max(result_limit);
+    }
+
+    match normalized_query.len() {
+        0 => result_limit
+            .saturating_mul(2)
+            .max(24)
+            .min(64)
+            .max(result_limit),
+        1 => match filter.mode {
+            crate::config::SearchMode::All => result_limit
+                .saturating_mul(3)
+                .max(45)
+                .min(96)
+                .max(result_limit),
+            crate::config::SearchMode::Files => result_limit
+                .saturating_mul(5)
+                .max(70)
+                .min(200)
+                .max(result_limit),
+            _ => result_limit
+                .saturating_mul(4)
+                .max(56)
+                .min(180)
+                .max(result_limit),
+        },
+        2 => match filter.mode {
+            crate::config::SearchMode::All => result_limit
+                .saturating_mul(4)
+                .max(56)
+                .min(140)
+                .max(result_limit),
+            crate::config::SearchMode::Files => result_limit
+                .saturating_mul(5)
+                .max(70)
+                .min(200)
+                .max(result_limit),
+            _ => result_limit
+                .saturating_mul(4)
+                .max(56)
+                .min(180)
+                .max(result_limit),
+        },
+        _ => base.min(280).max(result_limit),
+    }
+}
+
+fn indexed_seed_limit(candidate_limit: usize, normalized_query_len: usize) -> usize {
+    let multiplier = match normalized_query_len {
+        0 | 1 => 4,
+        2 => 2,
+        _ => 2,
+    };
+    candidate_limit.saturating_mul(multiplier).clamp(
+        INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT,
+        INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT,
+    )
+}
+
+fn adaptive_indexed_seed_limit(
+    session: &OverlaySearchSession,
+    candidate_limit: usize,
+    normalized_query_len: usize,
+    base_seed_limit: usize,
+) -> usize {
+    let mut samples: Vec<u128> = session.indexed_latency_ms.iter().copied().collect();
+    if samples.len() < 6 {
+        return base_seed_limit;
+    }
+
+    let p95 = percentile_u128(&mut samples, 0.95);
+    let scaled = if p95 >= 160 {
+        (base_seed_limit.saturating_mul(60)) / 100
+    } else if p95 >= 120 {
+        (base_seed_limit.saturating_mul(72)) / 100
+    } else if p95 >= 95 {
+        (base_seed_limit.saturating_mul(84)) / 100
+    } else if p95 <= 50 && normalized_query_len >= 3 {
+        (base_seed_limit.saturating_mul(108)) / 100
+    } else {
+        base_seed_limit
+    };
+
+    let minimum = candidate_limit.max(INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT / 2);
+    scaled.clamp(minimum, INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT)
+}
+
+fn record_indexed_latency_sample(session: &mut OverlaySearchSession, indexed_ms: u128) {
+    session.indexed_latency_ms.push_back(indexed_ms);
+    while session.indexed_latency_ms.len() > ADAPTIVE_INDEXED_LATENCY_WINDOW {
+        session.indexed_latency_ms.pop_front();
+    }
+}
+
+fn final_query_cache_key(
+    parsed_query: &ParsedQuery,
+    filter: &SearchFilter,
+    normalized_query: &str,
+    result_limit: usize,
+) -> String {
+    format!(
+        "q={};mode={:?};kind={};ext={};include={};exclude={};modified={:?};created={:?};cmd={};limit={}",
+        normalized_query,
+        filter.mode,
+        filter.kind_filter.as_deref().unwrap_or("-"),
+        filter.extension_filter.as_deref().unwrap_or("-"),
+        encode_term_groups(&filter.include_groups),
+        filter.exclude_terms.join(","),
+        filter.modified_within,
+        filter.created_within,
+        parsed_query.command_mode,
+        result_limit
+    )
+}
+
+fn encode_term_groups(groups: &[Vec<String>]) -> String {
+    if groups.is_empty() {
+        return "-".to_string();
+    }
+
+    groups
+        .iter()
+        .map(|group| group.join("+"))
+        .collect::<Vec<String>>()
+        .join("|")
+}
+
+fn cached_final_query_results(
+    session: &mut OverlaySearchSession,
+    key: &str,
+) -> Option<Vec<crate::model::SearchItem>> {
+    let cached = session.final_query_cache.get(key).cloned()?;
+    if let Some(position) = session
+        .final_query_cache_lru
+        .iter()
+        .position(|entry| entry == key)
+    {
+        session.final_query_cache_lru.remove(position);
+    }
+    session.final_query_cache_lru.push_back(key.to_string());
+    Some(cached)
+}
+
+fn store_final_query_results(
+    session: &mut OverlaySearchSession,
+    key: String,
+    results: &[crate::model::SearchItem],
+) {
+    if results.is_empty() {
+        return;
+    }
+
+    session
+        .final_query_cache
+        .insert(key.clone(), results.to_vec());
+    if let Some(position) = session
+        .final_query_cache_lru
+        .iter()
+        .position(|entry| entry == &key)
+    {
+        session.final_query_cache_lru.remove(position);
+    }
+    session.final_query_cache_lru.push_back(key);
+
+    while session.final_query_cache.len() > FINAL_QUERY_CACHE_MAX_ENTRIES {
+        let Some(oldest) = session.final_query_cache_lru.pop_front() else {
+            break;
+        };
+        session.final_query_cache.remove(&oldest);
+    }
+}
+
+fn can_use_indexed_prefix_cache(
+    cache: &IndexedPrefixCache,
+    prefix_cache_eligible: bool,
+    normalized_query: &str,
+    indexed_filter: &SearchFilter,
+) -> bool {
+    if !prefix_cache_eligible {
+        return false;
+    }
+    if cache.seed_items.is_empty() || cache.normalized_query.is_empty() {
+        return false;
+    }
+    if !indexed_filter_matches_for_prefix_cache(&cache.indexed_filter, indexed_filter) {
+        return false;
+    }
+    normalized_query.len() > cache.normalized_query.len()
+        && normalized_query.starts_with(&cache.normalized_query)
+}
+
+fn indexed_filter_matches_for_prefix_cache(a: &SearchFilter, b: &SearchFilter) -> bool {
+    a.mode == b.mode
+        && a.kind_filter == b.kind_filter
+        && a.extension_filter == b.extension_filter
+        && a.modified_within == b.modified_within
+        && a.created_within == b.created_within
+}
+
+fn is_prefix_cache_eligible_query(parsed_query: &ParsedQuery, short_query_app_bias: bool) -> bool {
+    if short_query_app_bias || parsed_query.command_mode {
+        return false;
+    }
+    if parsed_query.mode_override.is_some()
+        || parsed_query.kind_filter.is_some()
+        || parsed_query.extension_filter.is_some()
+        || !parsed_query.exclude_terms.is_empty()
+        || parsed_query.modified_within.is_some()
+        || parsed_query.created_within.is_some()
+    {
+        return false;
+    }
+    if parsed_query.free_text.trim().is_empty() {
+        return false;
+    }
+    parsed_query.raw.trim() == parsed_query.free_text.trim()
+}
+
+pub(crate) fn sanitize_query_for_profile_log(query: &str) -> String {
+    const MAX_QUERY_LOG_CHARS: usize = 48;
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return "-".to_string();
+    }
+    let mut cleaned = String::new();
+    for ch in trimmed.chars().take(MAX_QUERY_LOG_CHARS) {
+        if ch.is_control() {
+            cleaned.push(' ');
+        } else {
+            cleaned.push(ch);
+        }
+    }
+    cleaned.trim().to_string()
+}
+
+pub(crate) fn percentile_u128(values: &mut [u128], percentile: f64) -> u128 {
+    if values.is_empty() {
+        return 0;
+    }
+    values.sort_unstable();
+    let last = values.len().saturating_sub(1);
+    let idx = ((last as f64) * percentile.clamp(0.0, 1.0)).round() as usize;
+    values[idx.min(last)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        adaptive_indexed_seed_limit, append_process_plugin_results, can_use_indexed_prefix_cache,
+        candidate_limit_for_query, search_overlay_results, search_overlay_results_with_session,
+        should_skip_non_searchable_query, IndexedPrefixCache, OverlaySearchSession,
+        INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT, INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT,
+    };
+    use crate::action_registry::{ACTION_DIAGNOSTICS_BUNDLE_ID, ACTION_WEB_SEARCH_PREFIX};
+    use crate::config::{Config, SearchMode};
+    use crate::core_service::CoreService;
+    use crate::index_store::open_memory;
+    use crate::model::SearchItem;
+    use crate::plugin_sdk::PluginRegistry;
+    use crate::query_dsl::ParsedQuery;
+    use crate::search::SearchFilter;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn process_plugin_results_take_the_tail_of_a_full_list() {
+        let mut ranked = (0..5)
+            .map(|index| SearchItem::new(&format!("app-{index}"), "app", "App", "C:\\App.exe"))
+            .collect::<Vec<_>>();
+        let process_results = vec![
+            SearchItem::new("plugin:calc:result:a", "action", "4", "Calc"),
+            SearchItem::new("plugin:calc:result:b", "action", "Copy 4", "Calc"),
+        ];
+
+        append_process_plugin_results(&mut ranked, process_results, 5);
+
+        assert_eq!(ranked.len(), 5);
+        assert_eq!(ranked[2].id, "app-2");
+        assert_eq!(ranked[3].id, "plugin:calc:result:a");
+        assert_eq!(ranked[4].id, "plugin:calc:result:b");
+    }
+
+    #[test]
+    fn ranked_process_plugin_results_keep_their_place() {
+        let mut ranked = vec![
+            SearchItem::new("plugin:calc:result:a", "action", "Calculator", "Calc"),
+            SearchItem::new("app-0", "app", "Calc App", "C:\\Calc.exe"),
+        ];
+        let process_results = vec![
+            SearchItem::new("plugin:calc:result:a", "action", "Calculator", "Calc"),
+            SearchItem::new("plugin:calc:result:b", "action", "= 4", "Calc"),
+        ];
+
+        append_process_plugin_results(&mut ranked, process_results, 5);
+
+        let ids: Vec<&str> = ranked.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["plugin:calc:result:a", "app-0", "plugin:calc:result:b"]
+        );
+    }
+
+    #[test]
+    fn overlay_search_returns_ranked_results() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be valid")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("nex-overlay-search-{unique}.tmp"));
+        std::fs::write(&path, b"ok").expect("temp file should be created");
+
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        service
+            .upsert_item(&SearchItem::new(
+                "item-1",
+                "app",
+                "Visual Studio Code",
+                path.to_string_lossy().as_ref(),
+            ))
+            .expect("item should upsert");
+
+        let parsed = ParsedQuery::parse("code", true);
+        let cfg = Config::default();
+        let plugins = PluginRegistry::default();
+        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 20)
+            .expect("search should succeed");
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "item-1");
+
+        std::fs::remove_file(path).expect("temp file should be removed");
+    }
+
+    #[test]
+    fn command_with_arguments_is_pinned_only_while_path_executables_are_indexed() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be valid")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("nex-overlay-command-{unique}.tmp"));
+        std::fs::write(&path, b"ok").expect("temp file should be created");
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        service
+            .upsert_item(&SearchItem::new(
+                "command:code",
+                "command",
+                "code",
+                path.to_string_lossy().as_ref(),
+            ))
+            .expect("item should upsert");
+        let plugins = PluginRegistry::default();
+        let parsed = ParsedQuery::parse("code notes.txt", true);
+
+        let mut cfg = Config::default();
+        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 20)
+            .expect("search should succeed");
+        assert!(results.iter().all(|item| item.id != "command:code"));
+
+        cfg.path_executables_enabled = true;
+        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 20)
+            .expect("search should succeed");
+        assert_eq!(results[0].id, "command:code");
+
+        std::fs::remove_file(path).expect("temp file should be removed");
+    }
+
+    #[test]
+    fn command_mode_returns_action_results() {
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        let cfg = Config::default();
+        let plugins = PluginRegistry::default();
+        let parsed = ParsedQuery::parse(">diag", true);
+        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 10)
+            .expect("search should succeed");
+        assert!(results
+            .iter()
+            .any(|item| item.id == ACTION_DIAGNOSTICS_BUNDLE_ID));
+    }
+
+    #[test]
+    fn command_mode_includes_web_search_action() {
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        let cfg = Config::default();
+        let plugins = PluginRegistry::default();
+        let parsed = ParsedQuery::parse(">nex roadmap", true);
+        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 10)
+            .expect("search should succeed");
+        assert!(results
+            .iter()
+            .any(|item| item.id.starts_with(ACTION_WEB_SEARCH_PREFIX)));
+    }
+
+    #[test]
+    fn short_single_letter_query_in_all_mode_biases_to_apps() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be valid")
+            .as_nanos();
+        let app_path = std::env::temp_dir().join(format!("nex-short-query-app-{unique}.tmp"));
+        let file_path = std::env::temp_dir().join(format!("nex-short-query-file-{unique}.tmp"));
+        std::fs::write(&app_path, b"ok").expect("app temp file should be created");
+        std::fs::write(&file_path, b"ok").expect("file temp file should be created");
+
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        service
+            .upsert_item(&SearchItem::new(
+                "app-1",
+                "app",
+                "Vivaldi Browser",
+                app_path.to_string_lossy().as_ref(),
+            ))
+            .expect("app should upsert");
+        service
+            .upsert_item(&SearchItem::new(
+                "file-1",
+                "file",
+                "Vacation Notes",
+                file_path.to_string_lossy().as_ref(),
+            ))
+            .expect("file should upsert");
+
+        let parsed = ParsedQuery::parse("v", true);
+        let cfg = Config::default();
+        let plugins = PluginRegistry::default();
+        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 20)
+            .expect("search should succeed");
+        assert!(results.iter().any(|item| item.id == "app-1"));
+        assert!(!results.iter().any(|item| item.id == "file-1"));
+
+        std::fs::remove_file(app_path).expect("app temp file should be removed");
+        std::fs::remove_file(file_path).expect("file temp file should be removed");
+    }
+
+    #[test]
+    fn short_two_letter_query_in_all_mode_biases_to_apps() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be valid")
+            .as_nanos();
+        let app_path = std::env::temp_dir().join(format!("nex-short-two-app-{unique}.tmp"));
+        let file_path = std::env::temp_dir().join(format!("nex-short-two-file-{unique}.tmp"));
+        std::fs::write(&app_path, b"ok").expect("app temp file should be created");
+        std::fs::write(&file_path, b"ok").expect("file temp file should be created");
+
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        service
+            .upsert_item(&SearchItem::new(
+                "app-1",
+                "app",
+                "Valorant",
+                app_path.to_string_lossy().as_ref(),
+            ))
+            .expect("app should upsert");
+        service
+            .upsert_item(&SearchItem::new(
+                "file-1",
+                "file",
+                "Valuation Notes",
+                file_path.to_string_lossy().as_ref(),
+            ))
+            .expect("file should upsert");
+
+        let parsed = ParsedQuery::parse("va", true);
+        let cfg = Config::default();
+        let plugins = PluginRegistry::default();
+        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 20)
+            .expect("search should succeed");
+        assert!(results.iter().any(|item| item.id == "app-1"));
+        assert!(!results.iter().any(|item| item.id == "file-1"));
+
+        std::fs::remove_file(app_path).expect("app temp file should be removed");
+        std::fs::remove_file(file_path).expect("file temp file should be removed");
+    }
+
+    #[test]
+    fn skips_non_searchable_symbol_only_query() {
+        let parsed = ParsedQuery::parse("-", true);
+        let normalized = crate::model::normalize_for_search(parsed.free_text.trim());
+        assert!(should_skip_non_searchable_query(&parsed, &normalized));
+
+        let parsed_command = ParsedQuery::parse(">-", true);
+        let normalized_command =
+            crate::model::normalize_for_search(parsed_command.free_text.trim());
+        assert!(!should_skip_non_searchable_query(
+            &parsed_command,
+            &normalized_command
+        ));
+    }
+
+    #[test]
+    fn candidate_limit_adapts_to_query_shape() {
+        let all = SearchFilter::default();
+        let empty_all = candidate_limit_for_query(20, &all, "", false);
+        let short_all = candidate_limit_for_query(20, &all, "v", false);
+        let medium_all = candidate_limit_for_query(20, &all, "vi", false);
+        let long_all = candidate_limit_for_query(20, &all, "vivaldi", false);
+        assert!(empty_all <= short_all);
+        assert!(short_all < medium_all);
+        assert!(medium_all <= long_all);
+
+        let actions = SearchFilter {
+            mode: SearchMode::Actions,
+            ..SearchFilter::default()
+        };
+        let short_actions = candidate_limit_for_query(20, &actions, "v", true);
+        assert!(short_actions < long_all);
+    }
+
+    #[test]
+    fn prefix_cache_predicate_requires_same_filter_and_extended_query() {
+        let cache = IndexedPrefixCache {
+            normalized_query: "vi".to_string(),
+            indexed_filter: SearchFilter::default(),
+            seed_items: vec![SearchItem::new(
+                "app-1",
+                "app",
+                "Vivaldi",
+                "C:\\Vivaldi.exe",
+            )],
+        };
+
+        assert!(can_use_indexed_prefix_cache(
+            &cache,
+            true,
+            "viv",
+            &SearchFilter::default()
+        ));
+        assert!(!can_use_indexed_prefix_cache(
+            &cache,
+            true,
+            "vi",
+            &SearchFilter::default()
+        ));
+        assert!(!can_use_indexed_prefix_cache(
+            &cache,
+            true,
+            "xvi",
+            &SearchFilter::default()
+        ));
+
+        let different_mode = SearchFilter {
+            mode: SearchMode::Apps,
+            ..SearchFilter::default()
+        };
+        assert!(!can_use_indexed_prefix_cache(
+            &cache,
+            true,
+            "viv",
+            &different_mode
+        ));
+        assert!(!can_use_indexed_prefix_cache(
+            &cache,
+            false,
+            "viv",
+            &SearchFilter::default()
+        ));
+    }
+
+    #[test]
+    fn repeated_overlay_query_uses_final_cache() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be valid")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("nex-overlay-cache-{unique}.tmp"));
+        std::fs::write(&path, b"ok").expect("temp file should be created");
+
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        service
+            .upsert_item(&SearchItem::new(
+                "item-1",
+                "app",
+                "Vivaldi",
+                path.to_string_lossy().as_ref(),
+            ))
+            .expect("item should upsert");
+
+        let cfg = Config::default();
+        let plugins = PluginRegistry::default();
+        let parsed = ParsedQuery::parse("vi", true);
+        let mut session = OverlaySearchSession::default();
+
+        let first = search_overlay_results_with_session(
+            &service,
+            &cfg,
+            &plugins,
+            &parsed,
+            20,
+            &mut session,
+        )
+        .expect("first query should succeed");
+        let sample_count_after_first = session.indexed_latency_ms.len();
+
+        let second = search_overlay_results_with_session(
+            &service,
+            &cfg,
+            &plugins,
+            &parsed,
+            20,
+            &mut session,
+        )
+        .expect("second query should succeed");
+
+        assert_eq!(first, second);
+        assert_eq!(session.indexed_latency_ms.len(), sample_count_after_first);
+        assert!(!session.final_query_cache.is_empty());
+
+        std::fs::remove_file(path).expect("temp file should be removed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn slow_process_plugins_answer_a_later_search() {
+        use crate::plugin_host::{ProcessPlugin, ProcessPluginSpec};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::time::{Duration, Instant};
+
+        static WAKES: AtomicUsize = AtomicUsize::new(0);
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        let cfg = Config::default();
+        let plugins = PluginRegistry {
+            process_plugins: vec![Arc::new(ProcessPlugin::new(ProcessPluginSpec {
+                plugin_id: "echo".to_string(),
+                label: "Echo".to_string(),
+                command: "sh".into(),
+                args: vec![
+                    concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/echo_plugin.sh")
+                        .to_string(),
+                ],
+                working_dir: None,
+                env: Vec::new(),
+                timeout: Duration::from_secs(3),
+            }))],
+            ..PluginRegistry::default()
+        };
+        let parsed = ParsedQuery::parse("slow", true);
+        let mut session = OverlaySearchSession {
+            process_wake: Some(|| {
+                WAKES.fetch_add(1, Ordering::SeqCst);
+            }),
+            ..OverlaySearchSession::default()
+        };
+        let search = |session: &mut OverlaySearchSession| {
+            search_overlay_results_with_session(&service, &cfg, &plugins, &parsed, 20, session)
+                .expect("search should succeed")
+        };
+
+        let started = Instant::now();
+        assert!(search(&mut session).is_empty());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(session.final_query_cache.is_empty());
+
+        while !session.poll_process_answers() {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(WAKES.load(Ordering::SeqCst), 1);
+        let answered = search(&mut session);
+        assert_eq!(answered.len(), 1);
+        assert_eq!(answered[0].title, "Echo slow");
+    }
+
+    #[test]
+    fn adaptive_seed_limit_reduces_on_high_latency_window() {
+        let mut session = OverlaySearchSession::default();
+        session
+            .indexed_latency_ms
+            .extend(std::iter::repeat(170_u128).take(12));
+
+        let base = 320;
+        let tuned = adaptive_indexed_seed_limit(&session, 120, 1, base);
+        assert!(tuned < base);
+        assert!(tuned >= INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT / 2);
+        assert!(tuned <= INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::clipboard_history;
+use crate::config::Config;
+use crate::model::SearchItem;
+use crate::overlay_search::sanitize_query_for_profile_log;
+use crate::plugin_placeholders::{
+    expand_args, expand_placeholders, expand_snippet, placeholder_context, PlaceholderContext,
+};
+use crate::plugin_sdk::{
+    CommandOutput, PluginAction, PluginActionKind, PluginCapability, PluginRegistry,
+};
+use std::time::Duration;
+
+// Whoever runs a captured command waits for it, so keep the wait short.
+const PLUGIN_COMMAND_CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Captured commands are handed back unrun: the overlay runs them on a worker thread,
+// a `Launch` request inline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PluginActionOutcome {
+    Done,
+    Capture {
+        command: String,
+        args: Vec<String>,
+        output: CommandOutput,
+    },
+}
+
+// Checks capabilities, writes the audit lines and runs everything but captured commands.
+// `query_text` and `results` are only read to expand placeholders.
+pub(crate) fn run_plugin_action(
+    cfg: &Config,
+    plugins: &PluginRegistry,
+    action: &PluginAction,
+    query_text: &str,
+    results: &[SearchItem],
+) -> Result<PluginActionOutcome, String> {
+    match &action.kind {
+        PluginActionKind::OpenPath { path } => {
+            let capability = if crate::action_executor::is_non_filesystem_open_target(path) {
+                PluginCapability::OpenUrls
+            } else {
+                PluginCapability::Filesystem
+            };
+            authorize_plugin_action(cfg, plugins, action, capability, path)?;
+            crate::action_executor::launch_path(path)
+                .map(|()| PluginActionOutcome::Done)
+                .map_err(|error| format!("plugin open path failed: {error}"))
+        }
+        PluginActionKind::Command {
+            command,
+            args,
+            output,
+        } => {
+            if command.trim().is_empty() {
+                return Err("plugin command action missing command".to_string());
+            }
+            authorize_plugin_action(cfg, plugins, action, PluginCapability::RunCommands, command)?;
+            let context =
+                authorized_placeholder_context(cfg, plugins, action, args, query_text, results)?;
+            let args = expand_args(args, &context)
+                .map_err(|error| format!("plugin command args invalid: {error}"))?;
+            if *output == CommandOutput::Discard {
+                std::process::Command::new(command)
+                    .args(&args)
+                    .spawn()
+                    .map_err(|e| format!("plugin command spawn failed: {e}"))?;
+                return Ok(PluginActionOutcome::Done);
+            }
+
+            Ok(PluginActionOutcome::Capture {
+                command: command.clone(),
+                args,
+                output: *output,
+            })
+        }
+        PluginActionKind::Copy { text } => {
+            let context = authorized_placeholder_context(
+                cfg,
+                plugins,
+                action,
+                std::slice::from_ref(text),
+                query_text,
+                results,
+            )?;
+            let text = expand_placeholders(text, &context)
+                .map_err(|error| format!("plugin copy text invalid: {error}"))?;
+            clipboard_history::copy_text_to_clipboard(cfg, &text)
+                .map(|()| PluginActionOutcome::Done)
+                .map_err(|error| format!("plugin copy failed: {error}"))
+        }
+        PluginActionKind::Snippet { text } => {
+            let context = authorized_placeholder_context(
+                cfg,
+                plugins,
+                action,
+                std::slice::from_ref(text),
+                query_text,
+                results,
+            )?;
+            // The clipboard carries no caret, so `{cursor}` only drops out of the text here.
+            let expanded = expand_snippet(text, &context)
+                .map_err(|error| format!("plugin snippet invalid: {error}"))?;
+            clipboard_history::copy_text_to_clipboard(cfg, &expanded.text)
+                .map(|()| PluginActionOutcome::Done)
+                .map_err(|error| format!("plugin snippet copy failed: {error}"))
+        }
+    }
+}
+
+pub(crate) fn capture_plugin_command(command: &str, args: &[String]) -> Result<String, String> {
+    crate::action_executor::run_command_capture(command, args, PLUGIN_COMMAND_CAPTURE_TIMEOUT)
+        .map_err(|error| format!("plugin command failed: {error}"))
+}
+
+// The clipboard and environment are only read for actions whose templates ask for them.
+fn authorized_placeholder_context(
+    cfg: &Config,
+    plugins: &PluginRegistry,
+    action: &PluginAction,
+    templates: &[String],
+    query_text: &str,
+    results: &[SearchItem],
+) -> Result<PlaceholderContext, String> {
+    let reads_clipboard = templates
+        .iter()
+        .any(|template| template.contains("{clipboard"));
+    if reads_clipboard {
+        authorize_plugin_action(
+            cfg,
+            plugins,
+            action,
+            PluginCapability::ReadClipboard,
+            "{clipboard}",
+        )?;
+    }
+    let reads_env = templates.iter().any(|template| template.contains("{env:"));
+    if reads_env {
+        authorize_plugin_action(cfg, plugins, action, PluginCapability::ReadEnv, "{env}")?;
+    }
+    let mut context =
+        placeholder_context(cfg, plugins, action, query_text, results, reads_clipboard);
+    context.read_env = reads_env;
+    Ok(context)
+}
+
+// Every privileged plugin action leaves one audit line, whether it runs or is refused.
+// Command args are left out because they can carry query and clipboard text.
+fn authorize_plugin_action(
+    cfg: &Config,
+    plugins: &PluginRegistry,
+    action: &PluginAction,
+    capability: PluginCapability,
+    target: &str,
+) -> Result<(), String> {
+    let decision = plugins.authorize(cfg, &action.plugin_id, capability);
+    crate::logging::info(&format!(
+        "[nex] plugin_audit plugin={} action={} capability={} decision={} target={}",
+        action.plugin_id,
+        action.action_id,
+        capability.as_str(),
+        if decision.is_ok() {
+            "allowed"
+        } else {
+            "denied"
+        },
+        sanitize_query_for_profile_log(target)
+    ));
+    decision
+}
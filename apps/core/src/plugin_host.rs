@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::SearchItem;
+use crate::plugin_sdk::{CommandOutput, PluginAction, PluginActionKind, PLUGIN_PROVIDER_PREFIX};
+
+pub const DEFAULT_QUERY_TIMEOUT_MS: u64 = 150;
+pub const MAX_QUERY_TIMEOUT_MS: u64 = 2_000;
+const RESTART_BACKOFF_BASE_MS: u64 = 500;
+const RESTART_BACKOFF_MAX_MS: u64 = 30_000;
+// A live process that keeps missing its timeout is restarted like a crashed one.
+const TIMEOUTS_BEFORE_RESTART: u32 = 3;
+// Results stay launchable after later queries (the overlay may show cached lists).
+const MAX_REMEMBERED_ACTIONS: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessPluginSpec {
+    pub plugin_id: String,
+    pub label: String,
+    pub command: PathBuf,
+    pub args: Vec<String>,
+    pub working_dir: Option<PathBuf>,
+    pub env: Vec<(String, String)>,
+    pub timeout: Duration,
+}
+
+#[derive(Debug)]
+pub enum PluginHostError {
+    Spawn(String),
+    Io(std::io::Error),
+    Timeout(Duration),
+    Exited,
+    Stalled,
+    BackingOff(Duration),
+}
+
+impl Display for PluginHostError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spawn(error) => write!(f, "plugin spawn failed: {error}"),
+            Self::Io(error) => write!(f, "plugin io error: {error}"),
+            Self::Timeout(timeout) => {
+                write!(f, "plugin did not answer within {}ms", timeout.as_millis())
+            }
+            Self::Exited => write!(f, "plugin process exited"),
+            Self::Stalled => write!(f, "plugin stopped reading its stdin"),
+            Self::BackingOff(remaining) => write!(
+                f,
+                "plugin restart backing off for {}ms",
+                remaining.as_millis()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PluginHostError {}
+
+impl From<std::io::Error> for PluginHostError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+// One line per message in each direction.
+// host -> plugin: {"type":"query","id":1,"query":"text","limit":20}
+// plugin -> host: {"id":1,"results":[{"id":"r","title":"...","path":"..."}]}
+#[derive(Debug, Serialize)]
+struct QueryMessage<'a> {
+    #[serde(rename = "type")]
+    message_type: &'static str,
+    id: u64,
+    query: &'a str,
+    limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResultsMessage {
+    id: u64,
+    #[serde(default)]
+    results: Vec<PluginResult>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PluginResult {
+    id: String,
+    title: String,
+    subtitle: String,
+    path: String,
+    command: String,
+    args: Vec<String>,
+    output: String,
+    icon: String,
+}
+
+// Queries reach stdin through a writer thread, so a plugin that stops reading blocks that
+// thread instead of the caller. `pending_writes` counts queries not yet written.
+struct RunningPlugin {
+    child: Child,
+    writes: Sender<String>,
+    pending_writes: Arc<AtomicUsize>,
+    lines: Receiver<String>,
+}
+
+type QueryReply = Box<dyn FnOnce(Result<Vec<SearchItem>, PluginHostError>) + Send>;
+
+struct QueuedQuery {
+    query: String,
+    limit: usize,
+    reply: QueryReply,
+}
+
+#[derive(Default)]
+struct HostState {
+    running: Option<RunningPlugin>,
+    next_request_id: u64,
+    consecutive_crashes: u32,
+    consecutive_timeouts: u32,
+    restart_after: Option<Instant>,
+}
+
+// A plugin executable answering queries over stdin/stdout. It starts on the first query
+// and restarts with backoff after a crash; repeated timeouts count as a crash.
+pub struct ProcessPlugin {
+    spec: ProcessPluginSpec,
+    state: Mutex<HostState>,
+    actions: Mutex<HashMap<String, PluginAction>>,
+    queued: Mutex<Option<QueuedQuery>>,
+}
+
+impl std::fmt::Debug for ProcessPlugin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessPlugin")
+            .field("spec", &self.spec)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProcessPlugin {
+    pub fn new(spec: ProcessPluginSpec) -> Self {
+        Self {
+            spec,
+            state: Mutex::new(HostState::default()),
+            actions: Mutex::new(HashMap::new()),
+            queued: Mutex::new(None),
+        }
+    }
+
+    pub fn plugin_id(&self) -> &str {
+        &self.spec.plugin_id
+    }
+
+    pub fn spec(&self) -> &ProcessPluginSpec {
+        &self.spec
+    }
+
+    pub fn query(&self, query: &str, limit: usize) -> Result<Vec<SearchItem>, PluginHostError> {
+        self.query_with_state(&mut lock(&self.state), query, limit)
+    }
+
+    // Answers on a worker thread. While the plugin is busy only the newest query waits;
+    // a replaced query never gets its `reply`.
+    pub fn submit(
+        self: &Arc<Self>,
+        query: &str,
+        limit: usize,
+        reply: impl FnOnce(Result<Vec<SearchItem>, PluginHostError>) + Send + 'static,
+    ) {
+        *lock(&self.queued) = Some(QueuedQuery {
+            query: query.to_string(),
+            limit,
+            reply: Box::new(reply),
+        });
+        let plugin = Arc::clone(self);
+        std::thread::spawn(move || plugin.run_queued());
+    }
+
+    fn run_queued(&self) {
+        loop {
+            let mut state = match self.state.try_lock() {
+                Ok(guard) => guard,
+                Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+                // The worker holding the plugin picks the query up when it is done.
+                Err(TryLockError::WouldBlock) => return,
+            };
+            let Some(next) = lock(&self.queued).take() else {
+                drop(state);
+                // A query queued while the lock was held would otherwise be stranded.
+                if lock(&self.queued).is_some() {
+                    continue;
+                }
+                return;
+            };
+            let outcome = self.query_with_state(&mut state, &next.query, next.limit);
+            drop(state);
+            (next.reply)(outcome);
+        }
+    }
+
+    fn query_with_state(
+        &self,
+        state: &mut HostState,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchItem>, PluginHostError> {
+        if state.running.is_none() {
+            if let Some(remaining) = state
+                .restart_after
+                .and_then(|deadline| deadline.checked_duration_since(Instant::now()))
+            {
+                return Err(PluginHostError::BackingOff(remaining));
+            }
+            match self.spawn() {
+                Ok(running) => state.running = Some(running),
+                Err(error) => {
+                    self.record_crash(state);
+                    return Err(error);
+                }
+            }
+        }
+
+        state.next_request_id += 1;
+        let request_id = state.next_request_id;
+        let outcome = exchange(
+            state
+                .running
+                .as_mut()
+                .expect("plugin process should be running"),
+            request_id,
+            query,
+            limit,
+            self.spec.timeout,
+        );
+        match outcome {
+            Ok(results) => {
+                state.consecutive_crashes = 0;
+                state.consecutive_timeouts = 0;
+                state.restart_after = None;
+                Ok(self.remember_results(results, limit))
+            }
+            Err(PluginHostError::Timeout(timeout)) => {
+                state.consecutive_timeouts += 1;
+                if state.consecutive_timeouts >= TIMEOUTS_BEFORE_RESTART {
+                    self.record_crash(state);
+                }
+                Err(PluginHostError::Timeout(timeout))
+            }
+            Err(error) => {
+                self.record_crash(state);
+                Err(error)
+            }
+        }
+    }
+
+    pub fn action(&self, result_id: &str) -> Option<PluginAction> {
+        lock(&self.actions).get(result_id).cloned()
+    }
+
+    pub fn is_running(&self) -> bool {
+        lock(&self.state).running.is_some()
+    }
+
+    fn spawn(&self) -> Result<RunningPlugin, PluginHostError> {
+        let mut command = Command::new(&self.spec.command);
+        command
+            .args(&self.spec.args)
+            .envs(self.spec.env.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        if let Some(dir) = &self.spec.working_dir {
+            command.current_dir(dir);
+        }
+        let mut child = command.spawn().map_err(|error| {
+            PluginHostError::Spawn(format!("{}: {error}", self.spec.command.display()))
+        })?;
+        let mut stdin = child.stdin.take().expect("plugin stdin should be piped");
+        let stdout = child.stdout.take().expect("plugin stdout should be piped");
+
+        // Killing the process breaks the pipe, which ends a writer stuck in `write_all`.
+        let (writes, queued) = mpsc::channel::<String>();
+        let pending_writes = Arc::new(AtomicUsize::new(0));
+        let pending = Arc::clone(&pending_writes);
+        std::thread::spawn(move || {
+            for message in queued {
+                let written = stdin
+                    .write_all(message.as_bytes())
+                    .and_then(|()| stdin.flush());
+                pending.fetch_sub(1, Ordering::SeqCst);
+                if written.is_err() {
+                    break;
+                }
+            }
+        });
+
+        // The channel disconnects once the plugin closes stdout, which is how a crash
+        // is noticed while waiting for an answer.
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(RunningPlugin {
+            child,
+            writes,
+            pending_writes,
+            lines,
+        })
+    }
+
+    fn record_crash(&self, state: &mut HostState) {
+        if let Some(mut running) = state.running.take() {
+            let _ = running.child.kill();
+            let _ = running.child.wait();
+        }
+        state.consecutive_crashes += 1;
+        state.consecutive_timeouts = 0;
+        // The first crash restarts right away; repeated crashes back off.
+        let backoff_ms = if state.consecutive_crashes <= 1 {
+            0
+        } else {
+            RESTART_BACKOFF_BASE_MS
+                .saturating_mul(1 << (state.consecutive_crashes - 2).min(16))
+                .min(RESTART_BACKOFF_MAX_MS)
+        };
+        state.restart_after = Some(Instant::now() + Duration::from_millis(backoff_ms));
+    }
+
+    fn remember_results(&self, results: Vec<PluginResult>, limit: usize) -> Vec<SearchItem> {
+        let mut actions = lock(&self.actions);
+        if actions.len() > MAX_REMEMBERED_ACTIONS {
+            actions.clear();
+        }
+        let mut items = Vec::with_capacity(results.len().min(limit));
+        for result in results.into_iter().take(limit) {
+            let result_key = result.id.trim();
+            let title = result.title.trim();
+            if result_key.is_empty() || title.is_empty() {
+                continue;
+            }
+            let kind = if result.path.trim().is_empty() && !result.command.trim().is_empty() {
+                PluginActionKind::Command {
+                    command: result.command.trim().to_string(),
+                    args: result.args,
+                    output: CommandOutput::parse(&result.output),
+                }
+            } else {
+                PluginActionKind::OpenPath {
+                    path: result.path.trim().to_string(),
+                }
+            };
+            let result_id = format!(
+                "{PLUGIN_PROVIDER_PREFIX}{}:result:{result_key}",
+                self.spec.plugin_id
+            );
+            let subtitle = if result.subtitle.trim().is_empty() {
+                format!("{} plugin", self.spec.label)
+            } else {
+                result.subtitle.trim().to_string()
+            };
+            let icon = match &self.spec.working_dir {
+                Some(dir) => crate::plugin_manifest::resolve_icon(dir, &result.icon),
+                None => result.icon.trim().to_string(),
+            };
+            items.push(SearchItem::new(&result_id, "action", title, &subtitle).with_icon(&icon));
+            actions.insert(
+                result_id.clone(),
+                PluginAction {
+                    result_id,
+                    plugin_id: self.spec.plugin_id.clone(),
+                    action_id: result_key.to_string(),
+                    title: title.to_string(),
+                    subtitle,
+                    keywords: Vec::new(),
+                    kind,
+                },
+            );
+        }
+        items
+    }
+}
+
+impl Drop for ProcessPlugin {
+    fn drop(&mut self) {
+        let state = match self.state.get_mut() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some(mut running) = state.running.take() {
+            let _ = running.child.kill();
+            let _ = running.child.wait();
+        }
+    }
+}
+
+fn exchange(
+    running: &mut RunningPlugin,
+    request_id: u64,
+    query: &str,
+    limit: usize,
+    timeout: Duration,
+) -> Result<Vec<PluginResult>, PluginHostError> {
+    if running.child.try_wait()?.is_some() {
+        return Err(PluginHostError::Exited);
+    }
+    if running.pending_writes.load(Ordering::SeqCst) > 0 {
+        return Err(PluginHostError::Stalled);
+    }
+    let mut message = serde_json::to_string(&QueryMessage {
+        message_type: "query",
+        id: request_id,
+        query,
+        limit,
+    })
+    .expect("plugin query should serialize");
+    message.push('\n');
+    running.pending_writes.fetch_add(1, Ordering::SeqCst);
+    if running.writes.send(message).is_err() {
+        return Err(PluginHostError::Exited);
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match running.lines.recv_timeout(remaining) {
+            // Late answers to timed-out queries and stray output are skipped.
+            Ok(line) => match serde_json::from_str::<ResultsMessage>(&line) {
+                Ok(message) if message.id == request_id => return Ok(message.results),
+                _ => continue,
+            },
+            Err(RecvTimeoutError::Timeout) => return Err(PluginHostError::Timeout(timeout)),
+            Err(RecvTimeoutError::Disconnected) => return Err(PluginHostError::Exited),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
@@ -0,0 +1,634 @@
+use crate::plugin_host::MAX_QUERY_TIMEOUT_MS;
+use crate::plugin_sdk::PluginCapability;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// Bumped when a manifest field changes meaning. Manifests without `manifest_version` are
+// read as version 1.
+pub const MANIFEST_VERSION: u64 = 1;
+pub const NEX_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const MANIFEST_FIELDS: &[&str] = &[
+    "manifest_version",
+    "min_nex_version",
+    "id",
+    "name",
+    "version",
+    "enabled",
+    "provider_items",
+    "actions",
+    "process",
+    "trigger_keywords",
+    "global",
+    "capabilities",
+    "settings",
+    "snippets",
+    "snippets_dir",
+];
+const PROVIDER_ITEM_FIELDS: &[&str] = &["id", "kind", "title", "path", "icon", "secondary_actions"];
+const ACTION_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "subtitle",
+    "keywords",
+    "type",
+    "path",
+    "command",
+    "args",
+    "output",
+    "text",
+    "icon",
+    "secondary_actions",
+];
+const PROCESS_FIELDS: &[&str] = &["command", "args", "timeout_ms"];
+const SNIPPET_FIELDS: &[&str] = &["id", "title", "text", "keywords"];
+const ACTION_TYPES: &[&str] = &["open_path", "command", "copy"];
+// Icon values with one of these extensions are files; anything else is a glyph.
+const ICON_FILE_EXTENSIONS: &[&str] = &["png", "ico", "svg", "jpg", "jpeg", "bmp", "gif"];
+const OUTPUT_MODES: &[&str] = &["discard", "result", "clipboard"];
+const MANIFEST_EXTENSIONS: &[&str] = &["json", "json5", "toml"];
+// Checked in this order inside `<plugin_dir>/<name>/`.
+const BUNDLE_MANIFEST_NAMES: &[&str] = &["plugin.json", "plugin.json5", "plugin.toml"];
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub(crate) struct PluginManifest {
+    pub(crate) manifest_version: u64,
+    pub(crate) min_nex_version: String,
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) enabled: bool,
+    pub(crate) provider_items: Vec<ManifestProviderItem>,
+    pub(crate) actions: Vec<ManifestAction>,
+    pub(crate) process: Option<ManifestProcess>,
+    pub(crate) trigger_keywords: Vec<String>,
+    pub(crate) global: Option<bool>,
+    pub(crate) capabilities: Vec<String>,
+    pub(crate) settings: Map<String, Value>,
+    pub(crate) snippets: Vec<ManifestSnippet>,
+    pub(crate) snippets_dir: String,
+}
+
+impl Default for PluginManifest {
+    fn default() -> Self {
+        Self {
+            manifest_version: MANIFEST_VERSION,
+            min_nex_version: String::new(),
+            id: String::new(),
+            name: String::new(),
+            version: String::new(),
+            enabled: true,
+            provider_items: Vec::new(),
+            actions: Vec::new(),
+            process: None,
+            trigger_keywords: Vec::new(),
+            global: None,
+            capabilities: Vec::new(),
+            settings: Map::new(),
+            snippets: Vec::new(),
+            snippets_dir: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct ManifestProcess {
+    pub(crate) command: String,
+    pub(crate) args: Vec<String>,
+    pub(crate) timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct ManifestProviderItem {
+    pub(crate) id: String,
+    pub(crate) kind: String,
+    pub(crate) title: String,
+    pub(crate) path: String,
+    pub(crate) icon: String,
+    pub(crate) secondary_actions: Vec<ManifestAction>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct ManifestSnippet {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) text: String,
+    pub(crate) keywords: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub(crate) struct ManifestAction {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) subtitle: String,
+    pub(crate) keywords: Vec<String>,
+    #[serde(rename = "type")]
+    pub(crate) action_type: String,
+    pub(crate) path: String,
+    pub(crate) command: String,
+    pub(crate) args: Vec<String>,
+    pub(crate) output: String,
+    pub(crate) text: String,
+    pub(crate) icon: String,
+    pub(crate) secondary_actions: Vec<ManifestAction>,
+}
+
+impl Default for ManifestAction {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            title: String::new(),
+            subtitle: String::new(),
+            keywords: Vec::new(),
+            action_type: "open_path".to_string(),
+            path: String::new(),
+            command: String::new(),
+            args: Vec::new(),
+            output: String::new(),
+            text: String::new(),
+            icon: String::new(),
+            secondary_actions: Vec::new(),
+        }
+    }
+}
+
+impl PluginManifest {
+    // Settings as the strings commands see; numbers and booleans keep their JSON spelling.
+    pub(crate) fn settings_text(&self) -> BTreeMap<String, String> {
+        self.settings
+            .iter()
+            .map(|(key, value)| {
+                let text = match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                (key.clone(), text)
+            })
+            .collect()
+    }
+}
+
+// A manifest file, or a plugin folder holding `*.json`/`*.json5`/`*.toml` manifests and
+// `<name>/plugin.{json,json5,toml}` bundles.
+pub(crate) fn discover_manifest_paths(path: &Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+    if !path.is_dir() {
+        return Vec::new();
+    }
+
+    let mut out = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                out.extend(
+                    BUNDLE_MANIFEST_NAMES
+                        .iter()
+                        .map(|name| entry_path.join(name))
+                        .find(|candidate| candidate.is_file()),
+                );
+            } else if entry_path
+                .extension()
+                .and_then(|v| v.to_str())
+                .is_some_and(|v| {
+                    MANIFEST_EXTENSIONS
+                        .iter()
+                        .any(|ext| v.eq_ignore_ascii_case(ext))
+                })
+            {
+                out.push(entry_path);
+            }
+        }
+    }
+    // Stable order, so keyword conflicts resolve the same way on every load.
+    out.sort();
+    out
+}
+
+// `./run.py` and `scripts/run.py` name files next to the manifest; bare names (`python`),
+// absolute paths and URLs are left alone.
+pub(crate) fn resolve_bundle_path(manifest_dir: &Path, value: &str) -> String {
+    let trimmed = value.trim();
+    let path = Path::new(trimmed);
+    if trimmed.contains(':') || !path.is_relative() || path.components().count() < 2 {
+        return trimmed.to_string();
+    }
+    manifest_dir.join(path).to_string_lossy().into_owned()
+}
+
+// Every visible file directly inside a snippet folder, in name order.
+pub(crate) fn snippet_files(dir: &Path) -> Vec<PathBuf> {
+    let mut out = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| !name.starts_with('.'))
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    out.sort();
+    out
+}
+
+// `icons/gh.png` and `gh.png` are files next to the manifest; `★` or `\u{e721}` is a glyph
+// and is kept as written.
+pub(crate) fn resolve_icon(manifest_dir: &Path, value: &str) -> String {
+    let trimmed = value.trim();
+    let is_file = Path::new(trimmed)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            ICON_FILE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        });
+    if is_file && !trimmed.contains(':') && Path::new(trimmed).is_relative() {
+        return manifest_dir.join(trimmed).to_string_lossy().into_owned();
+    }
+    trimmed.to_string()
+}
+
+fn parse_manifest_value(path: &Path, raw: &str) -> Result<Value, String> {
+    let extension = path
+        .extension()
+        .and_then(|v| v.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "toml" => {
+            let value: toml::Value =
+                toml::from_str(raw).map_err(|e| format!("invalid toml: {e}"))?;
+            serde_json::to_value(value).map_err(|e| format!("invalid toml: {e}"))
+        }
+        "json5" => json5::from_str(raw).map_err(|e| format!("invalid json5: {e}")),
+        _ => serde_json::from_str(raw).map_err(|e| format!("invalid json: {e}")),
+    }
+}
+
+// The `id` a manifest names even when it fails validation. None when the file cannot be
+// read or parsed, or names no usable id.
+pub(crate) fn manifest_id_hint(path: &Path) -> Option<String> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    let value = parse_manifest_value(path, &raw).ok()?;
+    let id = value.get("id")?.as_str()?.trim();
+    (!id.is_empty() && !id.contains(':') && !id.contains(char::is_whitespace))
+        .then(|| id.to_string())
+}
+
+// Every problem found is reported as `<field>: <message>`, so a plugin author can fix a
+// manifest in one pass.
+pub(crate) fn load_manifest(path: &Path) -> Result<PluginManifest, Vec<String>> {
+    let raw = std::fs::read_to_string(path).map_err(|e| vec![format!("read failed: {e}")])?;
+    let value = parse_manifest_value(path, &raw).map_err(|error| vec![error])?;
+    let errors = validate_manifest(&value);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    serde_json::from_value(value).map_err(|e| vec![format!("invalid manifest: {e}")])
+}
+
+pub(crate) fn validate_manifest(value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    let Some(manifest) = value.as_object() else {
+        return vec!["manifest must be an object".to_string()];
+    };
+    unknown_fields(manifest, "", MANIFEST_FIELDS, &mut errors);
+
+    if let Some(version) = manifest.get("manifest_version") {
+        match version.as_u64() {
+            Some(0) | None => errors.push("manifest_version: expected a positive integer".into()),
+            Some(version) if version > MANIFEST_VERSION => errors.push(format!(
+                "manifest_version: {version} is newer than this nex supports ({MANIFEST_VERSION})"
+            )),
+            Some(_) => {}
+        }
+    }
+    if let Some(required) = string_field(manifest, "", "min_nex_version", &mut errors) {
+        match parse_version(&required) {
+            None => errors.push(format!(
+                "min_nex_version: '{required}' is not a version (expected e.g. 0.2.0)"
+            )),
+            Some(parts) if Some(parts) > parse_version(NEX_VERSION) => errors.push(format!(
+                "min_nex_version: plugin requires nex {required} or newer (running {NEX_VERSION})"
+            )),
+            Some(_) => {}
+        }
+    }
+
+    match string_field(manifest, "", "id", &mut errors) {
+        None => errors.push("id: required".to_string()),
+        Some(id) if id.trim().is_empty() => errors.push("id: must not be empty".to_string()),
+        Some(id) if id.contains(':') || id.contains(char::is_whitespace) => {
+            errors.push(format!("id: '{id}' must not contain ':' or whitespace"))
+        }
+        Some(_) => {}
+    }
+    string_field(manifest, "", "name", &mut errors);
+    string_field(manifest, "", "version", &mut errors);
+    bool_field(manifest, "", "enabled", &mut errors);
+    bool_field(manifest, "", "global", &mut errors);
+    string_list_field(manifest, "", "trigger_keywords", &mut errors);
+    if string_list_field(manifest, "", "capabilities", &mut errors) {
+        let capabilities = manifest["capabilities"].as_array().into_iter().flatten();
+        for (index, capability) in capabilities.filter_map(Value::as_str).enumerate() {
+            if PluginCapability::parse(capability).is_none() {
+                errors.push(format!(
+                    "capabilities[{index}]: unknown capability '{capability}' (expected {})",
+                    PluginCapability::ALL
+                        .map(PluginCapability::as_str)
+                        .join(", ")
+                ));
+            }
+        }
+    }
+
+    for (field, item) in object_list(manifest, "", "provider_items", &mut errors) {
+        unknown_fields(item, &field, PROVIDER_ITEM_FIELDS, &mut errors);
+        required_string(item, &field, "id", &mut errors);
+        required_string(item, &field, "title", &mut errors);
+        string_field(item, &field, "kind", &mut errors);
+        string_field(item, &field, "path", &mut errors);
+        string_field(item, &field, "icon", &mut errors);
+        validate_secondary_actions(item, &field, &mut errors);
+    }
+
+    if let Some(settings) = manifest.get("settings") {
+        match settings.as_object() {
+            None => errors.push("settings: expected an object".to_string()),
+            Some(settings) => {
+                for (key, value) in settings {
+                    if !(value.is_string() || value.is_number() || value.is_boolean()) {
+                        errors.push(format!(
+                            "settings.{key}: expected a string, number or boolean"
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    string_field(manifest, "", "snippets_dir", &mut errors);
+    let mut snippet_ids = HashSet::new();
+    for (field, snippet) in object_list(manifest, "", "snippets", &mut errors) {
+        unknown_fields(snippet, &field, SNIPPET_FIELDS, &mut errors);
+        if let Some(id) = required_string(snippet, &field, "id", &mut errors) {
+            if !snippet_ids.insert(id.trim().to_string()) {
+                errors.push(format!("{field}.id: duplicate snippet id '{}'", id.trim()));
+            }
+        }
+        required_string(snippet, &field, "title", &mut errors);
+        required_string(snippet, &field, "text", &mut errors);
+        string_list_field(snippet, &field, "keywords", &mut errors);
+    }
+
+    let mut action_ids = HashSet::new();
+    for (field, action) in object_list(manifest, "", "actions", &mut errors) {
+        validate_action(action, &field, &mut action_ids, &mut errors);
+        validate_secondary_actions(action, &field, &mut errors);
+    }
+
+    if let Some(process) = manifest.get("process") {
+        match process.as_object() {
+            None => errors.push("process: expected an object".to_string()),
+            Some(process) => {
+                unknown_fields(process, "process", PROCESS_FIELDS, &mut errors);
+                required_string(process, "process", "command", &mut errors);
+                string_list_field(process, "process", "args", &mut errors);
+                if let Some(timeout) = process.get("timeout_ms") {
+                    if !timeout
+                        .as_u64()
+                        .is_some_and(|ms| (1..=MAX_QUERY_TIMEOUT_MS).contains(&ms))
+                    {
+                        errors.push(format!(
+                            "process.timeout_ms: expected 1..={MAX_QUERY_TIMEOUT_MS}"
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    errors
+}
+
+fn validate_action(
+    action: &Map<String, Value>,
+    field: &str,
+    action_ids: &mut HashSet<String>,
+    errors: &mut Vec<String>,
+) {
+    unknown_fields(action, field, ACTION_FIELDS, errors);
+    if let Some(id) = required_string(action, field, "id", errors) {
+        if !action_ids.insert(id.trim().to_string()) {
+            errors.push(format!("{field}.id: duplicate action id '{}'", id.trim()));
+        }
+    }
+    required_string(action, field, "title", errors);
+    string_field(action, field, "subtitle", errors);
+    string_list_field(action, field, "keywords", errors);
+    string_list_field(action, field, "args", errors);
+    string_field(action, field, "icon", errors);
+
+    let action_type = string_field(action, field, "type", errors)
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "open_path".to_string());
+    if !ACTION_TYPES.contains(&action_type.as_str()) {
+        let (last, rest) = ACTION_TYPES
+            .split_last()
+            .expect("action types are not empty");
+        errors.push(format!(
+            "{field}.type: unknown action type '{action_type}' (expected {} or {last})",
+            rest.join(", ")
+        ));
+        return;
+    }
+    let output = string_field(action, field, "output", errors);
+    if action_type == "copy" {
+        required_string(action, field, "text", errors);
+    }
+    if action_type == "command" {
+        required_string(action, field, "command", errors);
+        if let Some(output) = output {
+            let normalized = output.trim().to_ascii_lowercase();
+            if !normalized.is_empty() && !OUTPUT_MODES.contains(&normalized.as_str()) {
+                errors.push(format!(
+                    "{field}.output: unknown output mode '{output}' (expected {})",
+                    OUTPUT_MODES.join(", ")
+                ));
+            }
+        }
+    } else {
+        if action_type == "open_path" {
+            required_string(action, field, "path", errors);
+        }
+        if output.is_some() {
+            errors.push(format!("{field}.output: only valid for command actions"));
+        }
+    }
+}
+
+// Secondary actions are plain actions listed under an item or action; they cannot nest.
+fn validate_secondary_actions(object: &Map<String, Value>, parent: &str, errors: &mut Vec<String>) {
+    let mut action_ids = HashSet::new();
+    for (field, action) in object_list(object, parent, "secondary_actions", errors) {
+        validate_action(action, &field, &mut action_ids, errors);
+        if action.contains_key("secondary_actions") {
+            errors.push(format!(
+                "{field}.secondary_actions: secondary actions cannot have their own"
+            ));
+        }
+    }
+}
+
+fn unknown_fields(
+    object: &Map<String, Value>,
+    parent: &str,
+    allowed: &[&str],
+    errors: &mut Vec<String>,
+) {
+    for key in object.keys() {
+        if !allowed.contains(&key.as_str()) {
+            errors.push(format!("{}: unknown field", field_path(parent, key)));
+        }
+    }
+}
+
+fn string_field(
+    object: &Map<String, Value>,
+    parent: &str,
+    key: &str,
+    errors: &mut Vec<String>,
+) -> Option<String> {
+    let value = object.get(key)?;
+    match value.as_str() {
+        Some(text) => Some(text.to_string()),
+        None => {
+            errors.push(format!("{}: expected a string", field_path(parent, key)));
+            None
+        }
+    }
+}
+
+fn required_string(
+    object: &Map<String, Value>,
+    parent: &str,
+    key: &str,
+    errors: &mut Vec<String>,
+) -> Option<String> {
+    let present = object.contains_key(key);
+    match string_field(object, parent, key, errors) {
+        Some(text) if !text.trim().is_empty() => Some(text),
+        Some(_) => {
+            errors.push(format!("{}: must not be empty", field_path(parent, key)));
+            None
+        }
+        None => {
+            if !present {
+                errors.push(format!("{}: required", field_path(parent, key)));
+            }
+            None
+        }
+    }
+}
+
+fn bool_field(object: &Map<String, Value>, parent: &str, key: &str, errors: &mut Vec<String>) {
+    if object.get(key).is_some_and(|value| !value.is_boolean()) {
+        errors.push(format!(
+            "{}: expected true or false",
+            field_path(parent, key)
+        ));
+    }
+}
+
+// True when the field is present and valid.
+fn string_list_field(
+    object: &Map<String, Value>,
+    parent: &str,
+    key: &str,
+    errors: &mut Vec<String>,
+) -> bool {
+    let Some(value) = object.get(key) else {
+        return false;
+    };
+    let is_string_list = value
+        .as_array()
+        .is_some_and(|items| items.iter().all(Value::is_string));
+    if !is_string_list {
+        errors.push(format!(
+            "{}: expected a list of strings",
+            field_path(parent, key)
+        ));
+    }
+    is_string_list
+}
+
+fn object_list<'a>(
+    object: &'a Map<String, Value>,
+    parent: &str,
+    key: &str,
+    errors: &mut Vec<String>,
+) -> Vec<(String, &'a Map<String, Value>)> {
+    let Some(value) = object.get(key) else {
+        return Vec::new();
+    };
+    let path = field_path(parent, key);
+    let Some(items) = value.as_array() else {
+        errors.push(format!("{path}: expected a list"));
+        return Vec::new();
+    };
+    let mut out = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let field = format!("{path}[{index}]");
+        match item.as_object() {
+            Some(item) => out.push((field, item)),
+            None => errors.push(format!("{field}: expected an object")),
+        }
+    }
+    out
+}
+
+fn field_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+// `major[.minor[.patch]]`, ignoring any `-pre`/`+build` suffix.
+fn parse_version(value: &str) -> Option<(u64, u64, u64)> {
+    let core = value.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_version;
+
+    #[test]
+    fn parses_partial_versions() {
+        assert_eq!(parse_version("1"), Some((1, 0, 0)));
+        assert_eq!(parse_version("0.2.3-beta"), Some((0, 2, 3)));
+        assert_eq!(parse_version("1.x"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+    }
+}
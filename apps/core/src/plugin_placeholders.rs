@@ -0,0 +1,287 @@
+use crate::config::Config;
+use crate::plugin_sdk::{PluginAction, PluginRegistry};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+// Values available to `{...}` placeholders in plugin command args and snippets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaceholderContext {
+    pub query: String,
+    pub selection_path: String,
+    pub clipboard: String,
+    pub plugin_dir: String,
+    pub settings: BTreeMap<String, String>,
+    pub date: String,
+    // `{env:NAME}` only expands when the plugin is granted `read_env`.
+    pub read_env: bool,
+}
+
+// Snippet text with `{cursor}` removed; `cursor` is the character offset where it stood.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandedSnippet {
+    pub text: String,
+    pub cursor: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaceholderError {
+    Unterminated(String),
+    UnknownPlaceholder(String),
+    UnknownEscape(String),
+    EnvNotGranted(String),
+    ShellEscapeUnsupported,
+}
+
+impl Display for PlaceholderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unterminated(template) => write!(f, "unterminated placeholder in '{template}'"),
+            Self::UnknownPlaceholder(name) => write!(f, "unknown placeholder '{{{name}}}'"),
+            Self::UnknownEscape(name) => write!(f, "unknown placeholder escape '{name}'"),
+            Self::EnvNotGranted(name) => write!(
+                f,
+                "placeholder '{{env:{name}}}' needs the read_env capability"
+            ),
+            Self::ShellEscapeUnsupported => write!(
+                f,
+                "the ':shell' escape is not supported on Windows; pass the value as its own arg"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlaceholderError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Escape {
+    Raw,
+    Url,
+    Shell,
+    Json,
+}
+
+// `{query}`, `{selection_path}`, `{clipboard}`, `{plugin_dir}`, `{date}`, `{env:NAME}` and
+// `{setting:NAME}` (from the manifest's `settings`), each optionally followed by an escape:
+// `{query:url}`, `{selection_path:shell}`. Args reach the command as separate argv
+// entries, so `raw` (the default) is enough unless the command is itself a shell or a URL.
+// cmd.exe has no quoting that survives `cmd /C`, so `shell` is refused on Windows.
+// `{{` and `}}` are literal braces.
+pub fn expand_placeholders(
+    template: &str,
+    context: &PlaceholderContext,
+) -> Result<String, PlaceholderError> {
+    expand(template, context, None)
+}
+
+// Snippets also accept `{cursor}`; only the first one is kept as the cursor position.
+pub fn expand_snippet(
+    template: &str,
+    context: &PlaceholderContext,
+) -> Result<ExpandedSnippet, PlaceholderError> {
+    let mut cursor = None;
+    let text = expand(template, context, Some(&mut cursor))?;
+    Ok(ExpandedSnippet { text, cursor })
+}
+
+fn expand(
+    template: &str,
+    context: &PlaceholderContext,
+    mut cursor: Option<&mut Option<usize>>,
+) -> Result<String, PlaceholderError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '}']) {
+        out.push_str(&rest[..index]);
+        let tail = &rest[index..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if let Some(after) = tail.strip_prefix('}') {
+            out.push('}');
+            rest = after;
+            continue;
+        }
+        let Some(end) = tail.find('}') else {
+            return Err(PlaceholderError::Unterminated(template.to_string()));
+        };
+        let spec = &tail[1..end];
+        match cursor.as_deref_mut() {
+            Some(position) if spec.trim() == "cursor" => {
+                position.get_or_insert(out.chars().count());
+            }
+            _ => out.push_str(&resolve(spec, context)?),
+        }
+        rest = &tail[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+pub fn expand_args(
+    args: &[String],
+    context: &PlaceholderContext,
+) -> Result<Vec<String>, PlaceholderError> {
+    args.iter()
+        .map(|arg| expand_placeholders(arg, context))
+        .collect()
+}
+
+fn resolve(spec: &str, context: &PlaceholderContext) -> Result<String, PlaceholderError> {
+    let mut parts = spec.split(':');
+    let name = parts.next().unwrap_or_default().trim();
+    let value = match name {
+        "query" => context.query.clone(),
+        "selection_path" => context.selection_path.clone(),
+        "clipboard" => context.clipboard.clone(),
+        "plugin_dir" => context.plugin_dir.clone(),
+        "date" => context.date.clone(),
+        "setting" => {
+            let key = parts.next().unwrap_or_default().trim();
+            match context.settings.get(key) {
+                Some(value) => value.clone(),
+                None => return Err(PlaceholderError::UnknownPlaceholder(spec.to_string())),
+            }
+        }
+        "env" => {
+            let variable = parts.next().unwrap_or_default().trim();
+            if variable.is_empty() {
+                return Err(PlaceholderError::UnknownPlaceholder(spec.to_string()));
+            }
+            if !context.read_env {
+                return Err(PlaceholderError::EnvNotGranted(variable.to_string()));
+            }
+            std::env::var(variable).unwrap_or_default()
+        }
+        _ => return Err(PlaceholderError::UnknownPlaceholder(spec.to_string())),
+    };
+    let escape = match parts.next().map(str::trim) {
+        None | Some("raw") => Escape::Raw,
+        Some("url") => Escape::Url,
+        Some("shell") if cfg!(target_os = "windows") => {
+            return Err(PlaceholderError::ShellEscapeUnsupported)
+        }
+        Some("shell") => Escape::Shell,
+        Some("json") => Escape::Json,
+        Some(other) => return Err(PlaceholderError::UnknownEscape(other.to_string())),
+    };
+    if parts.next().is_some() {
+        return Err(PlaceholderError::UnknownPlaceholder(spec.to_string()));
+    }
+    Ok(apply_escape(&value, escape))
+}
+
+// `YYYY-MM-DD` for a Unix timestamp, in UTC.
+pub fn utc_date(epoch_secs: i64) -> String {
+    // Civil-from-days over 400-year eras, so no calendar table is needed.
+    let days = epoch_secs.div_euclid(86_400) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// `{query}` is what follows a trigger keyword when the query was routed to a plugin.
+// Unrouted, a leading word that names the action (a word of its title, or one of its
+// keywords) is dropped, so `shout hello` gives the "Shout" action `hello`. Otherwise it is
+// the whole query. `{selection_path}` is the first file or app among the current results.
+pub(crate) fn placeholder_context(
+    cfg: &Config,
+    plugins: &PluginRegistry,
+    action: &PluginAction,
+    query_text: &str,
+    results: &[crate::model::SearchItem],
+    read_clipboard: bool,
+) -> PlaceholderContext {
+    let plugin_id = action.plugin_id.as_str();
+    let query = match plugins.route(query_text) {
+        Some(route) => route.argument,
+        None => strip_action_word(query_text.trim(), action),
+    };
+    let selection_path = results
+        .iter()
+        .find(|item| {
+            !item.kind.eq_ignore_ascii_case("action")
+                && !item.kind.eq_ignore_ascii_case("clipboard")
+                && !item.kind.eq_ignore_ascii_case("snippet")
+                && !item.path.trim().is_empty()
+        })
+        .map(|item| item.path.trim().to_string())
+        .unwrap_or_default();
+    PlaceholderContext {
+        query,
+        selection_path,
+        clipboard: if read_clipboard {
+            crate::clipboard_history::current_text(cfg)
+        } else {
+            String::new()
+        },
+        plugin_dir: plugins
+            .plugin_dirs
+            .get(plugin_id)
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        settings: plugins.settings.get(plugin_id).cloned().unwrap_or_default(),
+        date: utc_date(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+        ),
+        read_env: false,
+    }
+}
+
+fn strip_action_word(query: &str, action: &PluginAction) -> String {
+    let (head, rest) = query.split_once(char::is_whitespace).unwrap_or((query, ""));
+    let names_action = action
+        .title
+        .split_whitespace()
+        .chain(action.keywords.iter().map(String::as_str))
+        .any(|word| word.eq_ignore_ascii_case(head));
+    if names_action {
+        rest.trim().to_string()
+    } else {
+        query.to_string()
+    }
+}
+
+fn apply_escape(value: &str, escape: Escape) -> String {
+    match escape {
+        Escape::Raw => value.to_string(),
+        Escape::Url => percent_encode(value),
+        Escape::Shell => shell_quote(value),
+        Escape::Json => {
+            let quoted = serde_json::to_string(value).expect("string should serialize");
+            quoted[1..quoted.len() - 1].to_string()
+        }
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+// POSIX sh.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
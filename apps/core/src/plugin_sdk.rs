@@ -1,13 +1,133 @@
 use crate::config::Config;
-use crate::model::SearchItem;
-use serde::Deserialize;
-use std::collections::HashMap;
+use crate::core_service::CoreService;
+use crate::discovery::{DiscoveryProvider, ProviderError};
+use crate::model::{ItemAction, SearchItem};
+use crate::plugin_host::{
+    PluginHostError, ProcessPlugin, ProcessPluginSpec, DEFAULT_QUERY_TIMEOUT_MS,
+    MAX_QUERY_TIMEOUT_MS,
+};
+use crate::plugin_manifest::{
+    discover_manifest_paths, load_manifest, manifest_id_hint, resolve_bundle_path, resolve_icon,
+    snippet_files, ManifestAction, ManifestProcess, ManifestProviderItem, ManifestSnippet,
+    PluginManifest,
+};
+use crate::plugin_placeholders::{
+    expand_args, expand_placeholders, expand_snippet, PlaceholderError,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const PLUGIN_PROVIDER_PREFIX: &str = "plugin:";
+// Longest a query waits on process plugins; their `timeout_ms` is capped to it.
+pub const PROCESS_QUERY_BUDGET: Duration = Duration::from_millis(400);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PluginActionKind {
-    OpenPath { path: String },
-    Command { command: String, args: Vec<String> },
+    OpenPath {
+        path: String,
+    },
+    Command {
+        command: String,
+        args: Vec<String>,
+        output: CommandOutput,
+    },
+    Copy {
+        text: String,
+    },
+    Snippet {
+        text: String,
+    },
+}
+
+// What happens to a command action's stdout. Captured commands run to completion (with a
+// timeout) instead of being launched in the background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommandOutput {
+    #[default]
+    Discard,
+    Result,
+    Clipboard,
+}
+
+impl CommandOutput {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "result" => Self::Result,
+            "clipboard" => Self::Clipboard,
+            _ => Self::Discard,
+        }
+    }
+}
+
+// What a plugin may do when one of its actions runs. A manifest declares the capabilities
+// it needs; the user grants them per plugin with `plugin_grants`. An action runs only when
+// its capability is both declared and granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluginCapability {
+    RunCommands,
+    OpenUrls,
+    ReadClipboard,
+    ReadEnv,
+    Filesystem,
+}
+
+impl PluginCapability {
+    pub const ALL: [Self; 5] = [
+        Self::RunCommands,
+        Self::OpenUrls,
+        Self::ReadClipboard,
+        Self::ReadEnv,
+        Self::Filesystem,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "run_commands" => Some(Self::RunCommands),
+            "open_urls" => Some(Self::OpenUrls),
+            "read_clipboard" => Some(Self::ReadClipboard),
+            "read_env" => Some(Self::ReadEnv),
+            "filesystem" => Some(Self::Filesystem),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RunCommands => "run_commands",
+            Self::OpenUrls => "open_urls",
+            Self::ReadClipboard => "read_clipboard",
+            Self::ReadEnv => "read_env",
+            Self::Filesystem => "filesystem",
+        }
+    }
+}
+
+// `<plugin_id>:<capability>`, or `<plugin_id>:*` for every capability.
+pub fn parse_plugin_grant(grant: &str) -> Result<(String, Vec<PluginCapability>), String> {
+    let Some((plugin_id, capability)) = grant.trim().split_once(':') else {
+        return Err("expected <plugin_id>:<capability>".to_string());
+    };
+    let plugin_id = plugin_id.trim();
+    if plugin_id.is_empty() {
+        return Err("missing plugin id".to_string());
+    }
+    let capabilities = if capability.trim() == "*" {
+        PluginCapability::ALL.to_vec()
+    } else {
+        vec![PluginCapability::parse(capability)
+            .ok_or_else(|| format!("unknown capability '{}'", capability.trim()))?]
+    };
+    Ok((plugin_id.to_string(), capabilities))
+}
+
+pub fn is_capability_granted(cfg: &Config, plugin_id: &str, capability: PluginCapability) -> bool {
+    cfg.plugin_grants.iter().any(|grant| {
+        parse_plugin_grant(grant).is_ok_and(|(granted_id, capabilities)| {
+            granted_id == plugin_id && capabilities.contains(&capability)
+        })
+    })
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,11 +141,52 @@ pub struct PluginAction {
     pub kind: PluginActionKind,
 }
 
+// A plugin reachable through `trigger_keywords`. Its actions and process only join
+// unscoped queries when the manifest sets `global`.
+#[derive(Debug, Clone)]
+pub struct PluginTrigger {
+    pub plugin_id: String,
+    pub label: String,
+    pub keywords: Vec<String>,
+    pub global: bool,
+    pub action_items: Vec<SearchItem>,
+    pub process: Option<Arc<ProcessPlugin>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginRoute<'a> {
+    pub trigger: &'a PluginTrigger,
+    pub keyword: String,
+    pub argument: String,
+}
+
+// Icon and secondary actions for an indexed `provider_items` entry. The index only stores
+// the item itself, so these are attached again when the item comes back in results.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemPresentation {
+    pub icon: String,
+    pub secondary_actions: Vec<ItemAction>,
+}
+
+// `action_items` and `process_plugins` take part in every query; `triggers` are only
+// consulted when the query starts with one of their keywords. `snippet_items` are searched
+// in `all` and `snippets` mode whatever the plugin's keywords.
 #[derive(Debug, Default, Clone)]
 pub struct PluginRegistry {
-    pub provider_items: Vec<SearchItem>,
     pub action_items: Vec<SearchItem>,
+    pub snippet_items: Vec<SearchItem>,
     pub actions_by_result_id: HashMap<String, PluginAction>,
+    pub process_plugins: Vec<Arc<ProcessPlugin>>,
+    pub triggers: Vec<PluginTrigger>,
+    pub capabilities: HashMap<String, Vec<PluginCapability>>,
+    // The folder holding each plugin's manifest and its `settings` section, for the
+    // `{plugin_dir}` and `{setting:NAME}` placeholders.
+    pub plugin_dirs: HashMap<String, PathBuf>,
+    pub settings: HashMap<String, BTreeMap<String, String>>,
+    pub item_presentation: HashMap<String, ItemPresentation>,
+    // Folders named by `snippets_dir`, so polling can stamp their files without
+    // reparsing the manifests.
+    pub snippet_dirs: Vec<PathBuf>,
     pub load_warnings: Vec<String>,
 }
 
@@ -36,148 +197,681 @@ impl PluginRegistry {
         }
 
         let mut registry = Self::default();
-        for path in &cfg.plugin_paths {
-            for manifest_path in discover_manifest_paths(path) {
-                match load_manifest(&manifest_path) {
-                    Ok(manifest) => append_manifest(&mut registry, manifest),
-                    Err(error) => registry.load_warnings.push(format!(
-                        "plugin manifest '{}' failed: {error}",
-                        manifest_path.display()
-                    )),
-                }
-            }
+        for (manifest_path, manifest) in
+            enabled_manifests(&cfg.plugin_paths, &mut registry.load_warnings)
+        {
+            append_manifest(&mut registry, manifest, &manifest_path, cfg);
         }
         registry
     }
+
+    pub fn query_process_plugins(&self, query: &str, limit: usize) -> Vec<SearchItem> {
+        query_processes(&self.process_plugins, query, limit)
+    }
+
+    pub fn start_process_query(&self, query: &str, limit: usize, wake: fn()) -> ProcessQuery {
+        ProcessQuery::start(&self.process_plugins, query, limit, wake)
+    }
+
+    // `gh issue 12` routes to the plugin with keyword `gh` and argument `issue 12`. The
+    // keyword must be followed by whitespace so a bare `gh` still searches normally.
+    pub fn route(&self, query: &str) -> Option<PluginRoute<'_>> {
+        let (head, rest) = query.trim_start().split_once(char::is_whitespace)?;
+        let keyword = head.to_ascii_lowercase();
+        let trigger = self
+            .triggers
+            .iter()
+            .find(|trigger| trigger.keywords.contains(&keyword))?;
+        Some(PluginRoute {
+            trigger,
+            keyword,
+            argument: rest.trim().to_string(),
+        })
+    }
+
+    pub fn scoped_results(&self, route: &PluginRoute<'_>, limit: usize) -> Vec<SearchItem> {
+        let process_results =
+            query_processes(route.trigger.process.as_slice(), &route.argument, limit);
+        self.scoped_results_with_answers(route, process_results, limit)
+    }
+
+    // Process answers come first; static actions are listed in manifest order until an
+    // argument narrows them.
+    pub fn scoped_results_with_answers(
+        &self,
+        route: &PluginRoute<'_>,
+        process_results: Vec<SearchItem>,
+        limit: usize,
+    ) -> Vec<SearchItem> {
+        let mut results = process_results;
+        results.truncate(limit);
+        let remaining = limit.saturating_sub(results.len());
+        if route.argument.is_empty() {
+            results.extend(route.trigger.action_items.iter().take(remaining).cloned());
+        } else {
+            results.extend(crate::search::search_with_filter(
+                &route.trigger.action_items,
+                &route.argument,
+                remaining,
+                &crate::search::SearchFilter {
+                    mode: crate::config::SearchMode::Actions,
+                    ..crate::search::SearchFilter::default()
+                },
+            ));
+        }
+        results
+    }
+
+    // Fills in icons and secondary actions for plugin `provider_items` among `items`.
+    pub fn present(&self, items: &mut [SearchItem]) {
+        if self.item_presentation.is_empty() {
+            return;
+        }
+        for item in items {
+            if let Some(presentation) = self.item_presentation.get(&item.id) {
+                item.icon = presentation.icon.clone();
+                item.secondary_actions = presentation.secondary_actions.clone();
+            }
+        }
+    }
+
+    pub fn action(&self, result_id: &str) -> Option<PluginAction> {
+        if let Some(action) = self.actions_by_result_id.get(result_id) {
+            return Some(action.clone());
+        }
+        self.process_plugins
+            .iter()
+            .chain(
+                self.triggers
+                    .iter()
+                    .filter_map(|trigger| trigger.process.as_ref()),
+            )
+            .find_map(|plugin| plugin.action(result_id))
+    }
+
+    pub fn authorize(
+        &self,
+        cfg: &Config,
+        plugin_id: &str,
+        capability: PluginCapability,
+    ) -> Result<(), String> {
+        let declared = self
+            .capabilities
+            .get(plugin_id)
+            .is_some_and(|capabilities| capabilities.contains(&capability));
+        if !declared {
+            return Err(format!(
+                "plugin '{plugin_id}' does not declare the '{}' capability",
+                capability.as_str()
+            ));
+        }
+        if capability == PluginCapability::RunCommands && cfg.plugins_safe_mode {
+            return Err(
+                "plugin command execution blocked: plugins_safe_mode is enabled in config"
+                    .to_string(),
+            );
+        }
+        if !is_capability_granted(cfg, plugin_id, capability) {
+            return Err(format!(
+                "plugin '{plugin_id}' needs '{}' granted in plugin_grants (\"{plugin_id}:{}\")",
+                capability.as_str(),
+                capability.as_str()
+            ));
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(default)]
-struct PluginManifest {
-    id: String,
-    name: String,
-    version: String,
-    enabled: bool,
-    provider_items: Vec<ManifestProviderItem>,
-    actions: Vec<ManifestAction>,
+// What changed between two loads of the plugin registry, for the reload log line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PluginRegistryDiff {
+    pub added_plugins: Vec<String>,
+    pub removed_plugins: Vec<String>,
+    pub added_actions: Vec<String>,
+    pub removed_actions: Vec<String>,
+    pub changed_actions: Vec<String>,
+    pub new_warnings: Vec<String>,
 }
 
-impl Default for PluginManifest {
-    fn default() -> Self {
-        Self {
-            id: String::new(),
-            name: String::new(),
-            version: String::new(),
-            enabled: true,
-            provider_items: Vec::new(),
-            actions: Vec::new(),
+impl PluginRegistryDiff {
+    pub fn between(previous: &PluginRegistry, next: &PluginRegistry) -> Self {
+        let mut diff = Self {
+            added_plugins: sorted_missing(next.capabilities.keys(), &previous.capabilities),
+            removed_plugins: sorted_missing(previous.capabilities.keys(), &next.capabilities),
+            added_actions: sorted_missing(
+                next.actions_by_result_id.keys(),
+                &previous.actions_by_result_id,
+            ),
+            removed_actions: sorted_missing(
+                previous.actions_by_result_id.keys(),
+                &next.actions_by_result_id,
+            ),
+            ..Self::default()
+        };
+        diff.changed_actions = next
+            .actions_by_result_id
+            .iter()
+            .filter(|(id, action)| {
+                previous
+                    .actions_by_result_id
+                    .get(*id)
+                    .is_some_and(|old| old != *action)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        diff.changed_actions.sort();
+        diff.new_warnings = next
+            .load_warnings
+            .iter()
+            .filter(|warning| !previous.load_warnings.contains(warning))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn plugins_changed(&self) -> bool {
+        !self.added_plugins.is_empty() || !self.removed_plugins.is_empty()
+    }
+}
+
+fn sorted_missing<'a, V>(
+    keys: impl Iterator<Item = &'a String>,
+    other: &HashMap<String, V>,
+) -> Vec<String> {
+    let mut missing: Vec<String> = keys
+        .filter(|key| !other.contains_key(*key))
+        .cloned()
+        .collect();
+    missing.sort();
+    missing
+}
+
+// Reloads every manifest and swaps the registry in one step. Process plugins whose spec
+// did not change keep their running process.
+pub fn reload_registry(registry: &mut PluginRegistry, cfg: &Config) -> PluginRegistryDiff {
+    let mut next = PluginRegistry::load_from_config(cfg);
+    let running: Vec<Arc<ProcessPlugin>> = registry
+        .process_plugins
+        .iter()
+        .chain(
+            registry
+                .triggers
+                .iter()
+                .filter_map(|trigger| trigger.process.as_ref()),
+        )
+        .cloned()
+        .collect();
+    let adopt = |plugin: &mut Arc<ProcessPlugin>| {
+        if let Some(previous) = running
+            .iter()
+            .find(|previous| previous.spec() == plugin.spec())
+        {
+            *plugin = Arc::clone(previous);
         }
+    };
+    next.process_plugins.iter_mut().for_each(adopt);
+    next.triggers
+        .iter_mut()
+        .filter_map(|trigger| trigger.process.as_mut())
+        .for_each(adopt);
+
+    let diff = PluginRegistryDiff::between(registry, &next);
+    *registry = next;
+    diff
+}
+
+// Metadata of every manifest and `snippets_dir` file; the runtime polls it to notice edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestStamp {
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub len: u64,
+}
+
+pub fn manifest_stamps(cfg: &Config, registry: &PluginRegistry) -> Vec<ManifestStamp> {
+    if !cfg.plugins_enabled {
+        return Vec::new();
     }
+    let snippets = registry
+        .snippet_dirs
+        .iter()
+        .flat_map(|dir| snippet_files(dir));
+    cfg.plugin_paths
+        .iter()
+        .flat_map(|path| discover_manifest_paths(path))
+        .chain(snippets)
+        .map(|path| {
+            let metadata = std::fs::metadata(&path).ok();
+            ManifestStamp {
+                modified: metadata.as_ref().and_then(|meta| meta.modified().ok()),
+                len: metadata.map(|meta| meta.len()).unwrap_or(0),
+                path,
+            }
+        })
+        .collect()
+}
+
+// Blocks for at most `PROCESS_QUERY_BUDGET`; a slow or crashed plugin only loses its own
+// results for this query.
+fn query_processes(plugins: &[Arc<ProcessPlugin>], query: &str, limit: usize) -> Vec<SearchItem> {
+    let mut pending = ProcessQuery::start(plugins, query, limit, || {});
+    pending.wait(PROCESS_QUERY_BUDGET);
+    pending.items
+}
+
+type ProcessOutcome = (String, Result<Vec<SearchItem>, PluginHostError>);
+
+// One query sent to process plugins in parallel; `wake` runs after each answer.
+#[derive(Debug)]
+pub struct ProcessQuery {
+    outcomes: Option<mpsc::Receiver<ProcessOutcome>>,
+    items: Vec<SearchItem>,
 }
 
-#[derive(Debug, Deserialize, Default)]
-#[serde(default)]
-struct ManifestProviderItem {
-    id: String,
-    kind: String,
-    title: String,
-    path: String,
+impl ProcessQuery {
+    pub fn start(plugins: &[Arc<ProcessPlugin>], query: &str, limit: usize, wake: fn()) -> Self {
+        if plugins.is_empty() || query.trim().is_empty() || limit == 0 {
+            return Self {
+                outcomes: None,
+                items: Vec::new(),
+            };
+        }
+        let (sender, outcomes) = mpsc::channel();
+        for plugin in plugins {
+            let sender = sender.clone();
+            let plugin_id = plugin.plugin_id().to_string();
+            plugin.submit(query, limit, move |outcome| {
+                let _ = sender.send((plugin_id, outcome));
+                wake();
+            });
+        }
+        Self {
+            outcomes: Some(outcomes),
+            items: Vec::new(),
+        }
+    }
+
+    pub fn items(&self) -> &[SearchItem] {
+        &self.items
+    }
+
+    // All plugins have answered, failed or dropped the query for a newer one.
+    pub fn is_complete(&self) -> bool {
+        self.outcomes.is_none()
+    }
+
+    // Takes in answers that have arrived; true when any brought results.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(outcomes) = &self.outcomes {
+            match outcomes.try_recv() {
+                Ok(outcome) => changed |= self.accept(outcome),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => self.outcomes = None,
+            }
+        }
+        changed
+    }
+
+    pub fn wait(&mut self, budget: Duration) {
+        let deadline = Instant::now() + budget;
+        while let Some(outcomes) = &self.outcomes {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match outcomes.recv_timeout(remaining) {
+                Ok(outcome) => {
+                    self.accept(outcome);
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => self.outcomes = None,
+            }
+        }
+    }
+
+    fn accept(&mut self, (plugin_id, outcome): ProcessOutcome) -> bool {
+        match outcome {
+            Ok(results) => {
+                let changed = !results.is_empty();
+                self.items.extend(results);
+                changed
+            }
+            Err(PluginHostError::BackingOff(_)) => false,
+            Err(error) => {
+                crate::logging::warn(&format!("[nex] plugin {plugin_id} query failed: {error}"));
+                false
+            }
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(default)]
-struct ManifestAction {
-    id: String,
-    title: String,
-    subtitle: String,
-    keywords: Vec<String>,
-    #[serde(rename = "type")]
-    action_type: String,
-    path: String,
-    command: String,
-    args: Vec<String>,
+// Serves one plugin's `provider_items` through the regular discovery pipeline, so they
+// are stored, ranked and pruned like every other indexed item. The manifest is re-read
+// on each discovery; its mtime drives the change stamp.
+pub struct PluginItemsProvider {
+    name: String,
+    plugin_id: String,
+    manifest_path: PathBuf,
 }
 
-impl Default for ManifestAction {
-    fn default() -> Self {
+impl PluginItemsProvider {
+    pub fn new(plugin_id: &str, manifest_path: PathBuf) -> Self {
         Self {
-            id: String::new(),
-            title: String::new(),
-            subtitle: String::new(),
-            keywords: Vec::new(),
-            action_type: "open_path".to_string(),
-            path: String::new(),
-            command: String::new(),
-            args: Vec::new(),
+            name: format!("{PLUGIN_PROVIDER_PREFIX}{plugin_id}"),
+            plugin_id: plugin_id.to_string(),
+            manifest_path,
         }
     }
 }
 
-fn discover_manifest_paths(path: &Path) -> Vec<PathBuf> {
-    if path.is_file() {
-        return vec![path.to_path_buf()];
+impl DiscoveryProvider for PluginItemsProvider {
+    fn provider_name(&self) -> &str {
+        &self.name
+    }
+
+    fn discover(&self) -> Result<Vec<SearchItem>, ProviderError> {
+        let manifest = load_manifest(&self.manifest_path)
+            .map_err(|errors| ProviderError::new(errors.join("; ")))?;
+        if manifest.id.trim() != self.plugin_id {
+            return Err(ProviderError::new(format!(
+                "plugin manifest '{}' changed id to '{}'",
+                self.manifest_path.display(),
+                manifest.id.trim()
+            )));
+        }
+        if !manifest.enabled {
+            return Ok(Vec::new());
+        }
+        Ok(manifest_items(&self.plugin_id, manifest.provider_items))
     }
-    if !path.is_dir() {
+
+    fn change_stamp(&self) -> Option<String> {
+        let (modified_secs, len) = std::fs::metadata(&self.manifest_path)
+            .map(|meta| {
+                let modified_secs = meta
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or(0);
+                (modified_secs, meta.len())
+            })
+            .unwrap_or((0, 0));
+        Some(format!(
+            "{}:{modified_secs}:{len}",
+            self.manifest_path.display()
+        ))
+    }
+}
+
+// One provider per enabled plugin manifest. A broken manifest keeps a provider under the
+// id it names, so its discovery fails and backs off instead of its items being pruned.
+pub fn item_providers_from_config(cfg: &Config) -> Vec<PluginItemsProvider> {
+    if !cfg.plugins_enabled {
         return Vec::new();
     }
 
-    let mut out = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(path) {
-        for entry in entries.flatten() {
-            let entry_path = entry.path();
-            if entry_path.is_file()
-                && entry_path
-                    .extension()
-                    .and_then(|v| v.to_str())
-                    .is_some_and(|v| v.eq_ignore_ascii_case("json"))
+    item_owners(&cfg.plugin_paths)
+        .owners
+        .into_iter()
+        .map(|(plugin_id, manifest_path)| PluginItemsProvider::new(&plugin_id, manifest_path))
+        .collect()
+}
+
+// Plugins in `plugin_paths` whose indexed items must be kept. None while a manifest
+// fails to load without naming its id, since it may be any plugin.
+pub fn retained_plugin_ids(cfg: &Config) -> Option<HashSet<String>> {
+    if !cfg.plugins_enabled {
+        return Some(HashSet::new());
+    }
+    let scan = item_owners(&cfg.plugin_paths);
+    scan.all_attributed.then(|| {
+        scan.owners
+            .into_iter()
+            .map(|(plugin_id, _)| plugin_id)
+            .collect()
+    })
+}
+
+struct ItemOwners {
+    owners: Vec<(String, PathBuf)>,
+    all_attributed: bool,
+}
+
+// Enabled manifests and broken ones, in load order, with the first manifest for an id
+// winning.
+fn item_owners(plugin_paths: &[PathBuf]) -> ItemOwners {
+    let mut scan = ItemOwners {
+        owners: Vec::new(),
+        all_attributed: true,
+    };
+    for path in plugin_paths {
+        for manifest_path in discover_manifest_paths(path) {
+            let plugin_id = match load_manifest(&manifest_path) {
+                Ok(manifest) if manifest.enabled => manifest.id.trim().to_string(),
+                Ok(_) => continue,
+                Err(_) => match manifest_id_hint(&manifest_path) {
+                    Some(plugin_id) => plugin_id,
+                    None => {
+                        scan.all_attributed = false;
+                        continue;
+                    }
+                },
+            };
+            if scan.owners.iter().all(|(other, _)| *other != plugin_id) {
+                scan.owners.push((plugin_id, manifest_path));
+            }
+        }
+    }
+    scan
+}
+
+// Valid, enabled manifests in load order. A plugin id seen earlier wins; later manifests
+// with the same id are skipped with a warning.
+fn enabled_manifests(
+    plugin_paths: &[PathBuf],
+    warnings: &mut Vec<String>,
+) -> Vec<(PathBuf, PluginManifest)> {
+    let mut loaded: Vec<(PathBuf, PluginManifest)> = Vec::new();
+    for path in plugin_paths {
+        for manifest_path in discover_manifest_paths(path) {
+            let manifest = match load_manifest(&manifest_path) {
+                Ok(manifest) => manifest,
+                Err(errors) => {
+                    warnings.extend(errors.into_iter().map(|error| {
+                        format!(
+                            "plugin manifest '{}' invalid: {error}",
+                            manifest_path.display()
+                        )
+                    }));
+                    continue;
+                }
+            };
+            if !manifest.enabled {
+                continue;
+            }
+            let plugin_id = manifest.id.trim();
+            if let Some((first_path, _)) = loaded
+                .iter()
+                .find(|(_, other)| other.id.trim() == plugin_id)
             {
-                out.push(entry_path);
+                warnings.push(format!(
+                    "plugin manifest '{}' skipped: id '{plugin_id}' is already loaded from '{}'",
+                    manifest_path.display(),
+                    first_path.display()
+                ));
+                continue;
             }
+            loaded.push((manifest_path, manifest));
         }
     }
-    out
+    loaded
 }
 
-fn load_manifest(path: &Path) -> Result<PluginManifest, String> {
-    let raw = std::fs::read_to_string(path)
-        .map_err(|e| format!("read failed for '{}': {e}", path.display()))?;
-    let manifest: PluginManifest = serde_json::from_str(&raw)
-        .map_err(|e| format!("invalid json in '{}': {e}", path.display()))?;
-    if manifest.id.trim().is_empty() {
-        return Err("missing plugin id".to_string());
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestReport {
+    pub path: PathBuf,
+    pub plugin_id: Option<String>,
+    pub version: Option<String>,
+    pub errors: Vec<String>,
+}
+
+impl ManifestReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
     }
-    Ok(manifest)
 }
 
-fn append_manifest(registry: &mut PluginRegistry, manifest: PluginManifest) {
-    if !manifest.enabled {
-        return;
+// Checks every manifest under `path` (a manifest file or a plugin folder) the way the
+// runtime loads them, including duplicate ids. Backs `nex --validate-plugin`.
+pub fn validate_plugin_path(path: &Path) -> Vec<ManifestReport> {
+    let mut reports: Vec<ManifestReport> = Vec::new();
+    for manifest_path in discover_manifest_paths(path) {
+        let mut report = ManifestReport {
+            path: manifest_path.clone(),
+            plugin_id: None,
+            version: None,
+            errors: Vec::new(),
+        };
+        match load_manifest(&manifest_path) {
+            Ok(manifest) => {
+                let plugin_id = manifest.id.trim().to_string();
+                if let Some(first) = reports
+                    .iter()
+                    .find(|other| other.plugin_id.as_deref() == Some(plugin_id.as_str()))
+                {
+                    report.errors.push(format!(
+                        "id: '{plugin_id}' is already used by '{}'",
+                        first.path.display()
+                    ));
+                }
+                report.plugin_id = Some(plugin_id);
+                report.version =
+                    Some(manifest.version.trim().to_string()).filter(|version| !version.is_empty());
+            }
+            Err(errors) => report.errors = errors,
+        }
+        reports.push(report);
     }
+    reports
+}
+
+// `plugin:<plugin_id>:item:<item_id>`
+pub fn is_plugin_item_id(id: &str) -> bool {
+    id.starts_with(PLUGIN_PROVIDER_PREFIX) && id.contains(":item:")
+}
+
+pub fn plugin_item_id_prefix(provider_name: &str) -> String {
+    format!("{provider_name}:item:")
+}
 
+fn append_manifest(
+    registry: &mut PluginRegistry,
+    manifest: PluginManifest,
+    manifest_path: &Path,
+    cfg: &Config,
+) {
     let plugin_id = manifest.id.trim().to_string();
+    let capabilities = manifest
+        .capabilities
+        .iter()
+        .filter_map(|capability| PluginCapability::parse(capability))
+        .collect::<Vec<_>>();
+    for capability in &capabilities {
+        if !is_capability_granted(cfg, &plugin_id, *capability) {
+            registry.load_warnings.push(format!(
+                "plugin '{plugin_id}' declares '{}' but plugin_grants does not grant it",
+                capability.as_str()
+            ));
+        }
+    }
+    registry
+        .capabilities
+        .insert(plugin_id.clone(), capabilities);
+    let manifest_dir = manifest_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let settings = manifest.settings_text();
+    registry
+        .plugin_dirs
+        .insert(plugin_id.clone(), manifest_dir.clone());
+    registry
+        .settings
+        .insert(plugin_id.clone(), settings.clone());
     let plugin_label = if manifest.name.trim().is_empty() {
         plugin_id.clone()
     } else {
         manifest.name.trim().to_string()
     };
 
-    for item in manifest.provider_items {
+    for item in &manifest.provider_items {
         let item_id = item.id.trim();
-        let title = item.title.trim();
-        if item_id.is_empty() || title.is_empty() {
+        if item_id.is_empty() || item.title.trim().is_empty() {
             continue;
         }
-        let result_id = format!("plugin:{plugin_id}:item:{item_id}");
-        let kind = if item.kind.trim().is_empty() {
-            "file".to_string()
+        let result_id = format!("{PLUGIN_PROVIDER_PREFIX}{plugin_id}:item:{item_id}");
+        let secondary_actions = append_secondary_actions(
+            registry,
+            &plugin_id,
+            &plugin_label,
+            &result_id,
+            &item.secondary_actions,
+            &manifest_dir,
+        );
+        let icon = resolve_icon(&manifest_dir, &item.icon);
+        if !icon.is_empty() || !secondary_actions.is_empty() {
+            registry.item_presentation.insert(
+                result_id,
+                ItemPresentation {
+                    icon,
+                    secondary_actions,
+                },
+            );
+        }
+    }
+
+    append_snippets(
+        registry,
+        &plugin_id,
+        &plugin_label,
+        &manifest,
+        &manifest_dir,
+    );
+
+    // A process runs an arbitrary executable, so it needs `run_commands` like a command
+    // action and leaves the same audit line.
+    let mut process_plugin = None;
+    if let Some(process) = &manifest.process {
+        let decision = registry.authorize(cfg, &plugin_id, PluginCapability::RunCommands);
+        crate::logging::info(&format!(
+            "[nex] plugin_audit plugin={plugin_id} action=process capability={} decision={} target={}",
+            PluginCapability::RunCommands.as_str(),
+            if decision.is_ok() { "allowed" } else { "denied" },
+            process.command.trim()
+        ));
+        if let Err(reason) = decision {
+            registry.load_warnings.push(format!(
+                "plugin '{plugin_id}' process not started: {reason}"
+            ));
+        } else if process.command.trim().is_empty() {
+            registry
+                .load_warnings
+                .push(format!("plugin '{plugin_id}' process is missing a command"));
         } else {
-            item.kind.trim().to_string()
-        };
-        registry
-            .provider_items
-            .push(SearchItem::new(&result_id, &kind, title, item.path.trim()));
+            process_plugin = Some(Arc::new(ProcessPlugin::new(process_spec(
+                &plugin_id,
+                &plugin_label,
+                process,
+                &manifest_dir,
+                &settings,
+            ))));
+        }
     }
 
+    let mut action_items = Vec::new();
     for action in manifest.actions {
         let action_id = action.id.trim();
         let action_title = action.title.trim();
@@ -185,44 +879,293 @@ fn append_manifest(registry: &mut PluginRegistry, manifest: PluginManifest) {
             continue;
         }
         let result_id = format!("plugin:{plugin_id}:action:{action_id}");
-        let subtitle = if action.subtitle.trim().is_empty() {
-            format!("{plugin_label} plugin action")
-        } else {
-            action.subtitle.trim().to_string()
-        };
-        let kind = parse_action_kind(&action);
-        let plugin_action = PluginAction {
-            result_id: result_id.clone(),
-            plugin_id: plugin_id.clone(),
-            action_id: action_id.to_string(),
-            title: action_title.to_string(),
-            subtitle: subtitle.clone(),
-            keywords: action.keywords,
-            kind,
-        };
+        let plugin_action = plugin_action(
+            &plugin_id,
+            &plugin_label,
+            &result_id,
+            &action,
+            &manifest_dir,
+        );
         let keyword_suffix = if plugin_action.keywords.is_empty() {
             String::new()
         } else {
             format!(" {}", plugin_action.keywords.join(" "))
         };
-        registry.action_items.push(SearchItem::new(
+        let secondary_actions = append_secondary_actions(
+            registry,
+            &plugin_id,
+            &plugin_label,
+            &result_id,
+            &action.secondary_actions,
+            &manifest_dir,
+        );
+        action_items.push(
+            SearchItem::new(
+                &result_id,
+                "action",
+                action_title,
+                &format!("{}{keyword_suffix}", plugin_action.subtitle),
+            )
+            .with_icon(&resolve_icon(&manifest_dir, &action.icon))
+            .with_secondary_actions(secondary_actions),
+        );
+        registry
+            .actions_by_result_id
+            .insert(result_id, plugin_action);
+    }
+
+    let keywords = trigger_keywords(registry, &plugin_id, &manifest.trigger_keywords);
+    // Plugins without keywords have no scope to live in, so they are always global.
+    let global = keywords.is_empty() || manifest.global.unwrap_or(false);
+    if global {
+        registry.action_items.extend(action_items.iter().cloned());
+        registry.process_plugins.extend(process_plugin.clone());
+    }
+    if !keywords.is_empty() {
+        registry.triggers.push(PluginTrigger {
+            plugin_id,
+            label: plugin_label,
+            keywords,
+            global,
+            action_items,
+            process: process_plugin,
+        });
+    }
+}
+
+fn plugin_action(
+    plugin_id: &str,
+    plugin_label: &str,
+    result_id: &str,
+    action: &ManifestAction,
+    manifest_dir: &Path,
+) -> PluginAction {
+    let subtitle = if action.subtitle.trim().is_empty() {
+        format!("{plugin_label} plugin action")
+    } else {
+        action.subtitle.trim().to_string()
+    };
+    PluginAction {
+        result_id: result_id.to_string(),
+        plugin_id: plugin_id.to_string(),
+        action_id: action.id.trim().to_string(),
+        title: action.title.trim().to_string(),
+        subtitle,
+        keywords: action.keywords.clone(),
+        kind: resolve_action_paths(parse_action_kind(action), manifest_dir),
+    }
+}
+
+// Inline `snippets`, then one per `snippets_dir` file (`address.txt` becomes `address`);
+// inline ids win.
+fn append_snippets(
+    registry: &mut PluginRegistry,
+    plugin_id: &str,
+    plugin_label: &str,
+    manifest: &PluginManifest,
+    manifest_dir: &Path,
+) {
+    let mut snippets: Vec<ManifestSnippet> = manifest
+        .snippets
+        .iter()
+        .map(|snippet| ManifestSnippet {
+            id: snippet.id.trim().to_string(),
+            title: snippet.title.trim().to_string(),
+            text: snippet.text.clone(),
+            keywords: snippet.keywords.clone(),
+        })
+        .collect();
+    if !manifest.snippets_dir.trim().is_empty() {
+        let dir = manifest_dir.join(manifest.snippets_dir.trim());
+        if !dir.is_dir() {
+            registry.load_warnings.push(format!(
+                "plugin '{plugin_id}' snippets_dir '{}' is not a folder",
+                dir.display()
+            ));
+        }
+        registry.snippet_dirs.push(dir.clone());
+        for path in snippet_files(&dir) {
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if snippets.iter().any(|snippet| snippet.id == id) {
+                continue;
+            }
+            match std::fs::read_to_string(&path) {
+                Ok(text) => snippets.push(ManifestSnippet {
+                    id: id.to_string(),
+                    title: id.to_string(),
+                    text: text.trim_end_matches(['\r', '\n']).to_string(),
+                    keywords: Vec::new(),
+                }),
+                Err(error) => registry.load_warnings.push(format!(
+                    "plugin '{plugin_id}' snippet '{}' unreadable: {error}",
+                    path.display()
+                )),
+            }
+        }
+    }
+
+    for snippet in snippets {
+        if snippet.id.is_empty() || snippet.title.is_empty() {
+            continue;
+        }
+        let result_id = format!("{PLUGIN_PROVIDER_PREFIX}{plugin_id}:snippet:{}", snippet.id);
+        let preview = snippet
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let subtitle = if snippet.keywords.is_empty() {
+            preview
+        } else {
+            format!("{preview} {}", snippet.keywords.join(" "))
+        };
+        registry.snippet_items.push(SearchItem::new(
             &result_id,
-            "action",
-            action_title,
-            &format!("{subtitle}{keyword_suffix}"),
+            "snippet",
+            &snippet.title,
+            &subtitle,
         ));
+        registry.actions_by_result_id.insert(
+            result_id.clone(),
+            PluginAction {
+                result_id,
+                plugin_id: plugin_id.to_string(),
+                action_id: snippet.id,
+                title: snippet.title,
+                subtitle: format!("{plugin_label} snippet"),
+                keywords: snippet.keywords,
+                kind: PluginActionKind::Snippet { text: snippet.text },
+            },
+        );
+    }
+}
+
+// Secondary actions run like any plugin action, under `<parent result id>:secondary:<id>`.
+fn append_secondary_actions(
+    registry: &mut PluginRegistry,
+    plugin_id: &str,
+    plugin_label: &str,
+    parent_result_id: &str,
+    actions: &[ManifestAction],
+    manifest_dir: &Path,
+) -> Vec<ItemAction> {
+    let mut out = Vec::with_capacity(actions.len());
+    for action in actions {
+        let action_id = action.id.trim();
+        if action_id.is_empty() || action.title.trim().is_empty() {
+            continue;
+        }
+        let result_id = format!("{parent_result_id}:secondary:{action_id}");
+        out.push(ItemAction {
+            id: result_id.clone(),
+            title: action.title.trim().to_string(),
+            icon: resolve_icon(manifest_dir, &action.icon),
+        });
+        let plugin_action =
+            plugin_action(plugin_id, plugin_label, &result_id, action, manifest_dir);
         registry
             .actions_by_result_id
             .insert(result_id, plugin_action);
     }
+    out
+}
+
+// Keywords are single lowercase words; one already claimed by another plugin is skipped.
+fn trigger_keywords(registry: &mut PluginRegistry, plugin_id: &str, raw: &[String]) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::with_capacity(raw.len());
+    for keyword in raw {
+        let keyword = keyword.trim().to_ascii_lowercase();
+        if keyword.is_empty() || keyword.contains(char::is_whitespace) {
+            registry.load_warnings.push(format!(
+                "plugin '{plugin_id}' trigger keyword '{keyword}' must be a single word"
+            ));
+            continue;
+        }
+        if let Some(owner) = registry
+            .triggers
+            .iter()
+            .find(|trigger| trigger.keywords.contains(&keyword))
+        {
+            registry.load_warnings.push(format!(
+                "plugin '{plugin_id}' trigger keyword '{keyword}' is already used by '{}'",
+                owner.plugin_id
+            ));
+            continue;
+        }
+        if !keywords.contains(&keyword) {
+            keywords.push(keyword);
+        }
+    }
+    keywords
+}
+
+// Runs in the manifest's folder, with `NEX_PLUGIN_DIR` and `NEX_PLUGIN_SETTINGS` (JSON) set.
+fn process_spec(
+    plugin_id: &str,
+    plugin_label: &str,
+    process: &ManifestProcess,
+    manifest_dir: &Path,
+    settings: &BTreeMap<String, String>,
+) -> ProcessPluginSpec {
+    let command = PathBuf::from(resolve_bundle_path(manifest_dir, &process.command));
+    let env = vec![
+        (
+            "NEX_PLUGIN_DIR".to_string(),
+            manifest_dir.to_string_lossy().into_owned(),
+        ),
+        (
+            "NEX_PLUGIN_SETTINGS".to_string(),
+            serde_json::to_string(settings).expect("plugin settings should serialize"),
+        ),
+    ];
+    let timeout_ms = process
+        .timeout_ms
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT_MS)
+        .clamp(1, MAX_QUERY_TIMEOUT_MS);
+    ProcessPluginSpec {
+        plugin_id: plugin_id.to_string(),
+        label: plugin_label.to_string(),
+        command,
+        args: process.args.clone(),
+        working_dir: Some(manifest_dir.to_path_buf()).filter(|dir| !dir.as_os_str().is_empty()),
+        env,
+        timeout: Duration::from_millis(timeout_ms).min(PROCESS_QUERY_BUDGET),
+    }
+}
+
+fn manifest_items(plugin_id: &str, items: Vec<ManifestProviderItem>) -> Vec<SearchItem> {
+    let mut out = Vec::with_capacity(items.len());
+    for item in items {
+        let item_id = item.id.trim();
+        let title = item.title.trim();
+        if item_id.is_empty() || title.is_empty() {
+            continue;
+        }
+        let result_id = format!("{PLUGIN_PROVIDER_PREFIX}{plugin_id}:item:{item_id}");
+        let kind = if item.kind.trim().is_empty() {
+            "file".to_string()
+        } else {
+            item.kind.trim().to_string()
+        };
+        out.push(SearchItem::new(&result_id, &kind, title, item.path.trim()));
+    }
+    out
 }
 
 fn parse_action_kind(action: &ManifestAction) -> PluginActionKind {
     let normalized = action.action_type.trim().to_ascii_lowercase();
+    if normalized == "copy" {
+        return PluginActionKind::Copy {
+            text: action.text.clone(),
+        };
+    }
     if normalized == "command" {
         return PluginActionKind::Command {
             command: action.command.trim().to_string(),
             args: action.args.clone(),
+            output: CommandOutput::parse(&action.output),
         };
     }
     PluginActionKind::OpenPath {
@@ -230,10 +1173,244 @@ fn parse_action_kind(action: &ManifestAction) -> PluginActionKind {
     }
 }
 
+fn resolve_action_paths(kind: PluginActionKind, manifest_dir: &Path) -> PluginActionKind {
+    match kind {
+        PluginActionKind::OpenPath { path } => PluginActionKind::OpenPath {
+            path: resolve_bundle_path(manifest_dir, &path),
+        },
+        PluginActionKind::Command {
+            command,
+            args,
+            output,
+        } => PluginActionKind::Command {
+            command: resolve_bundle_path(manifest_dir, &command),
+            args,
+            output,
+        },
+        kind @ (PluginActionKind::Copy { .. } | PluginActionKind::Snippet { .. }) => kind,
+    }
+}
+
+/// Runs queries against plugin manifests through the overlay's search path, without
+/// launching anything or reading the real clipboard, for golden tests of a plugin.
+///
+/// ```
+/// # use nex_core::plugin_sdk::PluginTestHarness;
+/// # let dir = std::env::temp_dir().join(format!("nex-harness-doc-{}", std::process::id()));
+/// # std::fs::create_dir_all(&dir).unwrap();
+/// # let manifest = r#"{"id": "github", "trigger_keywords": ["gh"], "capabilities": ["run_commands"],
+/// #     "actions": [{"id": "issues", "title": "Issues", "type": "command", "command": "gh"}]}"#;
+/// # std::fs::write(dir.join("github.json"), manifest).unwrap();
+/// let harness = PluginTestHarness::load(&dir).unwrap().with_date("2026-01-02");
+/// let results = harness.query("gh issues").unwrap();
+/// assert_eq!(results[0].item.id, "plugin:github:action:issues");
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub struct PluginTestHarness {
+    cfg: Config,
+    service: CoreService,
+    registry: PluginRegistry,
+    clipboard: String,
+    date: Option<String>,
+}
+
+/// One ranked result. `action` is set for results a plugin owns.
+#[derive(Debug, Clone)]
+pub struct HarnessResult {
+    pub item: SearchItem,
+    pub action: Option<ResolvedAction>,
+}
+
+/// A plugin action with its placeholders expanded for the query. `cursor` is where a
+/// snippet's `{cursor}` stood.
+#[derive(Debug, Clone)]
+pub struct ResolvedAction {
+    pub action: PluginAction,
+    pub expanded: Result<PluginActionKind, PlaceholderError>,
+    pub cursor: Option<usize>,
+}
+
+impl PluginTestHarness {
+    /// Loads a manifest file or plugin folder with every declared capability granted.
+    /// An invalid manifest fails with one `<manifest>: <field>: <problem>` line per error.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Vec<String>> {
+        let path = path.as_ref();
+        let reports = validate_plugin_path(path);
+        if reports.is_empty() {
+            return Err(vec![format!(
+                "{}: no plugin manifests found",
+                path.display()
+            )]);
+        }
+        let errors: Vec<String> = reports
+            .iter()
+            .flat_map(|report| {
+                report
+                    .errors
+                    .iter()
+                    .map(|error| format!("{}: {error}", report.path.display()))
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let cfg = Config {
+            plugins_enabled: true,
+            plugin_paths: vec![path.to_path_buf()],
+            plugin_grants: reports
+                .iter()
+                .filter_map(|report| report.plugin_id.as_ref())
+                .map(|plugin_id| format!("{plugin_id}:*"))
+                .collect(),
+            plugins_safe_mode: false,
+            clipboard_enabled: false,
+            ..Config::default()
+        };
+        let db = crate::index_store::open_memory().map_err(|error| vec![error.to_string()])?;
+        let providers = item_providers_from_config(&cfg)
+            .into_iter()
+            .map(|provider| Box::new(provider) as Box<dyn DiscoveryProvider>)
+            .collect();
+        let service = CoreService::with_connection(cfg.clone(), db)
+            .map_err(|error| vec![error.to_string()])?
+            .with_providers(providers);
+        let report = service
+            .rebuild_index_with_report()
+            .map_err(|error| vec![error.to_string()])?;
+        let provider_errors: Vec<String> = report
+            .providers
+            .iter()
+            .filter_map(|provider| {
+                let error = provider.error.as_ref()?;
+                Some(format!("{}: {error}", provider.provider))
+            })
+            .collect();
+        if !provider_errors.is_empty() {
+            return Err(provider_errors);
+        }
+
+        let registry = PluginRegistry::load_from_config(&cfg);
+        Ok(Self {
+            cfg,
+            service,
+            registry,
+            clipboard: String::new(),
+            date: None,
+        })
+    }
+
+    /// What `{clipboard}` expands to.
+    pub fn with_clipboard(mut self, text: &str) -> Self {
+        self.clipboard = text.to_string();
+        self
+    }
+
+    /// Pins `{date}` so golden output does not change from day to day.
+    pub fn with_date(mut self, date: &str) -> Self {
+        self.date = Some(date.to_string());
+        self
+    }
+
+    pub fn registry(&self) -> &PluginRegistry {
+        &self.registry
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.registry.load_warnings
+    }
+
+    pub fn query(&self, text: &str) -> Result<Vec<HarnessResult>, String> {
+        self.query_with_limit(text, self.cfg.max_results as usize)
+    }
+
+    pub fn query_with_limit(&self, text: &str, limit: usize) -> Result<Vec<HarnessResult>, String> {
+        let parsed = crate::query_dsl::ParsedQuery::parse(text.trim(), self.cfg.search_dsl_enabled);
+        let items = crate::overlay_search::search_overlay_results(
+            &self.service,
+            &self.cfg,
+            &self.registry,
+            &parsed,
+            limit,
+        )?;
+        Ok(items
+            .iter()
+            .map(|item| HarnessResult {
+                item: item.clone(),
+                action: self.resolve(&item.id, text, &items),
+            })
+            .collect())
+    }
+
+    /// Resolves any plugin action id, including secondary actions, against `query`.
+    pub fn resolve_action(&self, result_id: &str, query: &str) -> Result<ResolvedAction, String> {
+        let results = self.query(query)?;
+        let items: Vec<SearchItem> = results.into_iter().map(|result| result.item).collect();
+        self.resolve(result_id, query, &items)
+            .ok_or_else(|| format!("unknown plugin action '{result_id}'"))
+    }
+
+    fn resolve(
+        &self,
+        result_id: &str,
+        query: &str,
+        results: &[SearchItem],
+    ) -> Option<ResolvedAction> {
+        let action = self.registry.action(result_id)?;
+        let mut context = crate::plugin_placeholders::placeholder_context(
+            &self.cfg,
+            &self.registry,
+            &action,
+            query,
+            results,
+            false,
+        );
+        context.clipboard = self.clipboard.clone();
+        // Declared capabilities are granted here, as in `load`.
+        context.read_env = self
+            .registry
+            .capabilities
+            .get(&action.plugin_id)
+            .is_some_and(|capabilities| capabilities.contains(&PluginCapability::ReadEnv));
+        if let Some(date) = &self.date {
+            context.date = date.clone();
+        }
+
+        let mut cursor = None;
+        let expanded = match &action.kind {
+            PluginActionKind::OpenPath { .. } => Ok(action.kind.clone()),
+            PluginActionKind::Command {
+                command,
+                args,
+                output,
+            } => expand_args(args, &context).map(|args| PluginActionKind::Command {
+                command: command.clone(),
+                args,
+                output: *output,
+            }),
+            PluginActionKind::Copy { text } => {
+                expand_placeholders(text, &context).map(|text| PluginActionKind::Copy { text })
+            }
+            PluginActionKind::Snippet { text } => expand_snippet(text, &context).map(|expanded| {
+                cursor = expanded.cursor;
+                PluginActionKind::Snippet {
+                    text: expanded.text,
+                }
+            }),
+        };
+        Some(ResolvedAction {
+            action,
+            expanded,
+            cursor,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::parse_action_kind;
-    use super::{ManifestAction, PluginActionKind};
+    use super::{CommandOutput, PluginActionKind};
+    use crate::plugin_manifest::ManifestAction;
 
     #[test]
     fn parses_command_action_kind() {
@@ -246,4 +1423,22 @@ mod tests {
         let kind = parse_action_kind(&action);
         assert!(matches!(kind, PluginActionKind::Command { .. }));
     }
+
+    #[test]
+    fn parses_command_output_mode() {
+        let action = ManifestAction {
+            action_type: "command".to_string(),
+            command: "git".to_string(),
+            output: "Clipboard".to_string(),
+            ..Default::default()
+        };
+        let kind = parse_action_kind(&action);
+        assert!(matches!(
+            kind,
+            PluginActionKind::Command {
+                output: CommandOutput::Clipboard,
+                ..
+            }
+        ));
+    }
 }
@@ -19,6 +19,7 @@ pub struct ParsedQuery {
     pub exclude_terms: Vec<String>,
     pub modified_within: Option<TimeFilterWindow>,
     pub created_within: Option<TimeFilterWindow>,
+    pub content_query: Option<String>,
     pub command_mode: bool,
 }
 
@@ -36,6 +37,7 @@ impl ParsedQuery {
                 exclude_terms: Vec::new(),
                 modified_within: None,
                 created_within: None,
+                content_query: None,
                 command_mode: false,
             };
         }
@@ -51,6 +53,7 @@ impl ParsedQuery {
                 exclude_terms: Vec::new(),
                 modified_within: None,
                 created_within: None,
+                content_query: None,
                 command_mode: false,
             };
         }
@@ -76,6 +79,7 @@ impl ParsedQuery {
         let mut expect_not = false;
         let mut modified_within = None;
         let mut created_within = None;
+        let mut content_terms: Vec<String> = Vec::new();
 
         for token in tokens {
             let token_trimmed = token.trim();
@@ -138,6 +142,14 @@ impl ParsedQuery {
                 expect_not = false;
                 continue;
             }
+            if let Some(value) = parse_prefixed(token_trimmed, "content:") {
+                let value = value.trim();
+                if !value.is_empty() {
+                    content_terms.push(value.to_string());
+                }
+                expect_not = false;
+                continue;
+            }
 
             let is_negative_literal = token_trimmed.starts_with('-') && token_trimmed.len() > 1;
             let target = if is_negative_literal {
@@ -167,6 +179,11 @@ impl ParsedQuery {
 
         include_groups.retain(|group| !group.is_empty());
         let free_text = free_terms.join(" ");
+        let content_query = if content_terms.is_empty() {
+            None
+        } else {
+            Some(content_terms.join(" "))
+        };
 
         Self {
             raw,
@@ -178,6 +195,7 @@ impl ParsedQuery {
             exclude_terms,
             modified_within,
             created_within,
+            content_query,
             command_mode,
         }
     }
@@ -271,6 +289,13 @@ mod tests {
         assert_eq!(parsed.free_text, "logs");
     }
 
+    #[test]
+    fn parses_content_operator() {
+        let parsed = ParsedQuery::parse(r#"content:"retry budget" kind:file notes"#, true);
+        assert_eq!(parsed.content_query.as_deref(), Some("retry budget"));
+        assert_eq!(parsed.free_text, "notes");
+    }
+
     #[test]
     fn disables_dsl_when_disabled() {
         let parsed = ParsedQuery::parse("kind:file notes", false);
@@ -1,6 +1,6 @@
 use crate::action_registry::{
-    search_actions_with_mode, ACTION_CLEAR_CLIPBOARD_ID, ACTION_DIAGNOSTICS_BUNDLE_ID,
-    ACTION_OPEN_CONFIG_ID, ACTION_OPEN_LOGS_ID, ACTION_REBUILD_INDEX_ID, ACTION_TRIM_MEMORY_ID,
+    ACTION_CLEAR_CLIPBOARD_ID, ACTION_DIAGNOSTICS_BUNDLE_ID, ACTION_OPEN_CONFIG_ID,
+    ACTION_OPEN_LOGS_ID, ACTION_REBUILD_INDEX_ID, ACTION_REPAIR_INDEX_ID, ACTION_TRIM_MEMORY_ID,
     ACTION_WEB_SEARCH_PREFIX,
 };
 use crate::clipboard_history;
@@ -9,23 +9,27 @@ use crate::core_service::{CoreService, LaunchTarget, ServiceError};
 use crate::hotkey_runtime::HotkeyRuntimeError;
 #[cfg(target_os = "windows")]
 use crate::hotkey_runtime::{default_hotkey_registrar, HotkeyRegistration};
+use crate::overlay_search::{
+    percentile_u128, resolved_mode_for_query, SHORT_QUERY_APP_BIAS_MAX_LEN,
+};
+#[cfg(target_os = "windows")]
+use crate::overlay_search::{search_overlay_results_with_session, OverlaySearchSession};
 #[cfg(target_os = "windows")]
 use crate::overlay_state::{HotkeyAction, OverlayState};
-use crate::plugin_sdk::{PluginActionKind, PluginRegistry};
+use crate::plugin_actions::{capture_plugin_command, run_plugin_action, PluginActionOutcome};
+use crate::plugin_sdk::{CommandOutput, PluginRegistry};
 use crate::query_dsl::ParsedQuery;
-use crate::search::SearchFilter;
+use crate::state_archive::PathRewrite;
 #[cfg(target_os = "windows")]
 use crate::windows_overlay::{
     is_instance_window_present, signal_existing_instance_quit, signal_existing_instance_show,
     NativeOverlayShell, OverlayEvent, OverlayRow, OverlayRowRole,
 };
-use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(target_os = "windows")]
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
 #[cfg(target_os = "windows")]
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 #[cfg(target_os = "windows")]
 const STATUS_ROW_NO_RESULTS: &str = "No results";
@@ -35,20 +39,14 @@ const STATUS_ROW_NO_COMMAND_RESULTS: &str = "No command matches";
 const STATUS_ROW_TYPE_TO_SEARCH: &str = "Start typing to search";
 #[cfg(target_os = "windows")]
 const STATUS_ROW_INDEXING: &str = "Indexing in background...";
-const QUERY_PROFILE_LOG_THRESHOLD_MS: u128 = 35;
-const SHORT_QUERY_APP_BIAS_MAX_LEN: usize = 2;
-const INDEXED_PREFIX_CACHE_MIN_QUERY_LEN: usize = 1;
-const INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT: usize = 120;
-const INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT: usize = 480;
 const QUERY_PROFILE_STATUS_SAMPLE_WINDOW: usize = 400;
-const FINAL_QUERY_CACHE_MAX_ENTRIES: usize = 32;
-const ADAPTIVE_INDEXED_LATENCY_WINDOW: usize = 24;
 #[cfg_attr(not(any(test, target_os = "windows")), allow(dead_code))]
 const UNINSTALL_QUERY_RESULT_LIMIT: usize = 160;
 #[cfg(target_os = "windows")]
 const CONFIG_RELOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
 const ACTION_UNINSTALL_CONFIRM_ID: &str = "action:uninstall:confirm";
 const ACTION_UNINSTALL_CANCEL_ID: &str = "action:uninstall:cancel";
+const PLUGIN_OUTPUT_ID_PREFIX: &str = "action:plugin-output:";
 static STDIO_LOGGING_ENABLED: AtomicBool = AtomicBool::new(true);
 #[cfg(target_os = "windows")]
 const CURRENT_RUNTIME_EXE_NAME: &str = "nex.exe";
@@ -84,30 +82,6 @@ fn line_contains_runtime_log_marker(line: &str, marker: &str) -> bool {
     runtime_log_prefixes().any(|prefix| line.contains(&runtime_log_marker(prefix, marker)))
 }
 
-#[derive(Debug, Clone, Default)]
-struct OverlaySearchSession {
-    indexed_prefix_cache: Option<IndexedPrefixCache>,
-    final_query_cache: HashMap<String, Vec<crate::model::SearchItem>>,
-    final_query_cache_lru: VecDeque<String>,
-    indexed_latency_ms: VecDeque<u128>,
-}
-
-impl OverlaySearchSession {
-    fn clear(&mut self) {
-        self.indexed_prefix_cache = None;
-        self.final_query_cache.clear();
-        self.final_query_cache_lru.clear();
-        self.indexed_latency_ms.clear();
-    }
-}
-
-#[derive(Debug, Clone)]
-struct IndexedPrefixCache {
-    normalized_query: String,
-    indexed_filter: SearchFilter,
-    seed_items: Vec<crate::model::SearchItem>,
-}
-
 #[cfg(target_os = "windows")]
 #[derive(Debug, Clone)]
 struct PendingUninstallConfirmation {
@@ -123,6 +97,7 @@ struct RuntimeConfigWatcher {
     path: std::path::PathBuf,
     last_checked: Instant,
     last_modified: Option<SystemTime>,
+    plugin_manifests: Vec<crate::plugin_sdk::ManifestStamp>,
 }
 
 #[cfg(target_os = "windows")]
@@ -145,6 +120,7 @@ pub enum RuntimeError {
     Overlay(String),
     Startup(crate::startup::StartupError),
     Io(std::io::Error),
+    Client(crate::client::ClientError),
 }
 
 impl std::fmt::Display for RuntimeError {
@@ -157,6 +133,7 @@ impl std::fmt::Display for RuntimeError {
             Self::Overlay(error) => write!(f, "overlay error: {error}"),
             Self::Startup(error) => write!(f, "startup error: {error}"),
             Self::Io(error) => write!(f, "io error: {error}"),
+            Self::Client(error) => write!(f, "client error: {error}"),
         }
     }
 }
@@ -175,6 +152,21 @@ impl From<ServiceError> for RuntimeError {
     }
 }
 
+impl From<crate::client::ClientError> for RuntimeError {
+    fn from(value: crate::client::ClientError) -> Self {
+        Self::Client(value)
+    }
+}
+
+impl From<crate::ipc::IpcError> for RuntimeError {
+    fn from(value: crate::ipc::IpcError) -> Self {
+        match value {
+            crate::ipc::IpcError::Io(error) => Self::Io(error),
+            crate::ipc::IpcError::Service(error) => Self::Service(error),
+        }
+    }
+}
+
 impl From<HotkeyRuntimeError> for RuntimeError {
     fn from(value: HotkeyRuntimeError) -> Self {
         Self::Hotkey(value)
@@ -193,7 +185,7 @@ impl From<std::io::Error> for RuntimeError {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RuntimeCommand {
     Run,
     Status,
@@ -204,9 +196,25 @@ pub enum RuntimeCommand {
     SyncStartup,
     SetLaunchAtStartup(bool),
     DiagnosticsBundle,
+    RepairIndex,
+    DowngradeIndex(i64),
+    Serve,
+    Search {
+        query: String,
+        mode: Option<crate::config::SearchMode>,
+        json: bool,
+        limit: Option<usize>,
+    },
+    Open(String),
+    ExportState(std::path::PathBuf),
+    ValidatePlugin(std::path::PathBuf),
+    ImportState {
+        archive: std::path::PathBuf,
+        rewrites: Vec<PathRewrite>,
+    },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RuntimeOptions {
     pub command: RuntimeCommand,
     pub background: bool,
@@ -222,8 +230,16 @@ impl Default for RuntimeOptions {
 }
 
 pub fn parse_cli_args(args: &[String]) -> Result<RuntimeOptions, String> {
+    match args.first().map(String::as_str) {
+        Some("search") => return parse_search_args(&args[1..]),
+        Some("open") => return parse_open_args(&args[1..]),
+        _ => {}
+    }
+
     let mut options = RuntimeOptions::default();
-    for arg in args {
+    let mut rewrites = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
         if let Some(value) = arg.strip_prefix("--set-launch-at-startup=") {
             let enabled = match value.trim().to_ascii_lowercase().as_str() {
                 "true" | "1" | "yes" | "on" => true,
@@ -238,6 +254,14 @@ pub fn parse_cli_args(args: &[String]) -> Result<RuntimeOptions, String> {
             continue;
         }
 
+        if let Some(value) = arg.strip_prefix("--downgrade-index=") {
+            let version = value.trim().parse::<i64>().map_err(|_| {
+                format!("invalid value for --downgrade-index: {value} (expected a schema version)")
+            })?;
+            options.command = RuntimeCommand::DowngradeIndex(version);
+            continue;
+        }
+
         match arg.as_str() {
             "--background" => options.background = true,
             "--foreground" => options.background = false,
@@ -248,15 +272,50 @@ pub fn parse_cli_args(args: &[String]) -> Result<RuntimeOptions, String> {
             "--ensure-config" => options.command = RuntimeCommand::EnsureConfig,
             "--sync-startup" => options.command = RuntimeCommand::SyncStartup,
             "--diagnostics-bundle" => options.command = RuntimeCommand::DiagnosticsBundle,
+            "--repair-index" => options.command = RuntimeCommand::RepairIndex,
+            "--serve" => options.command = RuntimeCommand::Serve,
+            "--export-state" => {
+                let path = next_path_arg(&mut args, arg)?;
+                options.command = RuntimeCommand::ExportState(path);
+            }
+            "--validate-plugin" => {
+                let path = next_path_arg(&mut args, arg)?;
+                options.command = RuntimeCommand::ValidatePlugin(path);
+            }
+            "--import-state" => {
+                let archive = next_path_arg(&mut args, arg)?;
+                options.command = RuntimeCommand::ImportState {
+                    archive,
+                    rewrites: Vec::new(),
+                };
+            }
+            "--rewrite-path" => {
+                let spec = args
+                    .next()
+                    .ok_or_else(|| "--rewrite-path requires FROM=TO".to_string())?;
+                let rewrite = PathRewrite::parse(spec)
+                    .ok_or_else(|| format!("invalid value for --rewrite-path: {spec} (expected FROM=TO)"))?;
+                rewrites.push(rewrite);
+            }
             "--help" | "-h" => {
                 return Err(
-                    "usage: nex [--background|--foreground] [--status|--status-json|--quit|--restart|--ensure-config|--sync-startup|--set-launch-at-startup=true|false|--diagnostics-bundle]".to_string(),
+                    "usage: nex search \"<query>\" [--mode <mode>] [--json] [--limit N] | nex open <id|query> | nex [--background|--foreground] [--status|--status-json|--quit|--restart|--ensure-config|--sync-startup|--set-launch-at-startup=true|false|--diagnostics-bundle|--repair-index|--downgrade-index=<version>|--serve|--export-state <file>|--import-state <file> [--rewrite-path FROM=TO]...|--validate-plugin <path>]".to_string(),
                 )
             }
             unknown => return Err(format!("unknown argument: {unknown}")),
         }
     }
 
+    if !rewrites.is_empty() {
+        match &mut options.command {
+            RuntimeCommand::ImportState {
+                rewrites: command_rewrites,
+                ..
+            } => *command_rewrites = rewrites,
+            _ => return Err("--rewrite-path is only valid with --import-state".to_string()),
+        }
+    }
+
     if options.command != RuntimeCommand::Run && options.background {
         return Err("background mode is only valid with normal run mode".to_string());
     }
@@ -264,12 +323,98 @@ pub fn parse_cli_args(args: &[String]) -> Result<RuntimeOptions, String> {
     Ok(options)
 }
 
+fn parse_search_args(args: &[String]) -> Result<RuntimeOptions, String> {
+    let mut terms = Vec::new();
+    let mut mode = None;
+    let mut json = false;
+    let mut limit = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            "--json" => json = true,
+            "--mode" => {
+                let value = inline_value
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| "--mode requires a value".to_string())?;
+                mode = Some(crate::config::SearchMode::parse(&value).ok_or_else(|| {
+                    format!(
+                        "invalid value for --mode: {value} (expected all|apps|files|actions|clipboard|bookmarks|snippets)"
+                    )
+                })?);
+            }
+            "--limit" => {
+                let value = inline_value
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| "--limit requires a value".to_string())?;
+                let parsed = value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or_else(|| {
+                        format!("invalid value for --limit: {value} (expected a positive number)")
+                    })?;
+                limit = Some(parsed);
+            }
+            unknown if unknown.starts_with("--") => {
+                return Err(format!("unknown search argument: {unknown}"));
+            }
+            term => terms.push(term.to_string()),
+        }
+    }
+
+    let query = terms.join(" ");
+    if query.trim().is_empty() {
+        return Err(
+            "usage: nex search \"<query>\" [--mode <mode>] [--json] [--limit N]".to_string(),
+        );
+    }
+    Ok(RuntimeOptions {
+        command: RuntimeCommand::Search {
+            query,
+            mode,
+            json,
+            limit,
+        },
+        background: false,
+    })
+}
+
+fn parse_open_args(args: &[String]) -> Result<RuntimeOptions, String> {
+    if let Some(flag) = args.iter().find(|arg| arg.starts_with("--")) {
+        return Err(format!("unknown open argument: {flag}"));
+    }
+    let target = args.join(" ");
+    if target.trim().is_empty() {
+        return Err("usage: nex open <id|query>".to_string());
+    }
+    Ok(RuntimeOptions {
+        command: RuntimeCommand::Open(target),
+        background: false,
+    })
+}
+
+fn next_path_arg(
+    args: &mut std::slice::Iter<'_, String>,
+    flag: &str,
+) -> Result<std::path::PathBuf, String> {
+    args.next()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty() && !value.starts_with("--"))
+        .map(std::path::PathBuf::from)
+        .ok_or_else(|| format!("{flag} requires a file path"))
+}
+
 pub fn run() -> Result<(), RuntimeError> {
     run_with_options(RuntimeOptions::default())
 }
 
 pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
-    configure_stdio_logging(options);
+    configure_stdio_logging(&options);
 
     if let Err(error) = crate::logging::init() {
         log_warn(&format!("[nex] logging init warning: {error}"));
@@ -291,6 +436,21 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
             return command_set_launch_at_startup(enabled);
         }
         RuntimeCommand::DiagnosticsBundle => return command_diagnostics_bundle(),
+        RuntimeCommand::RepairIndex => return command_repair_index(),
+        RuntimeCommand::DowngradeIndex(version) => return command_downgrade_index(version),
+        RuntimeCommand::Serve => return command_serve(),
+        RuntimeCommand::Search {
+            query,
+            mode,
+            json,
+            limit,
+        } => return command_search(&query, mode, json, limit),
+        RuntimeCommand::Open(target) => return command_open(&target),
+        RuntimeCommand::ExportState(path) => return command_export_state(&path),
+        RuntimeCommand::ValidatePlugin(path) => return command_validate_plugin(&path),
+        RuntimeCommand::ImportState { archive, rewrites } => {
+            return command_import_state(&archive, &rewrites);
+        }
         RuntimeCommand::Run => {}
     }
 
@@ -319,7 +479,7 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
             "[nex] startup cached_items={} (async indexing scheduled)",
             initial_cached_items
         ));
-        start_background_index_refresh(&runtime_config, initial_cached_items == 0)
+        start_background_index_refresh(&runtime_config, initial_cached_items == 0, service.events())
     };
     #[cfg(not(target_os = "windows"))]
     {
@@ -351,10 +511,10 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
             log_warn(&format!("[nex] plugin_warning {warning}"));
         }
         log_info(&format!(
-            "[nex] plugins loaded provider_items={} action_items={}",
-            plugin_registry.provider_items.len(),
+            "[nex] plugins loaded action_items={}",
             plugin_registry.action_items.len()
         ));
+        service.replace_plugins(plugin_registry.clone());
     }
 
     #[cfg(target_os = "windows")]
@@ -382,6 +542,8 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
             log_info("[nex] runtime already active; signaled existing instance");
             return Ok(());
         }
+        let ipc_server =
+            start_runtime_ipc_server(&runtime_config, &plugin_registry, service.events());
 
         let mut overlay_state = OverlayState::default();
         let overlay = NativeOverlayShell::create().map_err(RuntimeError::Overlay)?;
@@ -404,14 +566,22 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
             path: runtime_config.config_path.clone(),
             last_checked: Instant::now(),
             last_modified: config_file_modified_time(runtime_config.config_path.as_path()),
+            plugin_manifests: crate::plugin_sdk::manifest_stamps(&runtime_config, &plugin_registry),
         };
         let mut current_results: Vec<crate::model::SearchItem> = Vec::new();
         let mut suppressed_uninstall_titles: Vec<String> = Vec::new();
         let mut pending_uninstall_confirmation: Option<PendingUninstallConfirmation> = None;
         let mut pending_delayed_query: Option<String> = None;
+        let mut pending_selection: Option<PendingSelection> = None;
         let mut selected_index = 0_usize;
         let mut last_query = String::new();
-        let mut search_session = OverlaySearchSession::default();
+        let mut search_session = OverlaySearchSession {
+            degraded_providers: service.degraded_providers().unwrap_or_default(),
+            process_wake: Some(|| {
+                let _ = crate::windows_overlay::signal_background_task_done();
+            }),
+            ..OverlaySearchSession::default()
+        };
 
         overlay
             .run_message_loop_with_events(|event| {
@@ -425,11 +595,13 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
                     &mut max_results,
                     &mut config_watcher,
                     &mut background_index_refresh,
+                    ipc_server.as_ref(),
                 );
                 maybe_apply_background_index_refresh(
                     &service,
                     &mut background_index_refresh,
                     &runtime_config,
+                    &mut search_session,
                 );
                 match event {
                     OverlayEvent::Hotkey(_) => {
@@ -452,8 +624,7 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
                                 );
                                 overlay.show_and_focus();
                                 if runtime_config.clipboard_enabled {
-                                    let _ =
-                                        clipboard_history::maybe_capture_latest(&runtime_config);
+                                    let _ = service.capture_clipboard();
                                 }
                                 if overlay.query_text().trim().is_empty() {
                                     set_idle_overlay_state(&overlay);
@@ -475,6 +646,7 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
                                     &service,
                                     &mut background_index_refresh,
                                     &runtime_config,
+                                    &mut search_session,
                                 );
                             }
                         }
@@ -484,7 +656,7 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
                         reconcile_suppressed_uninstall_titles(&mut suppressed_uninstall_titles);
                         overlay.show_and_focus();
                         if runtime_config.clipboard_enabled {
-                            let _ = clipboard_history::maybe_capture_latest(&runtime_config);
+                            let _ = service.capture_clipboard();
                         }
                         if overlay.query_text().trim().is_empty() {
                             set_idle_overlay_state(&overlay);
@@ -631,8 +803,10 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
                                     &runtime_config,
                                     &plugin_registry,
                                     &pending.uninstall_action,
+                                    "",
+                                    &[],
                                 ) {
-                                    Ok(()) => {
+                                    Ok(_) => {
                                         track_uninstall_title_suppression(
                                             &mut suppressed_uninstall_titles,
                                             pending.uninstall_action.title.as_str(),
@@ -759,24 +933,86 @@ pub fn run_with_options(options: RuntimeOptions) -> Result<(), RuntimeError> {
                             selected_index,
                             last_query.as_str(),
                         ) {
-                            Ok(()) => {
-                                overlay.set_status_text("");
-                                overlay.hide_now();
-                                overlay_state.on_escape();
-                                reset_overlay_session(
+                            Ok(SelectionOutcome::Pending(pending)) => {
+                                overlay.set_status_text(pending.status);
+                                pending_selection = Some(pending);
+                            }
+                            outcome => {
+                                apply_selection_outcome(
+                                    outcome,
                                     &overlay,
+                                    &mut overlay_state,
                                     &mut current_results,
                                     &mut selected_index,
+                                    &mut pending_uninstall_confirmation,
+                                    &mut last_query,
+                                    &mut search_session,
                                 );
-                                pending_uninstall_confirmation = None;
-                                last_query.clear();
-                                search_session.clear();
-                            }
-                            Err(error) => {
-                                overlay.set_status_text(&format!("Launch error: {error}"));
                             }
                         }
                     }
+                    OverlayEvent::ShowActions => {
+                        if current_results.is_empty() || pending_uninstall_confirmation.is_some() {
+                            return;
+                        }
+                        if let Some(list_selection) = overlay.selected_index() {
+                            selected_index = list_selection.min(current_results.len() - 1);
+                        }
+                        let actions = secondary_action_results(&current_results[selected_index]);
+                        if actions.is_empty() {
+                            return;
+                        }
+                        current_results = actions;
+                        selected_index = 0;
+                        search_session.clear();
+                        let rows = overlay_rows(&current_results, true);
+                        overlay.set_results(&rows, selected_index);
+                        overlay.set_status_text("");
+                    }
+                    OverlayEvent::BackgroundTaskDone => {
+                        if search_session.poll_process_answers()
+                            && overlay.is_visible()
+                            && pending_uninstall_confirmation.is_none()
+                        {
+                            apply_query_change(
+                                std::mem::take(&mut last_query),
+                                &overlay,
+                                &service,
+                                &runtime_config,
+                                &plugin_registry,
+                                max_results,
+                                &background_index_refresh,
+                                &mut search_session,
+                                &mut pending_uninstall_confirmation,
+                                &suppressed_uninstall_titles,
+                                &mut current_results,
+                                &mut selected_index,
+                                &mut last_query,
+                            );
+                        }
+                        let finished = pending_selection
+                            .as_ref()
+                            .and_then(|pending| pending.receiver.try_recv().ok());
+                        let Some(outcome) = finished else {
+                            return;
+                        };
+                        let pending = pending_selection.take().expect("pending selection");
+                        if !overlay.is_visible() || overlay.query_text().trim() != pending.query.trim()
+                        {
+                            log_info("[nex] background selection finished after the overlay moved on");
+                            return;
+                        }
+                        apply_selection_outcome(
+                            outcome,
+                            &overlay,
+                            &mut overlay_state,
+                            &mut current_results,
+                            &mut selected_index,
+                            &mut pending_uninstall_confirmation,
+                            &mut last_query,
+                            &mut search_session,
+                        );
+                    }
                 }
             })
             .map_err(RuntimeError::Overlay)?;
@@ -963,6 +1199,7 @@ fn command_status_json() -> Result<(), RuntimeError> {
             "other_runtime_pids": state.other_runtime_pids,
             "diagnostics": diagnostics,
             "query_latency": report.map(query_profile_report_json),
+            "provider_health": load_provider_health_json(),
         });
         let encoded = serde_json::to_string_pretty(&payload)
             .map_err(|error| RuntimeError::Args(format!("status-json encode error: {error}")))?;
@@ -978,6 +1215,7 @@ fn command_status_json() -> Result<(), RuntimeError> {
             "other_runtime_pids": Vec::<u32>::new(),
             "diagnostics": serde_json::json!({}),
             "query_latency": serde_json::Value::Null,
+            "provider_health": load_provider_health_json(),
         });
         let encoded = serde_json::to_string_pretty(&payload)
             .map_err(|error| RuntimeError::Args(format!("status-json encode error: {error}")))?;
@@ -986,6 +1224,27 @@ fn command_status_json() -> Result<(), RuntimeError> {
     }
 }
 
+fn load_provider_health_json() -> serde_json::Value {
+    let health = config::load(None)
+        .map_err(|error| error.to_string())
+        .and_then(|cfg| {
+            crate::index_store::open_file_read_only(&cfg.index_db_path)
+                .map_err(|error| error.to_string())
+        })
+        .and_then(|db| {
+            crate::core_service::provider_health_snapshot(&db).map_err(|error| error.to_string())
+        });
+    match health {
+        Ok(entries) => serde_json::to_value(entries).unwrap_or(serde_json::Value::Null),
+        Err(error) => {
+            log_warn(&format!(
+                "[nex] status provider health unavailable: {error}"
+            ));
+            serde_json::Value::Null
+        }
+    }
+}
+
 fn command_diagnostics_bundle() -> Result<(), RuntimeError> {
     let cfg = config::load(None)?;
     let output_dir = write_diagnostics_bundle(&cfg)?;
@@ -996,6 +1255,218 @@ fn command_diagnostics_bundle() -> Result<(), RuntimeError> {
     Ok(())
 }
 
+fn command_repair_index() -> Result<(), RuntimeError> {
+    let cfg = config::load(None)?;
+    if is_runtime_instance_running() {
+        let problems =
+            crate::index_store::check_file(&cfg.index_db_path).map_err(ServiceError::from)?;
+        if problems.is_empty() {
+            log_info(&format!(
+                "[nex] repair-index integrity=ok vacuumed=false (runtime is running) path={}",
+                cfg.index_db_path.display()
+            ));
+            return Ok(());
+        }
+        crate::index_store::schedule_repair(&cfg.index_db_path).map_err(ServiceError::from)?;
+        log_warn(&format!(
+            "[nex] repair-index integrity=failed problems={} (runtime is running; repair scheduled for next start)",
+            problems.join("; ")
+        ));
+        return Ok(());
+    }
+
+    let report = crate::index_store::repair_file(&cfg.index_db_path).map_err(ServiceError::from)?;
+    if report.problems.is_empty() {
+        log_info(&format!(
+            "[nex] repair-index integrity=ok vacuumed={} path={}",
+            report.vacuumed,
+            cfg.index_db_path.display()
+        ));
+        return Ok(());
+    }
+
+    log_warn(&format!(
+        "[nex] repair-index integrity=failed problems={} quarantined={}",
+        report.problems.join("; "),
+        report.quarantined_path.as_deref().unwrap_or("none")
+    ));
+    let index_report = CoreService::new(cfg)?
+        .with_runtime_providers()
+        .rebuild_index_with_report()?;
+    log_info(&format!(
+        "[nex] repair-index rebuilt indexed={} discovered={}",
+        index_report.indexed_total, index_report.discovered_total
+    ));
+    Ok(())
+}
+
+// The tray instance has an overlay window; `--serve` only answers over IPC.
+#[cfg(target_os = "windows")]
+fn is_runtime_instance_running() -> bool {
+    is_instance_window_present() || crate::ipc::connect(&crate::ipc::default_endpoint()).is_ok()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_runtime_instance_running() -> bool {
+    crate::ipc::connect(&crate::ipc::default_endpoint()).is_ok()
+}
+
+fn command_search(
+    query: &str,
+    mode: Option<crate::config::SearchMode>,
+    json: bool,
+    limit: Option<usize>,
+) -> Result<(), RuntimeError> {
+    let cfg = config::load(None)?;
+    let mut client = crate::client::CoreClient::connect_or_open(cfg)?;
+    let results = client.search(query, mode, limit)?;
+    if json {
+        let encoded = serde_json::to_string_pretty(&crate::contract::SearchResponse { results })
+            .map_err(|error| RuntimeError::Args(format!("search encode error: {error}")))?;
+        println!("{encoded}");
+        return Ok(());
+    }
+    for result in &results {
+        println!(
+            "{}\t{}\t{}\t{}",
+            result.kind, result.title, result.path, result.id
+        );
+    }
+    Ok(())
+}
+
+fn command_open(target: &str) -> Result<(), RuntimeError> {
+    let cfg = config::load(None)?;
+    let mut client = crate::client::CoreClient::connect_or_open(cfg)?;
+    let launched = client.open(target)?;
+    crate::logging::info(&format!(
+        "[nex] open target={target} launched={launched} via={}",
+        if client.is_remote() { "ipc" } else { "index" }
+    ));
+    Ok(())
+}
+
+fn command_serve() -> Result<(), RuntimeError> {
+    let cfg = config::load(None)?;
+    let plugins = PluginRegistry::load_from_config(&cfg);
+    let endpoint = crate::ipc::default_endpoint();
+    let server =
+        crate::ipc::start_server(cfg, plugins, &endpoint, crate::events::EventBus::default())?;
+    log_info(&format!("[nex] serve endpoint={}", server.endpoint()));
+    server.wait();
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn start_runtime_ipc_server(
+    cfg: &config::Config,
+    plugins: &PluginRegistry,
+    events: &crate::events::EventBus,
+) -> Option<crate::ipc::IpcServer> {
+    if !cfg.ipc_enabled {
+        return None;
+    }
+    let endpoint = crate::ipc::default_endpoint();
+    match crate::ipc::start_server(cfg.clone(), plugins.clone(), &endpoint, events.clone()) {
+        Ok(server) => Some(server),
+        Err(error) => {
+            log_warn(&format!(
+                "[nex] ipc server unavailable endpoint={endpoint}: {error}"
+            ));
+            None
+        }
+    }
+}
+
+fn command_downgrade_index(version: i64) -> Result<(), RuntimeError> {
+    let cfg = config::load(None)?;
+    let backup = crate::index_store::downgrade_file(&cfg.index_db_path, version)
+        .map_err(ServiceError::from)?;
+    log_info(&format!(
+        "[nex] downgrade-index schema=v{version} path={} backup={}",
+        cfg.index_db_path.display(),
+        backup
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "none".to_string())
+    ));
+    Ok(())
+}
+
+fn command_export_state(path: &std::path::Path) -> Result<(), RuntimeError> {
+    let cfg = config::load(None)?;
+    let db = crate::index_store::open_from_config(&cfg).map_err(ServiceError::from)?;
+    let summary = crate::state_archive::export_state(&cfg, &db, path)
+        .map_err(|error| RuntimeError::Args(format!("export state failed: {error}")))?;
+    log_info(&format!(
+        "[nex] export-state path={} items={} query_selections={} clipboard_entries={} config={}",
+        path.display(),
+        summary.items,
+        summary.query_selections,
+        summary.clipboard_entries,
+        summary.config_included
+    ));
+    Ok(())
+}
+
+// Prints one line per manifest and each problem under it; fails when any manifest is
+// invalid so scripts can gate on the exit code.
+fn command_validate_plugin(path: &std::path::Path) -> Result<(), RuntimeError> {
+    let reports = crate::plugin_sdk::validate_plugin_path(path);
+    if reports.is_empty() {
+        return Err(RuntimeError::Args(format!(
+            "no plugin manifests found at {}",
+            path.display()
+        )));
+    }
+    let mut invalid = 0;
+    for report in &reports {
+        if report.is_valid() {
+            println!(
+                "ok\t{}\t{} {}",
+                report.path.display(),
+                report.plugin_id.as_deref().unwrap_or("-"),
+                report.version.as_deref().unwrap_or("")
+            );
+            continue;
+        }
+        invalid += 1;
+        println!("invalid\t{}", report.path.display());
+        for error in &report.errors {
+            println!("  {error}");
+        }
+    }
+    if invalid > 0 {
+        return Err(RuntimeError::Args(format!(
+            "{invalid} of {} plugin manifest(s) invalid",
+            reports.len()
+        )));
+    }
+    Ok(())
+}
+
+fn command_import_state(
+    archive: &std::path::Path,
+    rewrites: &[PathRewrite],
+) -> Result<(), RuntimeError> {
+    let cfg = config::load(None)?;
+    let db = crate::index_store::open_from_config(&cfg).map_err(ServiceError::from)?;
+    let summary = crate::state_archive::import_state(&cfg, &db, archive, rewrites)
+        .map_err(|error| RuntimeError::Args(format!("import state failed: {error}")))?;
+    for warning in &summary.warnings {
+        log_warn(&format!("[nex] import-state warning: {warning}"));
+    }
+    log_info(&format!(
+        "[nex] import-state path={} items={} query_selections={} clipboard_entries={} config={} rewritten_paths={} (restart the runtime to load imported state)",
+        archive.display(),
+        summary.items,
+        summary.query_selections,
+        summary.clipboard_entries,
+        summary.config_imported,
+        summary.rewritten_paths
+    ));
+    Ok(())
+}
+
 #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 struct StatusDiagnosticsSnapshot {
@@ -1396,17 +1867,6 @@ fn parse_quoted_field(line: &str, key: &str) -> Option<String> {
     Some(tail[1..(1 + end)].to_string())
 }
 
-#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
-fn percentile_u128(values: &mut [u128], percentile: f64) -> u128 {
-    if values.is_empty() {
-        return 0;
-    }
-    values.sort_unstable();
-    let last = values.len().saturating_sub(1);
-    let idx = ((last as f64) * percentile.clamp(0.0, 1.0)).round() as usize;
-    values[idx.min(last)]
-}
-
 fn command_quit() -> Result<(), RuntimeError> {
     #[cfg(target_os = "windows")]
     {
@@ -1629,6 +2089,7 @@ fn write_diagnostics_bundle(cfg: &config::Config) -> Result<std::path::PathBuf,
         "plugins_enabled": cfg.plugins_enabled,
         "plugin_paths_count": cfg.plugin_paths.len(),
         "plugins_safe_mode": cfg.plugins_safe_mode,
+        "plugin_grants_count": cfg.plugin_grants.len(),
         "game_mode_enabled": cfg.game_mode_enabled,
         "idle_cache_trim_ms": cfg.idle_cache_trim_ms,
         "active_memory_target_mb": cfg.active_memory_target_mb,
@@ -1640,17 +2101,43 @@ fn write_diagnostics_bundle(cfg: &config::Config) -> Result<std::path::PathBuf,
         "windows_search_enabled": cfg.windows_search_enabled,
         "windows_search_fallback_filesystem": cfg.windows_search_fallback_filesystem,
         "show_files": cfg.show_files,
-        "show_folders": cfg.show_folders
+        "show_folders": cfg.show_folders,
+        "browser_bookmarks_enabled": cfg.browser_bookmarks_enabled,
+        "browser_profile_paths_count": cfg.browser_profile_paths.len(),
+        "recent_documents_enabled": cfg.recent_documents_enabled,
+        "path_executables_enabled": cfg.path_executables_enabled,
+        "path_executables_extra_dirs_count": cfg.path_executables_extra_dirs.len(),
+        "content_indexing_enabled": cfg.content_indexing_enabled,
+        "content_index_max_file_bytes": cfg.content_index_max_file_bytes,
+        "ipc_enabled": cfg.ipc_enabled
     });
     let encoded = serde_json::to_string_pretty(&sanitized_cfg)
         .map_err(|e| RuntimeError::Args(format!("failed to encode sanitized config: {e}")))?;
     std::fs::write(bundle_dir.join("config.sanitized.json"), encoded)?;
 
+    if let Some(maintenance) = load_index_maintenance_json(cfg) {
+        std::fs::write(bundle_dir.join("index_maintenance.json"), maintenance)?;
+    }
+
     copy_recent_logs_to_bundle(&crate::logging::logs_dir(), &bundle_dir.join("logs"))?;
 
     Ok(bundle_dir)
 }
 
+fn load_index_maintenance_json(cfg: &config::Config) -> Option<String> {
+    if !cfg.index_db_path.exists() {
+        return None;
+    }
+    let report = crate::index_store::open_file_read_only(&cfg.index_db_path)
+        .and_then(|db| crate::index_store::last_maintenance(&db));
+    match report {
+        Ok(report) => serde_json::to_string_pretty(&report?).ok(),
+        Err(error) => {
+            Some(serde_json::json!({ "error": format!("index unreadable: {error}") }).to_string())
+        }
+    }
+}
+
 fn copy_recent_logs_to_bundle(
     source_logs_dir: &std::path::Path,
     target_logs_dir: &std::path::Path,
@@ -1823,6 +2310,7 @@ fn result_row(
         title: item.title.clone(),
         path: overlay_subtitle(item, command_mode),
         icon_path: item.path.clone(),
+        icon: item.icon.clone(),
     }
 }
 
@@ -2107,6 +2595,9 @@ fn overlay_subtitle(item: &crate::model::SearchItem, command_mode: bool) -> Stri
     if item.kind.eq_ignore_ascii_case("app") {
         return item.subtitle.trim().to_string();
     }
+    if !item.snippet.is_empty() {
+        return item.snippet.clone();
+    }
     if item.kind.eq_ignore_ascii_case("action") {
         if item.path.trim().is_empty() {
             return "Nex action".to_string();
@@ -2155,21 +2646,39 @@ fn set_idle_overlay_state(overlay: &NativeOverlayShell) {
     overlay.clear_placeholder_hint();
     overlay.set_results(&[], 0);
     overlay.set_status_text("");
+    overlay.set_mode_strip_text("");
 }
 
 #[cfg(target_os = "windows")]
 fn set_status_row_overlay_state(overlay: &NativeOverlayShell, message: &str) {
     overlay.clear_placeholder_hint();
-    let rows = [OverlayRow {
+    let rows = [status_row(message)];
+    overlay.set_results(&rows, 0);
+    overlay.set_status_text("");
+}
+
+#[cfg(target_os = "windows")]
+fn status_row(message: &str) -> OverlayRow {
+    OverlayRow {
         role: OverlayRowRole::Status,
         result_index: -1,
         kind: "status".to_string(),
         title: message.to_string(),
         path: String::new(),
         icon_path: String::new(),
-    }];
-    overlay.set_results(&rows, 0);
-    overlay.set_status_text("");
+        icon: String::new(),
+    }
+}
+
+#[cfg_attr(not(any(test, target_os = "windows")), allow(dead_code))]
+fn provider_degraded_status_text(degraded_providers: &[String]) -> Option<String> {
+    if degraded_providers.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Provider degraded: {} (results may be incomplete)",
+        degraded_providers.join(", ")
+    ))
 }
 
 #[cfg(target_os = "windows")]
@@ -2537,15 +3046,21 @@ fn is_likely_game_path(process_path: &str) -> bool {
 fn start_background_index_refresh(
     config: &Config,
     initial_cache_empty: bool,
+    events: &crate::events::EventBus,
 ) -> BackgroundIndexRefresh {
     let completed = Arc::new(AtomicBool::new(false));
     let result = Arc::new(Mutex::new(None));
     let completed_worker = completed.clone();
     let result_worker = result.clone();
     let worker_config = config.clone();
+    let worker_events = events.clone();
     std::thread::spawn(move || {
         let outcome = CoreService::new(worker_config)
-            .map(|service| service.with_runtime_providers())
+            .map(|service| {
+                service
+                    .with_runtime_providers()
+                    .with_event_bus(worker_events)
+            })
             .and_then(|service| service.rebuild_index_incremental_with_report())
             .map_err(|error| format!("background indexing failed: {error}"));
         let mut slot = match result_worker.lock() {
@@ -2571,6 +3086,7 @@ fn maybe_apply_background_index_refresh(
     service: &CoreService,
     state: &mut BackgroundIndexRefresh,
     runtime_config: &Config,
+    search_session: &mut OverlaySearchSession,
 ) {
     if state.cache_applied {
         return;
@@ -2603,12 +3119,13 @@ fn maybe_apply_background_index_refresh(
                     ));
                     for provider in &report.providers {
                         log_info(&format!(
-                            "[nex] index_provider name={} discovered={} upserted={} removed={} skipped={} elapsed_ms={}",
+                            "[nex] index_provider name={} discovered={} upserted={} removed={} skipped={} failed={} elapsed_ms={}",
                             provider.provider,
                             provider.discovered,
                             provider.upserted,
                             provider.removed,
                             provider.skipped,
+                            provider.error.is_some(),
                             provider.elapsed_ms
                         ));
                     }
@@ -2628,13 +3145,17 @@ fn maybe_apply_background_index_refresh(
         }
     }
 
+    match service.degraded_providers() {
+        Ok(degraded) => search_session.degraded_providers = degraded,
+        Err(error) => log_warn(&format!("[nex] provider health read failed: {error}")),
+    }
     state.cache_applied = true;
 
     if state.pending_discovery_reindex {
         log_info(
             "[nex] discovery settings queued during indexing; starting pending reindex",
         );
-        *state = start_background_index_refresh(runtime_config, false);
+        *state = start_background_index_refresh(runtime_config, false, service.events());
     }
 }
 
@@ -2643,275 +3164,26 @@ fn should_show_indexing_status(state: &BackgroundIndexRefresh) -> bool {
     state.initial_cache_empty && !state.cache_applied
 }
 
+// Empty text restores the overlay's default mode strip.
 #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
-#[cfg_attr(not(test), allow(dead_code))]
-fn search_overlay_results(
-    service: &CoreService,
-    cfg: &Config,
-    plugins: &PluginRegistry,
-    parsed_query: &ParsedQuery,
-    result_limit: usize,
-) -> Result<Vec<crate::model::SearchItem>, String> {
-    let mut session = OverlaySearchSession::default();
-    search_overlay_results_with_session(
-        service,
-        cfg,
-        plugins,
-        parsed_query,
-        result_limit,
-        &mut session,
-    )
+fn plugin_scope_strip_text(plugins: &PluginRegistry, query: &str) -> String {
+    plugins
+        .route(query)
+        .map(|route| format!("{}  \u{203a}  {}", route.trigger.label, route.keyword))
+        .unwrap_or_default()
 }
 
-#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
-fn search_overlay_results_with_session(
-    service: &CoreService,
-    cfg: &Config,
-    plugins: &PluginRegistry,
-    parsed_query: &ParsedQuery,
-    result_limit: usize,
-    session: &mut OverlaySearchSession,
-) -> Result<Vec<crate::model::SearchItem>, String> {
-    if result_limit == 0 {
-        return Ok(Vec::new());
-    }
-
-    let filter = build_search_filter(cfg, parsed_query);
-    let text_query = parsed_query.free_text.trim();
-    let normalized_query = crate::model::normalize_for_search(text_query);
-    if should_skip_non_searchable_query(parsed_query, &normalized_query) {
-        log_info(&format!(
-            "[nex] query_guard skip=non_searchable_symbol_only q=\"{}\"",
-            sanitize_query_for_profile_log(parsed_query.raw.as_str())
-        ));
-        session.clear();
-        return Ok(Vec::new());
-    }
-    let cache_key = final_query_cache_key(parsed_query, &filter, &normalized_query, result_limit);
-    if let Some(cached) = cached_final_query_results(session, &cache_key) {
-        return Ok(cached);
-    }
-    let candidate_limit = candidate_limit_for_query(
-        result_limit,
-        &filter,
-        &normalized_query,
-        parsed_query.command_mode,
-    );
-    let base_indexed_seed_limit = indexed_seed_limit(candidate_limit, normalized_query.len());
-    let seed_cap = (cfg.index_max_items_per_query_seed as usize).max(candidate_limit);
-    let indexed_seed_limit = adaptive_indexed_seed_limit(
-        session,
-        candidate_limit,
-        normalized_query.len(),
-        base_indexed_seed_limit,
-    )
-    .min(seed_cap);
-    let short_query_app_bias =
-        should_use_short_query_app_mode(parsed_query, &filter, &normalized_query);
-    let mut indexed_filter = filter.clone();
-    if short_query_app_bias {
-        indexed_filter.mode = crate::config::SearchMode::Apps;
-    }
-
-    let search_started = Instant::now();
-    let mut merged = Vec::new();
-    let indexed_started = Instant::now();
-    let mut indexed_cache_hit = false;
-    let prefix_cache_eligible = is_prefix_cache_eligible_query(parsed_query, short_query_app_bias);
-    let indexed_seed_items = if let Some(cache) =
-        session.indexed_prefix_cache.as_ref().filter(|cache| {
-            can_use_indexed_prefix_cache(
-                cache,
-                prefix_cache_eligible,
-                &normalized_query,
-                &indexed_filter,
-            )
-        }) {
-        indexed_cache_hit = true;
-        crate::search::search_with_filter(
-            &cache.seed_items,
-            text_query,
-            indexed_seed_limit,
-            &indexed_filter,
-        )
-    } else {
-        service
-            .search_with_filter_uncapped(text_query, indexed_seed_limit, &indexed_filter)
-            .map_err(|error| format!("indexed search failed: {error}"))?
-    };
-    let indexed_ms = indexed_started.elapsed().as_millis();
-    if !indexed_cache_hit {
-        record_indexed_latency_sample(session, indexed_ms);
-    }
-    let indexed_count = indexed_seed_items.len();
-    merged.extend(indexed_seed_items.iter().take(candidate_limit).cloned());
-    if prefix_cache_eligible && normalized_query.len() >= INDEXED_PREFIX_CACHE_MIN_QUERY_LEN {
-        session.indexed_prefix_cache = Some(IndexedPrefixCache {
-            normalized_query: normalized_query.clone(),
-            indexed_filter: indexed_filter.clone(),
-            seed_items: indexed_seed_items,
-        });
-    } else {
-        session.clear();
-    }
-
-    let mut provider_ms = 0_u128;
-    let mut provider_count = 0_usize;
-    if !short_query_app_bias {
-        let provider_started = Instant::now();
-        let provider_results = crate::search::search_with_filter(
-            &plugins.provider_items,
-            text_query,
-            candidate_limit,
-            &filter,
-        );
-        provider_ms = provider_started.elapsed().as_millis();
-        provider_count = provider_results.len();
-        merged.extend(provider_results);
-    }
-
-    let actions_started = Instant::now();
-    let mut action_items =
-        search_actions_with_mode(text_query, candidate_limit, parsed_query.command_mode, cfg);
-    let built_in_actions_count = action_items.len();
-    let mut plugin_action_count = 0_usize;
-    if !plugins.action_items.is_empty() {
-        let plugin_actions = crate::search::search_with_filter(
-            &plugins.action_items,
-            text_query,
-            candidate_limit,
-            &SearchFilter {
-                mode: crate::config::SearchMode::Actions,
-                ..SearchFilter::default()
-            },
-        );
-        plugin_action_count = plugin_actions.len();
-        action_items.extend(plugin_actions);
-    }
-    let action_results =
-        crate::search::search_with_filter(&action_items, text_query, candidate_limit, &filter);
-    let actions_ms = actions_started.elapsed().as_millis();
-    let action_count = action_results.len();
-    merged.extend(action_results);
-
-    let mut clipboard_ms = 0_u128;
-    let mut clipboard_count = 0_usize;
-    if !short_query_app_bias {
-        let clipboard_started = Instant::now();
-        let clipboard_results =
-            clipboard_history::search_history(cfg, text_query, &filter, candidate_limit.min(120));
-        clipboard_ms = clipboard_started.elapsed().as_millis();
-        clipboard_count = clipboard_results.len();
-        merged.extend(clipboard_results);
-    }
-
-    let rank_started = Instant::now();
-    let ranked = crate::search::search_with_filter(&merged, text_query, result_limit, &filter);
-    let rank_ms = rank_started.elapsed().as_millis();
-    let total_ms = search_started.elapsed().as_millis();
-    if total_ms >= QUERY_PROFILE_LOG_THRESHOLD_MS {
-        log_info(&format!(
-            "[nex] query_profile q=\"{}\" mode={} candidate_limit={} indexed_seed_limit={} short_app_bias={} indexed_cache_hit={} indexed_count={} indexed_ms={} provider_count={} provider_ms={} action_count={} action_ms={} built_in_actions={} plugin_actions={} clipboard_count={} clipboard_ms={} rank_ms={} total_ms={}",
-            sanitize_query_for_profile_log(text_query),
-            format!("{:?}", filter.mode).to_ascii_lowercase(),
-            candidate_limit,
-            indexed_seed_limit,
-            short_query_app_bias,
-            indexed_cache_hit,
-            indexed_count,
-            indexed_ms,
-            provider_count,
-            provider_ms,
-            action_count,
-            actions_ms,
-            built_in_actions_count,
-            plugin_action_count,
-            clipboard_count,
-            clipboard_ms,
-            rank_ms,
-            total_ms
-        ));
+#[cfg_attr(not(any(test, target_os = "windows")), allow(dead_code))]
+fn result_limit_for_query(base_limit: usize, parsed_query: &ParsedQuery) -> usize {
+    if base_limit == 0 {
+        return 0;
+    }
+    if parsed_query.command_mode
+        && crate::uninstall_registry::has_uninstall_intent(parsed_query.free_text.as_str())
+    {
+        return base_limit.max(UNINSTALL_QUERY_RESULT_LIMIT);
     }
-    store_final_query_results(session, cache_key, ranked.as_slice());
-    Ok(ranked)
-}
-
-fn build_search_filter(cfg: &Config, parsed_query: &ParsedQuery) -> SearchFilter {
-    let mode = resolved_mode_for_query(cfg, parsed_query);
-    SearchFilter {
-        mode,
-        kind_filter: parsed_query.kind_filter.clone(),
-        extension_filter: parsed_query.extension_filter.clone(),
-        include_files: cfg.show_files,
-        include_folders: cfg.show_folders,
-        include_groups: parsed_query.include_groups.clone(),
-        exclude_terms: parsed_query.exclude_terms.clone(),
-        modified_within: parsed_query.modified_within,
-        created_within: parsed_query.created_within,
-    }
-}
-
-fn resolved_mode_for_query(cfg: &Config, parsed_query: &ParsedQuery) -> crate::config::SearchMode {
-    let mut mode = parsed_query
-        .mode_override
-        .unwrap_or(cfg.search_mode_default);
-    if parsed_query.command_mode {
-        mode = crate::config::SearchMode::Actions;
-    }
-    mode
-}
-
-#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
-fn should_use_short_query_app_mode(
-    parsed_query: &ParsedQuery,
-    filter: &SearchFilter,
-    normalized_query: &str,
-) -> bool {
-    if normalized_query.is_empty() || normalized_query.len() > SHORT_QUERY_APP_BIAS_MAX_LEN {
-        return false;
-    }
-    if parsed_query.command_mode {
-        return false;
-    }
-    if filter.mode != crate::config::SearchMode::All {
-        return false;
-    }
-    parsed_query.kind_filter.is_none()
-        && parsed_query.extension_filter.is_none()
-        && parsed_query.exclude_terms.is_empty()
-        && parsed_query.modified_within.is_none()
-        && parsed_query.created_within.is_none()
-}
-
-fn should_skip_non_searchable_query(parsed_query: &ParsedQuery, normalized_query: &str) -> bool {
-    if !normalized_query.is_empty() {
-        return false;
-    }
-    if parsed_query.command_mode {
-        return false;
-    }
-    if parsed_query.mode_override.is_some() {
-        return false;
-    }
-    parsed_query.kind_filter.is_none()
-        && parsed_query.extension_filter.is_none()
-        && parsed_query.include_groups.is_empty()
-        && parsed_query.exclude_terms.is_empty()
-        && parsed_query.modified_within.is_none()
-        && parsed_query.created_within.is_none()
-}
-
-#[cfg_attr(not(any(test, target_os = "windows")), allow(dead_code))]
-fn result_limit_for_query(base_limit: usize, parsed_query: &ParsedQuery) -> usize {
-    if base_limit == 0 {
-        return 0;
-    }
-    if parsed_query.command_mode
-        && crate::uninstall_registry::has_uninstall_intent(parsed_query.free_text.as_str())
-    {
-        return base_limit.max(UNINSTALL_QUERY_RESULT_LIMIT);
-    }
-    base_limit
+    base_limit
 }
 
 #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
@@ -2963,6 +3235,7 @@ fn apply_query_change(
         return;
     }
     *last_query = trimmed.to_string();
+    overlay.set_mode_strip_text(&plugin_scope_strip_text(plugin_registry, &query));
     let parsed_query = ParsedQuery::parse(trimmed, runtime_config.search_dsl_enabled);
     let query_result_limit = result_limit_for_query(max_results, &parsed_query);
 
@@ -2981,9 +3254,13 @@ fn apply_query_change(
             }
             *current_results = results;
             *selected_index = 0;
+            let degraded_status = provider_degraded_status_text(&search_session.degraded_providers);
             if current_results.is_empty() {
                 if should_show_indexing_status(background_index_refresh) {
                     set_status_row_overlay_state(overlay, STATUS_ROW_INDEXING);
+                } else if let Some(message) = degraded_status.filter(|_| !parsed_query.command_mode)
+                {
+                    set_status_row_overlay_state(overlay, &message);
                 } else {
                     set_status_row_overlay_state(
                         overlay,
@@ -2995,7 +3272,10 @@ fn apply_query_change(
                     );
                 }
             } else {
-                let rows = overlay_rows(current_results, parsed_query.command_mode);
+                let mut rows = overlay_rows(current_results, parsed_query.command_mode);
+                if let Some(message) = degraded_status.filter(|_| !parsed_query.command_mode) {
+                    rows.push(status_row(&message));
+                }
                 overlay.set_results(&rows, *selected_index);
             }
         }
@@ -3025,6 +3305,7 @@ fn maybe_apply_runtime_config_reload(
     max_results: &mut usize,
     watcher: &mut RuntimeConfigWatcher,
     background_index_refresh: &mut BackgroundIndexRefresh,
+    ipc_server: Option<&crate::ipc::IpcServer>,
 ) {
     if watcher.last_checked.elapsed() < CONFIG_RELOAD_POLL_INTERVAL {
         return;
@@ -3033,6 +3314,16 @@ fn maybe_apply_runtime_config_reload(
 
     let modified = config_file_modified_time(watcher.path.as_path());
     if modified == watcher.last_modified {
+        maybe_reload_plugin_manifests(
+            overlay,
+            service,
+            runtime_config,
+            plugin_registry,
+            search_session,
+            watcher,
+            background_index_refresh,
+            ipc_server,
+        );
         return;
     }
     watcher.last_modified = modified;
@@ -3049,6 +3340,16 @@ fn maybe_apply_runtime_config_reload(
                     != previous.windows_search_fallback_filesystem
                 || next_config.show_files != previous.show_files
                 || next_config.show_folders != previous.show_folders
+                || next_config.browser_bookmarks_enabled != previous.browser_bookmarks_enabled
+                || next_config.browser_profile_paths != previous.browser_profile_paths
+                || next_config.recent_documents_enabled != previous.recent_documents_enabled
+                || next_config.path_executables_enabled != previous.path_executables_enabled
+                || next_config.path_executables_extra_dirs != previous.path_executables_extra_dirs
+                || next_config.plugins_enabled != previous.plugins_enabled
+                || next_config.plugin_paths != previous.plugin_paths
+                || next_config.content_indexing_enabled != previous.content_indexing_enabled
+                || next_config.content_index_max_file_bytes
+                    != previous.content_index_max_file_bytes
                 || next_config.index_max_items_total != previous.index_max_items_total
                 || next_config.index_max_items_per_root != previous.index_max_items_per_root
                 || next_config.index_max_items_per_query_seed
@@ -3062,10 +3363,11 @@ fn maybe_apply_runtime_config_reload(
                 runtime_config.active_memory_target_mb,
             );
             overlay.set_game_mode_enabled(runtime_config.game_mode_enabled);
-            *plugin_registry = PluginRegistry::load_from_config(runtime_config);
-            for warning in &plugin_registry.load_warnings {
-                log_warn(&format!("[nex] plugin_warning {warning}"));
-            }
+            let plugin_diff = crate::plugin_sdk::reload_registry(plugin_registry, runtime_config);
+            log_plugin_reload(&plugin_diff, plugin_registry);
+            service.replace_plugins(plugin_registry.clone());
+            watcher.plugin_manifests =
+                crate::plugin_sdk::manifest_stamps(runtime_config, plugin_registry);
             search_session.clear();
             *pending_uninstall_confirmation = None;
 
@@ -3088,7 +3390,7 @@ fn maybe_apply_runtime_config_reload(
                 } else {
                     if background_index_refresh.cache_applied {
                         *background_index_refresh =
-                            start_background_index_refresh(runtime_config, false);
+                            start_background_index_refresh(runtime_config, false, service.events());
                         log_info("[nex] discovery settings changed; background reindex started");
                     } else {
                         background_index_refresh.pending_discovery_reindex = true;
@@ -3096,6 +3398,13 @@ fn maybe_apply_runtime_config_reload(
                         log_info("[nex] discovery settings changed while indexing is active; reindex queued");
                     }
                 }
+            } else if let Err(error) = service.apply_config(runtime_config) {
+                log_warn(&format!("[nex] config apply failed after reload: {error}"));
+            }
+            // IPC launches go through the same plugins, grants and safe mode.
+            if let Some(server) = ipc_server {
+                server.replace_plugins(plugin_registry);
+                server.apply_config(runtime_config, discovery_config_changed);
             }
 
             log_info(&format!(
@@ -3137,6 +3446,89 @@ fn maybe_apply_runtime_config_reload(
     }
 }
 
+// Manifests are polled with the config file. A reload swaps in a freshly built registry;
+// added or removed plugins also change the discovery providers serving `provider_items`.
+#[cfg(target_os = "windows")]
+fn maybe_reload_plugin_manifests(
+    overlay: &NativeOverlayShell,
+    service: &CoreService,
+    runtime_config: &Config,
+    plugin_registry: &mut PluginRegistry,
+    search_session: &mut OverlaySearchSession,
+    watcher: &mut RuntimeConfigWatcher,
+    background_index_refresh: &mut BackgroundIndexRefresh,
+    ipc_server: Option<&crate::ipc::IpcServer>,
+) {
+    let stamps = crate::plugin_sdk::manifest_stamps(runtime_config, plugin_registry);
+    if stamps == watcher.plugin_manifests {
+        return;
+    }
+
+    let diff = crate::plugin_sdk::reload_registry(plugin_registry, runtime_config);
+    // Restamp against the reloaded registry, which may name different snippet folders.
+    watcher.plugin_manifests = crate::plugin_sdk::manifest_stamps(runtime_config, plugin_registry);
+    search_session.clear();
+    log_plugin_reload(&diff, plugin_registry);
+    service.replace_plugins(plugin_registry.clone());
+    if let Some(server) = ipc_server {
+        server.replace_plugins(plugin_registry);
+        if diff.plugins_changed() {
+            server.apply_config(runtime_config, true);
+        }
+    }
+    if diff.plugins_changed() {
+        if let Err(error) = service.reconfigure_runtime_providers(runtime_config) {
+            log_warn(&format!(
+                "[nex] provider reconfigure failed after plugin reload: {error}"
+            ));
+        } else if background_index_refresh.cache_applied {
+            *background_index_refresh =
+                start_background_index_refresh(runtime_config, false, service.events());
+        } else {
+            background_index_refresh.pending_discovery_reindex = true;
+        }
+    }
+    if !diff.is_empty() {
+        overlay.set_status_text("Plugins reloaded");
+    }
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn log_plugin_reload(diff: &crate::plugin_sdk::PluginRegistryDiff, registry: &PluginRegistry) {
+    for warning in &diff.new_warnings {
+        log_warn(&format!("[nex] plugin_warning {warning}"));
+    }
+    if let Some(line) = plugin_reload_log_line(diff, registry) {
+        log_info(&line);
+    }
+}
+
+fn plugin_reload_log_line(
+    diff: &crate::plugin_sdk::PluginRegistryDiff,
+    registry: &PluginRegistry,
+) -> Option<String> {
+    if diff.is_empty() {
+        return None;
+    }
+    let list = |ids: &[String]| {
+        if ids.is_empty() {
+            "-".to_string()
+        } else {
+            ids.join(",")
+        }
+    };
+    Some(format!(
+        "[nex] plugins reloaded added={} removed={} actions_added={} actions_removed={} actions_changed={} new_warnings={} action_items={}",
+        list(&diff.added_plugins),
+        list(&diff.removed_plugins),
+        list(&diff.added_actions),
+        list(&diff.removed_actions),
+        list(&diff.changed_actions),
+        diff.new_warnings.len(),
+        registry.action_items.len()
+    ))
+}
+
 #[cfg(target_os = "windows")]
 fn should_suppress_failed_uninstall(error: &str) -> bool {
     let lower = error.to_ascii_lowercase();
@@ -3174,257 +3566,6 @@ fn uninstall_confirmation_results(
     ]
 }
 
-fn candidate_limit_for_query(
-    result_limit: usize,
-    filter: &SearchFilter,
-    normalized_query: &str,
-    command_mode: bool,
-) -> usize {
-    if result_limit == 0 {
-        return 0;
-    }
-
-    let base = result_limit.saturating_mul(6).max(60);
-    if command_mode || filter.mode == crate::config::SearchMode::Actions {
-        return result_limit
-            .saturating_mul(4)
-            .max(48)
-            .min(160)
-            .max(result_limit);
-    }
-
-    match normalized_query.len() {
-        0 => result_limit
-            .saturating_mul(2)
-            .max(24)
-            .min(64)
-            .max(result_limit),
-        1 => match filter.mode {
-            crate::config::SearchMode::All => result_limit
-                .saturating_mul(3)
-                .max(45)
-                .min(96)
-                .max(result_limit),
-            crate::config::SearchMode::Files => result_limit
-                .saturating_mul(5)
-                .max(70)
-                .min(200)
-                .max(result_limit),
-            _ => result_limit
-                .saturating_mul(4)
-                .max(56)
-                .min(180)
-                .max(result_limit),
-        },
-        2 => match filter.mode {
-            crate::config::SearchMode::All => result_limit
-                .saturating_mul(4)
-                .max(56)
-                .min(140)
-                .max(result_limit),
-            crate::config::SearchMode::Files => result_limit
-                .saturating_mul(5)
-                .max(70)
-                .min(200)
-                .max(result_limit),
-            _ => result_limit
-                .saturating_mul(4)
-                .max(56)
-                .min(180)
-                .max(result_limit),
-        },
-        _ => base.min(280).max(result_limit),
-    }
-}
-
-fn indexed_seed_limit(candidate_limit: usize, normalized_query_len: usize) -> usize {
-    let multiplier = match normalized_query_len {
-        0 | 1 => 4,
-        2 => 2,
-        _ => 2,
-    };
-    candidate_limit.saturating_mul(multiplier).clamp(
-        INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT,
-        INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT,
-    )
-}
-
-fn adaptive_indexed_seed_limit(
-    session: &OverlaySearchSession,
-    candidate_limit: usize,
-    normalized_query_len: usize,
-    base_seed_limit: usize,
-) -> usize {
-    let mut samples: Vec<u128> = session.indexed_latency_ms.iter().copied().collect();
-    if samples.len() < 6 {
-        return base_seed_limit;
-    }
-
-    let p95 = percentile_u128(&mut samples, 0.95);
-    let scaled = if p95 >= 160 {
-        (base_seed_limit.saturating_mul(60)) / 100
-    } else if p95 >= 120 {
-        (base_seed_limit.saturating_mul(72)) / 100
-    } else if p95 >= 95 {
-        (base_seed_limit.saturating_mul(84)) / 100
-    } else if p95 <= 50 && normalized_query_len >= 3 {
-        (base_seed_limit.saturating_mul(108)) / 100
-    } else {
-        base_seed_limit
-    };
-
-    let minimum = candidate_limit.max(INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT / 2);
-    scaled.clamp(minimum, INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT)
-}
-
-fn record_indexed_latency_sample(session: &mut OverlaySearchSession, indexed_ms: u128) {
-    session.indexed_latency_ms.push_back(indexed_ms);
-    while session.indexed_latency_ms.len() > ADAPTIVE_INDEXED_LATENCY_WINDOW {
-        session.indexed_latency_ms.pop_front();
-    }
-}
-
-fn final_query_cache_key(
-    parsed_query: &ParsedQuery,
-    filter: &SearchFilter,
-    normalized_query: &str,
-    result_limit: usize,
-) -> String {
-    format!(
-        "q={};mode={:?};kind={};ext={};include={};exclude={};modified={:?};created={:?};cmd={};limit={}",
-        normalized_query,
-        filter.mode,
-        filter.kind_filter.as_deref().unwrap_or("-"),
-        filter.extension_filter.as_deref().unwrap_or("-"),
-        encode_term_groups(&filter.include_groups),
-        filter.exclude_terms.join(","),
-        filter.modified_within,
-        filter.created_within,
-        parsed_query.command_mode,
-        result_limit
-    )
-}
-
-fn encode_term_groups(groups: &[Vec<String>]) -> String {
-    if groups.is_empty() {
-        return "-".to_string();
-    }
-
-    groups
-        .iter()
-        .map(|group| group.join("+"))
-        .collect::<Vec<String>>()
-        .join("|")
-}
-
-fn cached_final_query_results(
-    session: &mut OverlaySearchSession,
-    key: &str,
-) -> Option<Vec<crate::model::SearchItem>> {
-    let cached = session.final_query_cache.get(key).cloned()?;
-    if let Some(position) = session
-        .final_query_cache_lru
-        .iter()
-        .position(|entry| entry == key)
-    {
-        session.final_query_cache_lru.remove(position);
-    }
-    session.final_query_cache_lru.push_back(key.to_string());
-    Some(cached)
-}
-
-fn store_final_query_results(
-    session: &mut OverlaySearchSession,
-    key: String,
-    results: &[crate::model::SearchItem],
-) {
-    if results.is_empty() {
-        return;
-    }
-
-    session
-        .final_query_cache
-        .insert(key.clone(), results.to_vec());
-    if let Some(position) = session
-        .final_query_cache_lru
-        .iter()
-        .position(|entry| entry == &key)
-    {
-        session.final_query_cache_lru.remove(position);
-    }
-    session.final_query_cache_lru.push_back(key);
-
-    while session.final_query_cache.len() > FINAL_QUERY_CACHE_MAX_ENTRIES {
-        let Some(oldest) = session.final_query_cache_lru.pop_front() else {
-            break;
-        };
-        session.final_query_cache.remove(&oldest);
-    }
-}
-
-fn can_use_indexed_prefix_cache(
-    cache: &IndexedPrefixCache,
-    prefix_cache_eligible: bool,
-    normalized_query: &str,
-    indexed_filter: &SearchFilter,
-) -> bool {
-    if !prefix_cache_eligible {
-        return false;
-    }
-    if cache.seed_items.is_empty() || cache.normalized_query.is_empty() {
-        return false;
-    }
-    if !indexed_filter_matches_for_prefix_cache(&cache.indexed_filter, indexed_filter) {
-        return false;
-    }
-    normalized_query.len() > cache.normalized_query.len()
-        && normalized_query.starts_with(&cache.normalized_query)
-}
-
-fn indexed_filter_matches_for_prefix_cache(a: &SearchFilter, b: &SearchFilter) -> bool {
-    a.mode == b.mode
-        && a.kind_filter == b.kind_filter
-        && a.extension_filter == b.extension_filter
-        && a.modified_within == b.modified_within
-        && a.created_within == b.created_within
-}
-
-fn is_prefix_cache_eligible_query(parsed_query: &ParsedQuery, short_query_app_bias: bool) -> bool {
-    if short_query_app_bias || parsed_query.command_mode {
-        return false;
-    }
-    if parsed_query.mode_override.is_some()
-        || parsed_query.kind_filter.is_some()
-        || parsed_query.extension_filter.is_some()
-        || !parsed_query.exclude_terms.is_empty()
-        || parsed_query.modified_within.is_some()
-        || parsed_query.created_within.is_some()
-    {
-        return false;
-    }
-    if parsed_query.free_text.trim().is_empty() {
-        return false;
-    }
-    parsed_query.raw.trim() == parsed_query.free_text.trim()
-}
-
-fn sanitize_query_for_profile_log(query: &str) -> String {
-    const MAX_QUERY_LOG_CHARS: usize = 48;
-    let trimmed = query.trim();
-    if trimmed.is_empty() {
-        return "-".to_string();
-    }
-    let mut cleaned = String::new();
-    for ch in trimmed.chars().take(MAX_QUERY_LOG_CHARS) {
-        if ch.is_control() {
-            cleaned.push(' ');
-        } else {
-            cleaned.push(ch);
-        }
-    }
-    cleaned.trim().to_string()
-}
-
 #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 fn launch_overlay_selection(
     service: &CoreService,
@@ -3433,7 +3574,7 @@ fn launch_overlay_selection(
     results: &[crate::model::SearchItem],
     selected_index: usize,
     query_text: &str,
-) -> Result<(), String> {
+) -> Result<SelectionOutcome, String> {
     if results.is_empty() {
         return Err("no result selected".to_string());
     }
@@ -3447,40 +3588,190 @@ fn launch_overlay_selection(
 
     let selected = &results[selected_index];
     if selected.kind.eq_ignore_ascii_case("action") {
-        return execute_action_selection(service, cfg, plugins, selected);
+        return execute_action_selection(service, cfg, plugins, selected, query_text, results);
     }
     if selected.kind.eq_ignore_ascii_case("clipboard") {
-        return clipboard_history::copy_result_to_clipboard(cfg, &selected.id);
+        return clipboard_history::copy_result_to_clipboard(cfg, &selected.id)
+            .map(|()| SelectionOutcome::Done);
+    }
+    if selected.kind.eq_ignore_ascii_case("snippet") {
+        return execute_plugin_action(cfg, plugins, &selected.id, query_text, results);
     }
 
     let parsed_query = ParsedQuery::parse(query_text.trim(), cfg.search_dsl_enabled);
     let mode = resolved_mode_for_query(cfg, &parsed_query);
     service
         .launch_with_query_context(LaunchTarget::Id(&selected.id), Some(query_text), Some(mode))
+        .map(|()| SelectionOutcome::Done)
         .map_err(|error| format!("launch failed: {error}"))
 }
 
+#[cfg(target_os = "windows")]
+#[allow(clippy::too_many_arguments)]
+fn apply_selection_outcome(
+    outcome: Result<SelectionOutcome, String>,
+    overlay: &NativeOverlayShell,
+    overlay_state: &mut OverlayState,
+    current_results: &mut Vec<crate::model::SearchItem>,
+    selected_index: &mut usize,
+    pending_uninstall_confirmation: &mut Option<PendingUninstallConfirmation>,
+    last_query: &mut String,
+    search_session: &mut OverlaySearchSession,
+) {
+    match outcome {
+        Ok(SelectionOutcome::Output(item)) => {
+            *current_results = vec![*item];
+            *selected_index = 0;
+            search_session.clear();
+            let rows = overlay_rows(current_results, false);
+            overlay.set_results(&rows, *selected_index);
+            overlay.set_status_text("");
+        }
+        Ok(SelectionOutcome::Status(text)) => {
+            overlay.set_status_text(&text);
+        }
+        Ok(SelectionOutcome::Done) => {
+            overlay.set_status_text("");
+            overlay.hide_now();
+            overlay_state.on_escape();
+            reset_overlay_session(overlay, current_results, selected_index);
+            *pending_uninstall_confirmation = None;
+            last_query.clear();
+            search_session.clear();
+        }
+        // Workers only send finished outcomes.
+        Ok(SelectionOutcome::Pending(_)) => {}
+        Err(error) => {
+            overlay.set_status_text(&format!("Launch error: {error}"));
+        }
+    }
+}
+
+// A plugin command with `output = "result"` leaves a row behind instead of closing the
+// overlay, and `Status` keeps it open with a message. Captured commands and the index
+// check run on a worker thread and come back as `Pending`.
+#[derive(Debug)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+enum SelectionOutcome {
+    Done,
+    Output(Box<crate::model::SearchItem>),
+    Status(String),
+    Pending(PendingSelection),
+}
+
+// The outcome of a selection still running in the background. It only applies while the
+// overlay still shows `query`; otherwise the user has moved on and it is dropped.
+#[derive(Debug)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+struct PendingSelection {
+    query: String,
+    status: &'static str,
+    receiver: std::sync::mpsc::Receiver<Result<SelectionOutcome, String>>,
+}
+
+// Runs a captured plugin command without blocking the overlay thread, then wakes the
+// message loop. Clipboard output is copied as soon as the command finishes.
+fn spawn_plugin_capture(
+    cfg: Config,
+    result_id: String,
+    command: String,
+    args: Vec<String>,
+    output: CommandOutput,
+    query_text: &str,
+) -> PendingSelection {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let outcome = capture_plugin_command(&command, &args).and_then(|text| {
+            if output == CommandOutput::Clipboard {
+                clipboard_history::copy_text_to_clipboard(&cfg, &text)
+                    .map(|()| SelectionOutcome::Done)
+                    .map_err(|error| format!("copy plugin output failed: {error}"))
+            } else {
+                Ok(SelectionOutcome::Output(Box::new(plugin_output_item(
+                    &result_id, &text,
+                ))))
+            }
+        });
+        let _ = sender.send(outcome);
+        #[cfg(target_os = "windows")]
+        let _ = crate::windows_overlay::signal_background_task_done();
+    });
+    PendingSelection {
+        query: query_text.to_string(),
+        status: "Running plugin command...",
+        receiver,
+    }
+}
+
+// Integrity check plus VACUUM can take seconds on a large index, so it gets its own
+// connection on a worker thread, like the background index refresh.
+fn spawn_index_check(cfg: Config, query_text: &str) -> PendingSelection {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let outcome = CoreService::new(cfg)
+            .and_then(|service| service.check_index())
+            .map(|report| SelectionOutcome::Status(index_check_status_text(&report)))
+            .map_err(|error| format!("index check failed: {error}"));
+        if let Err(error) = &outcome {
+            log_warn(&format!("[nex] action_repair_index {error}"));
+        }
+        let _ = sender.send(outcome);
+        #[cfg(target_os = "windows")]
+        let _ = crate::windows_overlay::signal_background_task_done();
+    });
+    PendingSelection {
+        query: query_text.to_string(),
+        status: "Checking search index...",
+        receiver,
+    }
+}
+
+fn index_check_status_text(report: &crate::index_store::IndexMaintenanceReport) -> String {
+    if report.problems.is_empty() {
+        log_info("[nex] action_repair_index integrity=ok vacuumed=true");
+        return "Search index is healthy and compacted".to_string();
+    }
+    log_warn(&format!(
+        "[nex] action_repair_index integrity=failed problems={} (repair scheduled for next start)",
+        report.problems.join("; ")
+    ));
+    format!(
+        "Search index is damaged ({} problems); it will be repaired on next start",
+        report.problems.len()
+    )
+}
+
 #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 fn execute_action_selection(
     service: &CoreService,
     cfg: &Config,
     plugins: &PluginRegistry,
     selected: &crate::model::SearchItem,
-) -> Result<(), String> {
+    query_text: &str,
+    results: &[crate::model::SearchItem],
+) -> Result<SelectionOutcome, String> {
     if selected
         .id
         .starts_with(crate::uninstall_registry::ACTION_UNINSTALL_PREFIX)
     {
         return crate::uninstall_registry::execute_uninstall_action(&selected.id)
+            .map(|()| SelectionOutcome::Done)
             .map_err(|error| format!("uninstall launch failed: {error}"));
     }
 
     if selected.id.starts_with(ACTION_WEB_SEARCH_PREFIX) {
         return crate::action_executor::launch_open_target(selected.path.trim())
+            .map(|()| SelectionOutcome::Done)
             .map_err(|error| format!("web search launch failed: {error}"));
     }
 
-    match selected.id.as_str() {
+    if selected.id.starts_with(PLUGIN_OUTPUT_ID_PREFIX) {
+        return clipboard_history::copy_text_to_clipboard(cfg, &selected.subtitle)
+            .map(|()| SelectionOutcome::Done)
+            .map_err(|error| format!("copy plugin output failed: {error}"));
+    }
+
+    let done = match selected.id.as_str() {
         ACTION_OPEN_LOGS_ID => crate::logging::open_logs_folder()
             .map_err(|error| format!("open logs folder failed: {error}")),
         ACTION_REBUILD_INDEX_ID => {
@@ -3511,49 +3802,89 @@ fn execute_action_selection(
             log_info("[nex] trim memory action invoked");
             Ok(())
         }
-        _ => execute_plugin_action(cfg, plugins, &selected.id),
-    }
+        ACTION_REPAIR_INDEX_ID => {
+            return Ok(SelectionOutcome::Pending(spawn_index_check(
+                cfg.clone(),
+                query_text,
+            )))
+        }
+        _ => return execute_plugin_action(cfg, plugins, &selected.id, query_text, results),
+    };
+    done.map(|()| SelectionOutcome::Done)
 }
 
 fn execute_plugin_action(
     cfg: &Config,
     plugins: &PluginRegistry,
     result_id: &str,
-) -> Result<(), String> {
+    query_text: &str,
+    results: &[crate::model::SearchItem],
+) -> Result<SelectionOutcome, String> {
     let action = plugins
-        .actions_by_result_id
-        .get(result_id)
+        .action(result_id)
         .ok_or_else(|| "unknown action".to_string())?;
 
-    match &action.kind {
-        PluginActionKind::OpenPath { path } => crate::action_executor::launch_path(path)
-            .map_err(|error| format!("plugin open path failed: {error}")),
-        PluginActionKind::Command { command, args } => {
-            if cfg.plugins_safe_mode {
-                return Err(
-                    "plugin command execution blocked: plugins_safe_mode is enabled in config"
-                        .to_string(),
-                );
-            }
-            if command.trim().is_empty() {
-                return Err("plugin command action missing command".to_string());
-            }
-            std::process::Command::new(command)
-                .args(args)
-                .spawn()
-                .map_err(|e| format!("plugin command spawn failed: {e}"))?;
-            Ok(())
-        }
-    }
+    match run_plugin_action(cfg, plugins, &action, query_text, results)? {
+        PluginActionOutcome::Done => Ok(SelectionOutcome::Done),
+        PluginActionOutcome::Capture {
+            command,
+            args,
+            output,
+        } => Ok(SelectionOutcome::Pending(spawn_plugin_capture(
+            cfg.clone(),
+            action.result_id,
+            command,
+            args,
+            output,
+            query_text,
+        ))),
+    }
+}
+
+// Tab swaps the results for the selected item's secondary actions. They are plugin action
+// ids, so submitting one runs like any other plugin action; typing searches again.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn secondary_action_results(item: &crate::model::SearchItem) -> Vec<crate::model::SearchItem> {
+    item.secondary_actions
+        .iter()
+        .map(|action| {
+            crate::model::SearchItem::new(&action.id, "action", &action.title, "")
+                .with_icon(&action.icon)
+        })
+        .collect()
 }
 
-fn configure_stdio_logging(options: RuntimeOptions) {
+// The full output rides in `subtitle`, which action rows do not display; selecting the
+// row copies it.
+fn plugin_output_item(result_id: &str, output: &str) -> crate::model::SearchItem {
+    let title = output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("(no output)");
+    let mut item = crate::model::SearchItem::new(
+        &format!("{PLUGIN_OUTPUT_ID_PREFIX}{result_id}"),
+        "action",
+        title,
+        "",
+    )
+    .with_subtitle(output);
+    item.snippet = "Enter to copy output".to_string();
+    item
+}
+
+fn configure_stdio_logging(options: &RuntimeOptions) {
     let suppress_from_env = env_var_with_legacy("NEX_SUPPRESS_STDIO", "SWIFTFIND_SUPPRESS_STDIO")
         .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
         .unwrap_or(false);
     let suppress_for_background = options.command == RuntimeCommand::Run && options.background;
+    // Client output is meant for pipes; keep log lines out of it.
+    let suppress_for_client = matches!(
+        options.command,
+        RuntimeCommand::Search { .. } | RuntimeCommand::Open(_) | RuntimeCommand::ValidatePlugin(_)
+    );
     STDIO_LOGGING_ENABLED.store(
-        !(suppress_from_env || suppress_for_background),
+        !(suppress_from_env || suppress_for_background || suppress_for_client),
         Ordering::Relaxed,
     );
 }
@@ -3579,60 +3910,182 @@ fn log_warn(message: &str) {
 #[cfg(test)]
 mod tests {
     use super::{
-        adaptive_indexed_seed_limit, can_use_indexed_prefix_cache, candidate_limit_for_query,
         dedupe_overlay_results, filter_suppressed_uninstall_results, launch_overlay_selection,
         maybe_expand_uninstall_quick_shortcut, next_selection_index, parse_cli_args,
-        parse_status_diagnostics_snapshot, parse_tasklist_pid_lines, result_limit_for_query,
-        search_overlay_results, search_overlay_results_with_session,
-        should_block_hotkey_for_foreground_window, should_hide_known_start_menu_doc_sample_entry,
-        should_skip_non_searchable_query, summarize_query_profiles,
-        track_uninstall_title_suppression,
-        uninstall_confirmation_results, uninstall_target_title_from_action_title,
-        ForegroundWindowSnapshot, IndexedPrefixCache, OverlaySearchSession, RuntimeCommand,
-        RuntimeOptions, ACTION_UNINSTALL_CANCEL_ID, ACTION_UNINSTALL_CONFIRM_ID,
-        INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT, INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT,
-        UNINSTALL_QUERY_RESULT_LIMIT,
+        parse_status_diagnostics_snapshot, parse_tasklist_pid_lines, plugin_reload_log_line,
+        plugin_scope_strip_text, provider_degraded_status_text, result_limit_for_query,
+        secondary_action_results, should_block_hotkey_for_foreground_window,
+        should_hide_known_start_menu_doc_sample_entry, summarize_query_profiles,
+        track_uninstall_title_suppression, uninstall_confirmation_results,
+        uninstall_target_title_from_action_title, ForegroundWindowSnapshot, RuntimeCommand,
+        RuntimeOptions, SelectionOutcome, ACTION_UNINSTALL_CANCEL_ID, ACTION_UNINSTALL_CONFIRM_ID,
+        PLUGIN_OUTPUT_ID_PREFIX, UNINSTALL_QUERY_RESULT_LIMIT,
     };
-    use crate::action_registry::{ACTION_DIAGNOSTICS_BUNDLE_ID, ACTION_WEB_SEARCH_PREFIX};
-    use crate::config::{Config, SearchMode};
+    use crate::action_registry::ACTION_REPAIR_INDEX_ID;
+    use crate::config::Config;
     use crate::core_service::CoreService;
     use crate::index_store::open_memory;
     use crate::model::SearchItem;
-    use crate::plugin_sdk::PluginRegistry;
+    use crate::overlay_search::search_overlay_results;
+    use crate::plugin_sdk::{CommandOutput, PluginActionKind, PluginCapability, PluginRegistry};
     use crate::query_dsl::ParsedQuery;
-    use crate::search::SearchFilter;
     use std::time::{SystemTime, UNIX_EPOCH};
 
     #[test]
-    fn overlay_search_returns_ranked_results() {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("clock should be valid")
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!("nex-overlay-search-{unique}.tmp"));
-        std::fs::write(&path, b"ok").expect("temp file should be created");
-
+    fn trigger_keyword_queries_only_reach_their_plugin() {
         let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
             .expect("service should initialize");
-        service
-            .upsert_item(&SearchItem::new(
-                "item-1",
-                "app",
-                "Visual Studio Code",
-                path.to_string_lossy().as_ref(),
-            ))
-            .expect("item should upsert");
-
-        let parsed = ParsedQuery::parse("code", true);
+        let plugins = PluginRegistry {
+            triggers: vec![crate::plugin_sdk::PluginTrigger {
+                plugin_id: "github".to_string(),
+                label: "GitHub".to_string(),
+                keywords: vec!["gh".to_string()],
+                global: false,
+                action_items: vec![SearchItem::new(
+                    "plugin:github:action:issues",
+                    "action",
+                    "Open issues",
+                    "GitHub plugin action",
+                )],
+                process: None,
+            }],
+            ..PluginRegistry::default()
+        };
         let cfg = Config::default();
-        let plugins = PluginRegistry::default();
+
+        let parsed = ParsedQuery::parse("gh issues", true);
         let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 20)
             .expect("search should succeed");
-
         assert_eq!(results.len(), 1);
-        assert_eq!(results[0].id, "item-1");
+        assert_eq!(results[0].id, "plugin:github:action:issues");
+        assert_eq!(
+            plugin_scope_strip_text(&plugins, "gh issues"),
+            "GitHub  \u{203a}  gh"
+        );
+        assert!(plugin_scope_strip_text(&plugins, "issues").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plugin_command_output_becomes_a_result_row() {
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        let result_id = "plugin:shout:action:echo";
+        let mut plugins = PluginRegistry::default();
+        plugins
+            .capabilities
+            .insert("shout".to_string(), vec![PluginCapability::RunCommands]);
+        plugins.actions_by_result_id.insert(
+            result_id.to_string(),
+            crate::plugin_sdk::PluginAction {
+                result_id: result_id.to_string(),
+                plugin_id: "shout".to_string(),
+                action_id: "echo".to_string(),
+                title: "Echo".to_string(),
+                subtitle: String::new(),
+                keywords: Vec::new(),
+                kind: PluginActionKind::Command {
+                    command: "echo".to_string(),
+                    args: vec!["q={query:url}".to_string(), "{selection_path}".to_string()],
+                    output: CommandOutput::Result,
+                },
+            },
+        );
+        let results = vec![
+            SearchItem::new(result_id, "action", "Echo", ""),
+            SearchItem::new("file-1", "file", "Notes", "/tmp/notes.txt"),
+        ];
+        let mut cfg = Config {
+            plugins_safe_mode: false,
+            ..Config::default()
+        };
+
+        let error = launch_overlay_selection(&service, &cfg, &plugins, &results, 0, "a b")
+            .expect_err("ungranted capability should be refused");
+        assert!(error.contains("\"shout:run_commands\""));
+        cfg.plugin_grants = vec!["shout:run_commands".to_string()];
+
+        let outcome = launch_overlay_selection(&service, &cfg, &plugins, &results, 0, "a b")
+            .expect("command should start");
+        let SelectionOutcome::Pending(pending) = outcome else {
+            panic!("expected the capture to run in the background");
+        };
+        assert_eq!(pending.query, "a b");
+        let outcome = pending
+            .receiver
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("capture should finish")
+            .expect("command should run");
+        let SelectionOutcome::Output(item) = outcome else {
+            panic!("expected an output row");
+        };
+        assert_eq!(item.id, format!("{PLUGIN_OUTPUT_ID_PREFIX}{result_id}"));
+        assert_eq!(item.title, "q=a%20b /tmp/notes.txt");
+        assert_eq!(item.subtitle, "q=a%20b /tmp/notes.txt");
+    }
 
-        std::fs::remove_file(path).expect("temp file should be removed");
+    #[test]
+    fn repair_index_action_checks_the_index_in_the_background() {
+        let root = std::env::temp_dir().join(format!(
+            "nex-runtime-check-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let cfg = Config {
+            index_db_path: root.join("index.sqlite3"),
+            ..Config::default()
+        };
+        let service = CoreService::new(cfg.clone()).expect("service should initialize");
+        let plugins = PluginRegistry::default();
+        let results = vec![SearchItem::new(
+            ACTION_REPAIR_INDEX_ID,
+            "action",
+            "Repair",
+            "",
+        )];
+
+        let outcome = launch_overlay_selection(&service, &cfg, &plugins, &results, 0, ">repair")
+            .expect("check should start");
+        let SelectionOutcome::Pending(pending) = outcome else {
+            panic!("expected the check to run in the background");
+        };
+        assert_eq!(pending.status, "Checking search index...");
+        let outcome = pending
+            .receiver
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("check should finish")
+            .expect("check should succeed");
+        let SelectionOutcome::Status(text) = outcome else {
+            panic!("expected a status message");
+        };
+        assert_eq!(text, "Search index is healthy and compacted");
+
+        drop(service);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn plugin_reload_log_line_lists_changes() {
+        let plugins = PluginRegistry::default();
+        let unchanged = crate::plugin_sdk::PluginRegistryDiff::default();
+        assert!(plugin_reload_log_line(&unchanged, &plugins).is_none());
+
+        let diff = crate::plugin_sdk::PluginRegistryDiff {
+            added_plugins: vec!["todo".to_string()],
+            changed_actions: vec![
+                "plugin:notes:action:a".to_string(),
+                "plugin:notes:action:b".to_string(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            plugin_reload_log_line(&diff, &plugins).unwrap(),
+            "[nex] plugins reloaded added=todo removed=- actions_added=- actions_removed=- \
+             actions_changed=plugin:notes:action:a,plugin:notes:action:b new_warnings=0 \
+             action_items=0"
+        );
     }
 
     #[test]
@@ -3733,25 +4186,6 @@ mod tests {
         assert_eq!(next_selection_index(5, 3, 0), 2);
     }
 
-    #[test]
-    fn candidate_limit_adapts_to_query_shape() {
-        let all = SearchFilter::default();
-        let empty_all = candidate_limit_for_query(20, &all, "", false);
-        let short_all = candidate_limit_for_query(20, &all, "v", false);
-        let medium_all = candidate_limit_for_query(20, &all, "vi", false);
-        let long_all = candidate_limit_for_query(20, &all, "vivaldi", false);
-        assert!(empty_all <= short_all);
-        assert!(short_all < medium_all);
-        assert!(medium_all <= long_all);
-
-        let actions = SearchFilter {
-            mode: SearchMode::Actions,
-            ..SearchFilter::default()
-        };
-        let short_actions = candidate_limit_for_query(20, &actions, "v", true);
-        assert!(short_actions < long_all);
-    }
-
     #[test]
     fn uninstall_queries_use_expanded_result_limit() {
         let parsed = ParsedQuery::parse(">uninstall", true);
@@ -3817,6 +4251,47 @@ mod tests {
         assert_eq!(results[1].title, "Cancel");
     }
 
+    #[test]
+    fn secondary_actions_open_as_plugin_action_rows() {
+        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
+            .expect("service should initialize");
+        let action_id = "plugin:github:item:repo:secondary:copy";
+        let mut plugins = PluginRegistry::default();
+        plugins.actions_by_result_id.insert(
+            action_id.to_string(),
+            crate::plugin_sdk::PluginAction {
+                result_id: action_id.to_string(),
+                plugin_id: "github".to_string(),
+                action_id: "copy".to_string(),
+                title: "Copy link".to_string(),
+                subtitle: String::new(),
+                keywords: Vec::new(),
+                kind: PluginActionKind::Copy {
+                    text: "{env:GITHUB_TOKEN}".to_string(),
+                },
+            },
+        );
+        let item = SearchItem::new("plugin:github:item:repo", "file", "nex", "")
+            .with_secondary_actions(vec![crate::model::ItemAction {
+                id: action_id.to_string(),
+                title: "Copy link".to_string(),
+                icon: "\u{e8c8}".to_string(),
+            }]);
+
+        let results = secondary_action_results(&item);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, action_id);
+        assert_eq!(results[0].kind, "action");
+        assert_eq!(results[0].icon, "\u{e8c8}");
+        assert!(secondary_action_results(&results[0]).is_empty());
+
+        // Submitting the row runs the registered action, capability checks included.
+        let error =
+            launch_overlay_selection(&service, &Config::default(), &plugins, &results, 0, "")
+                .expect_err("undeclared capability should be refused");
+        assert!(error.contains("'read_env'"), "{error}");
+    }
+
     #[test]
     fn suppressed_uninstall_results_are_filtered_from_results() {
         let mut results = vec![
@@ -3901,56 +4376,6 @@ mod tests {
         assert!(!should_hide_known_start_menu_doc_sample_entry(&normal_lnk));
     }
 
-    #[test]
-    fn prefix_cache_predicate_requires_same_filter_and_extended_query() {
-        let cache = IndexedPrefixCache {
-            normalized_query: "vi".to_string(),
-            indexed_filter: SearchFilter::default(),
-            seed_items: vec![SearchItem::new(
-                "app-1",
-                "app",
-                "Vivaldi",
-                "C:\\Vivaldi.exe",
-            )],
-        };
-
-        assert!(can_use_indexed_prefix_cache(
-            &cache,
-            true,
-            "viv",
-            &SearchFilter::default()
-        ));
-        assert!(!can_use_indexed_prefix_cache(
-            &cache,
-            true,
-            "vi",
-            &SearchFilter::default()
-        ));
-        assert!(!can_use_indexed_prefix_cache(
-            &cache,
-            true,
-            "xvi",
-            &SearchFilter::default()
-        ));
-
-        let different_mode = SearchFilter {
-            mode: SearchMode::Apps,
-            ..SearchFilter::default()
-        };
-        assert!(!can_use_indexed_prefix_cache(
-            &cache,
-            true,
-            "viv",
-            &different_mode
-        ));
-        assert!(!can_use_indexed_prefix_cache(
-            &cache,
-            false,
-            "viv",
-            &SearchFilter::default()
-        ));
-    }
-
     #[test]
     fn game_mode_does_not_block_standard_maximized_apps() {
         let snapshot = ForegroundWindowSnapshot {
@@ -3979,73 +4404,6 @@ mod tests {
         assert!(should_block_hotkey_for_foreground_window(&snapshot));
     }
 
-    #[test]
-    fn repeated_overlay_query_uses_final_cache() {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("clock should be valid")
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!("nex-overlay-cache-{unique}.tmp"));
-        std::fs::write(&path, b"ok").expect("temp file should be created");
-
-        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
-            .expect("service should initialize");
-        service
-            .upsert_item(&SearchItem::new(
-                "item-1",
-                "app",
-                "Vivaldi",
-                path.to_string_lossy().as_ref(),
-            ))
-            .expect("item should upsert");
-
-        let cfg = Config::default();
-        let plugins = PluginRegistry::default();
-        let parsed = ParsedQuery::parse("vi", true);
-        let mut session = OverlaySearchSession::default();
-
-        let first = search_overlay_results_with_session(
-            &service,
-            &cfg,
-            &plugins,
-            &parsed,
-            20,
-            &mut session,
-        )
-        .expect("first query should succeed");
-        let sample_count_after_first = session.indexed_latency_ms.len();
-
-        let second = search_overlay_results_with_session(
-            &service,
-            &cfg,
-            &plugins,
-            &parsed,
-            20,
-            &mut session,
-        )
-        .expect("second query should succeed");
-
-        assert_eq!(first, second);
-        assert_eq!(session.indexed_latency_ms.len(), sample_count_after_first);
-        assert!(!session.final_query_cache.is_empty());
-
-        std::fs::remove_file(path).expect("temp file should be removed");
-    }
-
-    #[test]
-    fn adaptive_seed_limit_reduces_on_high_latency_window() {
-        let mut session = OverlaySearchSession::default();
-        session
-            .indexed_latency_ms
-            .extend(std::iter::repeat(170_u128).take(12));
-
-        let base = 320;
-        let tuned = adaptive_indexed_seed_limit(&session, 120, 1, base);
-        assert!(tuned < base);
-        assert!(tuned >= INDEXED_PREFIX_CACHE_MIN_SEED_LIMIT / 2);
-        assert!(tuned <= INDEXED_PREFIX_CACHE_MAX_SEED_LIMIT);
-    }
-
     #[test]
     fn parses_background_run_args() {
         let args = vec!["--background".to_string()];
@@ -4080,6 +4438,110 @@ mod tests {
         assert!(!options.background);
     }
 
+    #[test]
+    fn parses_repair_index_command() {
+        let args = vec!["--repair-index".to_string()];
+        let options = parse_cli_args(&args).expect("repair command should parse");
+        assert_eq!(options.command, RuntimeCommand::RepairIndex);
+
+        let args = vec!["--downgrade-index=3".to_string()];
+        let options = parse_cli_args(&args).expect("downgrade command should parse");
+        assert_eq!(options.command, RuntimeCommand::DowngradeIndex(3));
+        assert!(parse_cli_args(&["--downgrade-index=x".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parses_search_and_open_subcommands() {
+        let args = [
+            "search",
+            "visual studio",
+            "--mode",
+            "files",
+            "--json",
+            "--limit=3",
+        ]
+        .map(String::from)
+        .to_vec();
+        let options = parse_cli_args(&args).expect("search should parse");
+        assert_eq!(
+            options.command,
+            RuntimeCommand::Search {
+                query: "visual studio".to_string(),
+                mode: Some(crate::config::SearchMode::Files),
+                json: true,
+                limit: Some(3),
+            }
+        );
+
+        let args = ["search", "code", "notes"].map(String::from).to_vec();
+        let options = parse_cli_args(&args).expect("unquoted terms should parse");
+        assert!(matches!(
+            options.command,
+            RuntimeCommand::Search { ref query, mode: None, json: false, limit: None } if query == "code notes"
+        ));
+
+        assert!(parse_cli_args(&["search".to_string()]).is_err());
+        assert!(parse_cli_args(&["search", "x", "--mode", "music"].map(String::from)).is_err());
+        assert!(parse_cli_args(&["search", "x", "--limit", "0"].map(String::from)).is_err());
+
+        let options =
+            parse_cli_args(&["open", "app:code"].map(String::from)).expect("open should parse");
+        assert_eq!(
+            options.command,
+            RuntimeCommand::Open("app:code".to_string())
+        );
+        assert!(parse_cli_args(&["open".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parses_serve_command() {
+        let options = parse_cli_args(&["--serve".to_string()]).expect("serve should parse");
+        assert_eq!(options.command, RuntimeCommand::Serve);
+        assert!(parse_cli_args(&["--serve".to_string(), "--background".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parses_state_archive_commands() {
+        let args = vec![
+            "--rewrite-path".to_string(),
+            "C:\\Users\\alice=C:\\Users\\bob".to_string(),
+            "--import-state".to_string(),
+            "state.tar".to_string(),
+        ];
+        let options = parse_cli_args(&args).expect("import should parse");
+        assert_eq!(
+            options.command,
+            RuntimeCommand::ImportState {
+                archive: std::path::PathBuf::from("state.tar"),
+                rewrites: vec![crate::state_archive::PathRewrite::new(
+                    "C:\\Users\\alice",
+                    "C:\\Users\\bob"
+                )],
+            }
+        );
+
+        let args = vec!["--export-state".to_string()];
+        assert!(parse_cli_args(&args).is_err());
+        let args = vec![
+            "--export-state".to_string(),
+            "state.tar".to_string(),
+            "--rewrite-path".to_string(),
+            "a=b".to_string(),
+        ];
+        assert!(parse_cli_args(&args).is_err());
+    }
+
+    #[test]
+    fn parses_validate_plugin_command() {
+        let args = vec!["--validate-plugin".to_string(), "plugins".to_string()];
+        let options = parse_cli_args(&args).expect("validate should parse");
+        assert_eq!(
+            options.command,
+            RuntimeCommand::ValidatePlugin(std::path::PathBuf::from("plugins"))
+        );
+        assert!(parse_cli_args(&["--validate-plugin".to_string()]).is_err());
+    }
+
     #[test]
     fn parses_set_launch_at_startup_command() {
         let args = vec!["--set-launch-at-startup=true".to_string()];
@@ -4107,119 +4569,6 @@ mod tests {
         assert!(error.contains("background mode"));
     }
 
-    #[test]
-    fn command_mode_returns_action_results() {
-        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
-            .expect("service should initialize");
-        let cfg = Config::default();
-        let plugins = PluginRegistry::default();
-        let parsed = ParsedQuery::parse(">diag", true);
-        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 10)
-            .expect("search should succeed");
-        assert!(results
-            .iter()
-            .any(|item| item.id == ACTION_DIAGNOSTICS_BUNDLE_ID));
-    }
-
-    #[test]
-    fn command_mode_includes_web_search_action() {
-        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
-            .expect("service should initialize");
-        let cfg = Config::default();
-        let plugins = PluginRegistry::default();
-        let parsed = ParsedQuery::parse(">nex roadmap", true);
-        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 10)
-            .expect("search should succeed");
-        assert!(results
-            .iter()
-            .any(|item| item.id.starts_with(ACTION_WEB_SEARCH_PREFIX)));
-    }
-
-    #[test]
-    fn short_single_letter_query_in_all_mode_biases_to_apps() {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("clock should be valid")
-            .as_nanos();
-        let app_path = std::env::temp_dir().join(format!("nex-short-query-app-{unique}.tmp"));
-        let file_path =
-            std::env::temp_dir().join(format!("nex-short-query-file-{unique}.tmp"));
-        std::fs::write(&app_path, b"ok").expect("app temp file should be created");
-        std::fs::write(&file_path, b"ok").expect("file temp file should be created");
-
-        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
-            .expect("service should initialize");
-        service
-            .upsert_item(&SearchItem::new(
-                "app-1",
-                "app",
-                "Vivaldi Browser",
-                app_path.to_string_lossy().as_ref(),
-            ))
-            .expect("app should upsert");
-        service
-            .upsert_item(&SearchItem::new(
-                "file-1",
-                "file",
-                "Vacation Notes",
-                file_path.to_string_lossy().as_ref(),
-            ))
-            .expect("file should upsert");
-
-        let parsed = ParsedQuery::parse("v", true);
-        let cfg = Config::default();
-        let plugins = PluginRegistry::default();
-        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 20)
-            .expect("search should succeed");
-        assert!(results.iter().any(|item| item.id == "app-1"));
-        assert!(!results.iter().any(|item| item.id == "file-1"));
-
-        std::fs::remove_file(app_path).expect("app temp file should be removed");
-        std::fs::remove_file(file_path).expect("file temp file should be removed");
-    }
-
-    #[test]
-    fn short_two_letter_query_in_all_mode_biases_to_apps() {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("clock should be valid")
-            .as_nanos();
-        let app_path = std::env::temp_dir().join(format!("nex-short-two-app-{unique}.tmp"));
-        let file_path = std::env::temp_dir().join(format!("nex-short-two-file-{unique}.tmp"));
-        std::fs::write(&app_path, b"ok").expect("app temp file should be created");
-        std::fs::write(&file_path, b"ok").expect("file temp file should be created");
-
-        let service = CoreService::with_connection(Config::default(), open_memory().unwrap())
-            .expect("service should initialize");
-        service
-            .upsert_item(&SearchItem::new(
-                "app-1",
-                "app",
-                "Valorant",
-                app_path.to_string_lossy().as_ref(),
-            ))
-            .expect("app should upsert");
-        service
-            .upsert_item(&SearchItem::new(
-                "file-1",
-                "file",
-                "Valuation Notes",
-                file_path.to_string_lossy().as_ref(),
-            ))
-            .expect("file should upsert");
-
-        let parsed = ParsedQuery::parse("va", true);
-        let cfg = Config::default();
-        let plugins = PluginRegistry::default();
-        let results = search_overlay_results(&service, &cfg, &plugins, &parsed, 20)
-            .expect("search should succeed");
-        assert!(results.iter().any(|item| item.id == "app-1"));
-        assert!(!results.iter().any(|item| item.id == "file-1"));
-
-        std::fs::remove_file(app_path).expect("app temp file should be removed");
-        std::fs::remove_file(file_path).expect("file temp file should be removed");
-    }
-
     #[test]
     fn dedupes_duplicate_app_titles_for_overlay() {
         let mut results = vec![
@@ -4270,6 +4619,19 @@ mod tests {
         assert_eq!(ids, vec!["a1", "f2"]);
     }
 
+    #[test]
+    fn degraded_status_text_lists_providers() {
+        assert_eq!(provider_degraded_status_text(&[]), None);
+        assert_eq!(
+            provider_degraded_status_text(&[
+                "filesystem".to_string(),
+                "recent-documents".to_string()
+            ])
+            .as_deref(),
+            Some("Provider degraded: filesystem, recent-documents (results may be incomplete)")
+        );
+    }
+
     #[test]
     fn parses_status_diagnostics_snapshot_from_log_content() {
         let content = "\
@@ -4317,21 +4679,6 @@ mod tests {
         assert_eq!(summary.short_query_p95_total_ms, 27);
     }
 
-    #[test]
-    fn skips_non_searchable_symbol_only_query() {
-        let parsed = ParsedQuery::parse("-", true);
-        let normalized = crate::model::normalize_for_search(parsed.free_text.trim());
-        assert!(should_skip_non_searchable_query(&parsed, &normalized));
-
-        let parsed_command = ParsedQuery::parse(">-", true);
-        let normalized_command =
-            crate::model::normalize_for_search(parsed_command.free_text.trim());
-        assert!(!should_skip_non_searchable_query(
-            &parsed_command,
-            &normalized_command
-        ));
-    }
-
     #[test]
     fn parses_tasklist_pid_lines_from_list_output() {
         let content = "\
@@ -71,6 +71,7 @@ pub struct SearchFilter {
     pub exclude_terms: Vec<String>,
     pub modified_within: Option<TimeFilterWindow>,
     pub created_within: Option<TimeFilterWindow>,
+    pub content_query: Option<String>,
 }
 
 impl Default for SearchFilter {
@@ -85,6 +86,7 @@ impl Default for SearchFilter {
             exclude_terms: Vec::new(),
             modified_within: None,
             created_within: None,
+            content_query: None,
         }
     }
 }
@@ -226,6 +228,10 @@ fn score_item(
     if !matches_mode(item, filter.mode) {
         return None;
     }
+    // Content matches are resolved by the index; anything without a snippet did not match.
+    if filter.content_query.is_some() && item.snippet.is_empty() {
+        return None;
+    }
     if let Some(kind) = &filter.kind_filter {
         if !matches_kind_filter(item, kind) {
             return None;
@@ -352,6 +358,8 @@ fn mode_bonus(item: &SearchItem, mode: SearchMode) -> i64 {
         }
         SearchMode::Actions if item.kind.eq_ignore_ascii_case("action") => 550,
         SearchMode::Clipboard if item.kind.eq_ignore_ascii_case("clipboard") => 550,
+        SearchMode::Bookmarks if item.kind.eq_ignore_ascii_case("bookmark") => 550,
+        SearchMode::Snippets if item.kind.eq_ignore_ascii_case("snippet") => 550,
         _ => -2_500,
     }
 }
@@ -555,6 +563,7 @@ fn is_default_filter(filter: &SearchFilter) -> bool {
         && filter.exclude_terms.is_empty()
         && filter.modified_within.is_none()
         && filter.created_within.is_none()
+        && filter.content_query.is_none()
 }
 
 fn matches_mode(item: &SearchItem, mode: SearchMode) -> bool {
@@ -566,6 +575,8 @@ fn matches_mode(item: &SearchItem, mode: SearchMode) -> bool {
         }
         SearchMode::Actions => item.kind.eq_ignore_ascii_case("action"),
         SearchMode::Clipboard => item.kind.eq_ignore_ascii_case("clipboard"),
+        SearchMode::Bookmarks => item.kind.eq_ignore_ascii_case("bookmark"),
+        SearchMode::Snippets => item.kind.eq_ignore_ascii_case("snippet"),
     }
 }
 
@@ -589,6 +600,12 @@ fn matches_kind_filter(item: &SearchItem, kind_filter: &str) -> bool {
     if normalized == "clipboard" {
         return item.kind.eq_ignore_ascii_case("clipboard");
     }
+    if normalized == "bookmark" || normalized == "bookmarks" {
+        return item.kind.eq_ignore_ascii_case("bookmark");
+    }
+    if normalized == "command" || normalized == "commands" {
+        return item.kind.eq_ignore_ascii_case("command");
+    }
     item.kind.eq_ignore_ascii_case(&normalized)
 }
 
@@ -0,0 +1,598 @@
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::clipboard_history::{self, ClipboardEntry};
+use crate::config::{self, Config, ConfigError};
+use crate::index_store::{self, IndexBatchWriter, QuerySelectionRecord, StoreError};
+use crate::model::SearchItem;
+
+pub const STATE_ARCHIVE_FORMAT: &str = "nex-state";
+pub const STATE_ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const STATE_ENTRY: &str = "state.jsonl";
+const CONFIG_ENTRY_PREFIX: &str = "config.";
+const TAR_BLOCK: usize = 512;
+
+#[derive(Debug)]
+pub enum StateArchiveError {
+    Io(std::io::Error),
+    Store(StoreError),
+    Config(ConfigError),
+    Format(String),
+}
+
+impl Display for StateArchiveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "io error: {error}"),
+            Self::Store(error) => write!(f, "store error: {error}"),
+            Self::Config(error) => write!(f, "config error: {error}"),
+            Self::Format(error) => write!(f, "archive format error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for StateArchiveError {}
+
+impl From<std::io::Error> for StateArchiveError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<StoreError> for StateArchiveError {
+    fn from(value: StoreError) -> Self {
+        Self::Store(value)
+    }
+}
+
+impl From<ConfigError> for StateArchiveError {
+    fn from(value: ConfigError) -> Self {
+        Self::Config(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathRewrite {
+    pub from: String,
+    pub to: String,
+}
+
+impl PathRewrite {
+    pub fn new(from: &str, to: &str) -> Self {
+        Self {
+            from: trim_separators(from).to_string(),
+            to: trim_separators(to).to_string(),
+        }
+    }
+
+    // `FROM=TO`, e.g. `C:\Users\alice=C:\Users\bob`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (from, to) = spec.split_once('=')?;
+        let rewrite = Self::new(from.trim(), to.trim());
+        if rewrite.from.is_empty() || rewrite.to.is_empty() {
+            return None;
+        }
+        Some(rewrite)
+    }
+
+    fn apply(&self, value: &str) -> Option<String> {
+        for offset in path_offsets(value) {
+            let rest = &value[offset..];
+            let Some(matched) = rest.get(..self.from.len()) else {
+                continue;
+            };
+            if !matched.eq_ignore_ascii_case(&self.from) {
+                continue;
+            }
+            let tail = &rest[self.from.len()..];
+            if !(tail.is_empty() || tail.starts_with(['\\', '/'])) {
+                continue;
+            }
+            // Ids are built from lowercased paths; keep them in the form discovery produces.
+            let is_lowercased =
+                matched == matched.to_ascii_lowercase() && matched != matched.to_ascii_uppercase();
+            let to = if is_lowercased {
+                self.to.to_ascii_lowercase()
+            } else {
+                self.to.clone()
+            };
+            return Some(format!("{}{to}{tail}", &value[..offset]));
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub items: usize,
+    pub query_selections: usize,
+    pub clipboard_entries: usize,
+    pub config_included: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub items: usize,
+    pub query_selections: usize,
+    pub clipboard_entries: usize,
+    pub config_imported: bool,
+    pub rewritten_paths: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchiveManifest {
+    format: String,
+    version: u32,
+    exported_epoch_secs: i64,
+    #[serde(default)]
+    profile_root: Option<String>,
+    #[serde(default)]
+    config_entry: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StateRecord {
+    Item {
+        id: String,
+        kind: String,
+        title: String,
+        path: String,
+        #[serde(default)]
+        subtitle: String,
+        use_count: u32,
+        last_accessed_epoch_secs: i64,
+    },
+    QuerySelection {
+        query_norm: String,
+        mode: String,
+        item_id: String,
+        selected_count: i64,
+        last_selected_epoch_secs: i64,
+    },
+    Clipboard {
+        id: String,
+        text: String,
+        captured_epoch_secs: i64,
+    },
+}
+
+pub fn export_state(
+    cfg: &Config,
+    db: &Connection,
+    archive_path: &Path,
+) -> Result<ExportSummary, StateArchiveError> {
+    let mut summary = ExportSummary::default();
+    let mut lines = String::new();
+
+    // Only learned signals travel; the catalog itself is rediscovered on the new machine.
+    for item in index_store::list_items(db)? {
+        if item.use_count == 0 && item.last_accessed_epoch_secs <= 0 {
+            continue;
+        }
+        push_record(
+            &mut lines,
+            &StateRecord::Item {
+                id: item.id,
+                kind: item.kind,
+                title: item.title,
+                path: item.path,
+                subtitle: item.subtitle,
+                use_count: item.use_count,
+                last_accessed_epoch_secs: item.last_accessed_epoch_secs,
+            },
+        )?;
+        summary.items += 1;
+    }
+    for record in index_store::list_all_query_selections(db)? {
+        push_record(
+            &mut lines,
+            &StateRecord::QuerySelection {
+                query_norm: record.query_norm,
+                mode: record.mode,
+                item_id: record.item_id,
+                selected_count: record.selected_count,
+                last_selected_epoch_secs: record.last_selected_epoch_secs,
+            },
+        )?;
+        summary.query_selections += 1;
+    }
+    for entry in clipboard_history::load_history(cfg) {
+        push_record(
+            &mut lines,
+            &StateRecord::Clipboard {
+                id: entry.id,
+                text: entry.text,
+                captured_epoch_secs: entry.captured_epoch_secs,
+            },
+        )?;
+        summary.clipboard_entries += 1;
+    }
+
+    let config_entry = if cfg.config_path.exists() {
+        let extension = cfg
+            .config_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("toml")
+            .to_ascii_lowercase();
+        Some((
+            format!("{CONFIG_ENTRY_PREFIX}{extension}"),
+            std::fs::read(&cfg.config_path)?,
+        ))
+    } else {
+        None
+    };
+    summary.config_included = config_entry.is_some();
+
+    let manifest = ArchiveManifest {
+        format: STATE_ARCHIVE_FORMAT.to_string(),
+        version: STATE_ARCHIVE_VERSION,
+        exported_epoch_secs: now_epoch_secs(),
+        profile_root: local_profile_root(),
+        config_entry: config_entry.as_ref().map(|(name, _)| name.clone()),
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest)
+        .map_err(|error| StateArchiveError::Format(error.to_string()))?;
+
+    if let Some(parent) = archive_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut out = std::io::BufWriter::new(std::fs::File::create(archive_path)?);
+    write_tar_entry(&mut out, MANIFEST_ENTRY, &manifest)?;
+    write_tar_entry(&mut out, STATE_ENTRY, lines.as_bytes())?;
+    if let Some((name, bytes)) = &config_entry {
+        write_tar_entry(&mut out, name, bytes)?;
+    }
+    out.write_all(&[0_u8; TAR_BLOCK * 2])?;
+    out.flush()?;
+    Ok(summary)
+}
+
+// Explicit rewrites win over the automatic source-profile -> local-profile rule.
+pub fn import_state(
+    cfg: &Config,
+    db: &Connection,
+    archive_path: &Path,
+    rewrites: &[PathRewrite],
+) -> Result<ImportSummary, StateArchiveError> {
+    let entries = read_tar_entries(&std::fs::read(archive_path)?)?;
+    let entry = |name: &str| {
+        entries
+            .iter()
+            .find(|(entry_name, _)| entry_name == name)
+            .map(|(_, bytes)| bytes.as_slice())
+    };
+
+    let manifest: ArchiveManifest = serde_json::from_slice(
+        entry(MANIFEST_ENTRY)
+            .ok_or_else(|| StateArchiveError::Format("missing manifest".to_string()))?,
+    )
+    .map_err(|error| StateArchiveError::Format(format!("invalid manifest: {error}")))?;
+    if manifest.format != STATE_ARCHIVE_FORMAT {
+        return Err(StateArchiveError::Format(format!(
+            "unexpected archive format: {}",
+            manifest.format
+        )));
+    }
+    if manifest.version > STATE_ARCHIVE_VERSION {
+        return Err(StateArchiveError::Format(format!(
+            "archive version {} is newer than supported version {STATE_ARCHIVE_VERSION}",
+            manifest.version
+        )));
+    }
+
+    let mut rules = rewrites.to_vec();
+    if let (Some(source), Some(local)) = (&manifest.profile_root, local_profile_root()) {
+        if !source.eq_ignore_ascii_case(&local) {
+            rules.push(PathRewrite::new(source, &local));
+        }
+    }
+
+    let mut summary = ImportSummary::default();
+    let mut rewrite = |value: String| match rules.iter().find_map(|rule| rule.apply(&value)) {
+        Some(rewritten) => {
+            summary.rewritten_paths += 1;
+            rewritten
+        }
+        None => value,
+    };
+
+    let mut items = Vec::new();
+    let mut selections = Vec::new();
+    let mut clipboard = Vec::new();
+    let state = entry(STATE_ENTRY).unwrap_or_default();
+    for (line_no, line) in String::from_utf8_lossy(state).lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: StateRecord = serde_json::from_str(line).map_err(|error| {
+            StateArchiveError::Format(format!("invalid record on line {}: {error}", line_no + 1))
+        })?;
+        match record {
+            StateRecord::Item {
+                id,
+                kind,
+                title,
+                path,
+                subtitle,
+                use_count,
+                last_accessed_epoch_secs,
+            } => items.push(
+                SearchItem::new(&rewrite(id), &kind, &title, &rewrite(path))
+                    .with_subtitle(&rewrite(subtitle))
+                    .with_usage(use_count, last_accessed_epoch_secs),
+            ),
+            StateRecord::QuerySelection {
+                query_norm,
+                mode,
+                item_id,
+                selected_count,
+                last_selected_epoch_secs,
+            } => selections.push(QuerySelectionRecord {
+                query_norm,
+                mode,
+                item_id: rewrite(item_id),
+                selected_count,
+                last_selected_epoch_secs,
+            }),
+            StateRecord::Clipboard {
+                id,
+                text,
+                captured_epoch_secs,
+            } => clipboard.push(ClipboardEntry {
+                id,
+                text,
+                captured_epoch_secs,
+            }),
+        }
+    }
+
+    if let Some(config_entry) = manifest.config_entry.as_deref() {
+        match entry(config_entry) {
+            Some(bytes) => {
+                summary.config_imported = import_config(cfg, config_entry, bytes, &rules)?;
+                if !summary.config_imported {
+                    summary.warnings.push(format!(
+                        "skipped {config_entry}: local config uses a different format"
+                    ));
+                }
+            }
+            None => summary
+                .warnings
+                .push(format!("manifest lists missing entry {config_entry}")),
+        }
+    }
+
+    let mut writer = IndexBatchWriter::begin(db)?;
+    for mut item in items {
+        // Never lower local counters; the newer machine may already have its own history.
+        if let Some(existing) = index_store::get_item(writer.connection(), &item.id)? {
+            item = existing.clone().with_usage(
+                existing.use_count.max(item.use_count),
+                existing
+                    .last_accessed_epoch_secs
+                    .max(item.last_accessed_epoch_secs),
+            );
+        }
+        writer.upsert_item(&item)?;
+        summary.items += 1;
+    }
+    for record in &selections {
+        index_store::merge_query_selection(writer.connection(), record)?;
+        summary.query_selections += 1;
+    }
+    writer.commit()?;
+
+    if !clipboard.is_empty() {
+        summary.clipboard_entries =
+            clipboard_history::import_entries(cfg, clipboard).map_err(StateArchiveError::Format)?;
+    }
+    Ok(summary)
+}
+
+fn import_config(
+    cfg: &Config,
+    entry_name: &str,
+    bytes: &[u8],
+    rules: &[PathRewrite],
+) -> Result<bool, StateArchiveError> {
+    let extension = entry_name.trim_start_matches(CONFIG_ENTRY_PREFIX);
+    let local_extension = cfg
+        .config_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    if !extension.eq_ignore_ascii_case(local_extension) {
+        return Ok(false);
+    }
+
+    let mut imported = config::parse_text(&String::from_utf8_lossy(bytes))?;
+    imported.config_path = cfg.config_path.clone();
+    // Only path-valued fields are rewritten; free text like templates is left alone.
+    let rewrite_path = |path: &mut PathBuf| {
+        if let Some(rewritten) = rules
+            .iter()
+            .find_map(|rule| rule.apply(&path.to_string_lossy()))
+        {
+            *path = PathBuf::from(rewritten);
+        }
+    };
+    rewrite_path(&mut imported.index_db_path);
+    imported
+        .discovery_roots
+        .iter_mut()
+        .chain(imported.discovery_exclude_roots.iter_mut())
+        .chain(imported.browser_profile_paths.iter_mut())
+        .chain(imported.path_executables_extra_dirs.iter_mut())
+        .chain(imported.plugin_paths.iter_mut())
+        .for_each(rewrite_path);
+    config::validate(&imported).map_err(ConfigError::Validation)?;
+
+    let backup = cfg
+        .config_path
+        .with_extension(format!("{local_extension}.pre-import-{}", now_epoch_secs()));
+    let had_config = cfg.config_path.exists();
+    if had_config {
+        std::fs::copy(&cfg.config_path, &backup)?;
+    } else if let Some(parent) = cfg.config_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    config::save_to_path(&imported, &cfg.config_path)?;
+
+    if let Err(error) = config::load(Some(&cfg.config_path)) {
+        if had_config {
+            std::fs::copy(&backup, &cfg.config_path)?;
+            let _ = std::fs::remove_file(&backup);
+        } else {
+            let _ = std::fs::remove_file(&cfg.config_path);
+        }
+        return Err(error.into());
+    }
+    Ok(true)
+}
+
+fn push_record(lines: &mut String, record: &StateRecord) -> Result<(), StateArchiveError> {
+    let encoded = serde_json::to_string(record)
+        .map_err(|error| StateArchiveError::Format(error.to_string()))?;
+    lines.push_str(&encoded);
+    lines.push('\n');
+    Ok(())
+}
+
+// Plain ustar, no compression: readable with any `tar` and simple enough to avoid a dependency.
+fn write_tar_entry(out: &mut impl Write, name: &str, data: &[u8]) -> std::io::Result<()> {
+    let mut header = [0_u8; TAR_BLOCK];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+    header[136..148].copy_from_slice(format!("{:011o}\0", now_epoch_secs().max(0)).as_bytes());
+    header[148..156].copy_from_slice(b"        ");
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    let checksum: u32 = header.iter().map(|&byte| u32::from(byte)).sum();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+
+    out.write_all(&header)?;
+    out.write_all(data)?;
+    let padding = (TAR_BLOCK - data.len() % TAR_BLOCK) % TAR_BLOCK;
+    out.write_all(&vec![0_u8; padding])
+}
+
+fn read_tar_entries(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>, StateArchiveError> {
+    let truncated = || StateArchiveError::Format("truncated archive".to_string());
+    let mut entries = Vec::new();
+    let mut reader = bytes;
+    loop {
+        let mut header = [0_u8; TAR_BLOCK];
+        if reader.read_exact(&mut header).is_err() || header.iter().all(|&byte| byte == 0) {
+            break;
+        }
+        let name = tar_field(&header[..100]);
+        let size = usize::from_str_radix(tar_field(&header[124..136]).trim(), 8)
+            .map_err(|_| StateArchiveError::Format(format!("invalid size for entry {name}")))?;
+        let padded = size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+        let data = reader.get(..size).ok_or_else(truncated)?.to_vec();
+        reader = reader.get(padded..).ok_or_else(truncated)?;
+        if header[156] == b'0' || header[156] == 0 {
+            entries.push((name, data));
+        }
+    }
+    if entries.is_empty() {
+        return Err(StateArchiveError::Format(
+            "archive has no entries".to_string(),
+        ));
+    }
+    Ok(entries)
+}
+
+fn tar_field(field: &[u8]) -> String {
+    let end = field
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+// Offsets where a path can start: the value itself, or after an id prefix like `file:`.
+fn path_offsets(value: &str) -> Vec<usize> {
+    let mut offsets = vec![0];
+    if let Some(index) = value.find(':') {
+        let prefix = &value[..index];
+        if prefix.len() > 1
+            && prefix
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+        {
+            offsets.push(index + 1);
+        }
+    }
+    offsets
+}
+
+fn trim_separators(value: &str) -> &str {
+    value.trim_end_matches(['\\', '/'])
+}
+
+fn local_profile_root() -> Option<String> {
+    let key = if cfg!(target_os = "windows") {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    };
+    std::env::var(key)
+        .ok()
+        .map(|value| trim_separators(value.trim()).to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn now_epoch_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_tar_entries, write_tar_entry, PathRewrite, TAR_BLOCK};
+
+    #[test]
+    fn rewrites_profile_prefix_in_ids_and_paths() {
+        let rule = PathRewrite::parse("C:\\Users\\alice\\=D:\\Profiles\\bob").unwrap();
+        assert_eq!(
+            rule.apply("file:c:\\users\\alice\\notes.txt").as_deref(),
+            Some("file:d:\\profiles\\bob\\notes.txt")
+        );
+        assert_eq!(
+            rule.apply("C:\\Users\\alice\\notes.txt").as_deref(),
+            Some("D:\\Profiles\\bob\\notes.txt")
+        );
+        assert_eq!(rule.apply("C:\\Users\\alicia\\notes.txt"), None);
+        assert_eq!(PathRewrite::parse("=D:\\x"), None);
+    }
+
+    #[test]
+    fn tar_entries_round_trip() {
+        let mut out = Vec::new();
+        write_tar_entry(&mut out, "a.json", b"{}").unwrap();
+        write_tar_entry(&mut out, "b.jsonl", &[b'x'; 700]).unwrap();
+        out.extend_from_slice(&[0_u8; TAR_BLOCK * 2]);
+
+        let entries = read_tar_entries(&out).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], ("a.json".to_string(), b"{}".to_vec()));
+        assert_eq!(entries[1].1.len(), 700);
+    }
+}
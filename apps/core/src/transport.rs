@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::contract::{CoreRequest, CoreResponse};
 use crate::core_service::{CoreService, ServiceError};
+use crate::events::{CoreEvent, EventSubscription};
+use crate::jsonrpc;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -13,6 +15,7 @@ pub enum ErrorCode {
     Store,
     Config,
     Provider,
+    ReadOnly,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -51,7 +54,118 @@ pub fn handle_json(service: &CoreService, payload: &str) -> String {
     serde_json::to_string(&response).expect("transport response should serialize")
 }
 
-fn map_service_error(error: ServiceError) -> ErrorResponse {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFraming {
+    Envelope,
+    JsonRpc,
+}
+
+// Once a connection subscribes it stops taking requests and only receives event lines.
+pub struct EventStream {
+    subscription: EventSubscription,
+    framing: EventFraming,
+}
+
+impl EventStream {
+    pub fn new(service: &CoreService, events: Vec<String>, framing: EventFraming) -> Self {
+        Self {
+            subscription: EventSubscription::new(service.subscribe(), events),
+            framing,
+        }
+    }
+
+    // Blocks for the next matching event; `None` once the service is gone.
+    pub fn next_line(&self) -> Option<String> {
+        let event = self.subscription.next_event()?;
+        Some(match self.framing {
+            EventFraming::Envelope => encode_event(&event),
+            EventFraming::JsonRpc => jsonrpc::event_notification(&event),
+        })
+    }
+}
+
+pub struct LineReply {
+    pub response: Option<String>,
+    pub events: Option<EventStream>,
+}
+
+// Line-oriented variant used by the IPC server. Lines with a `jsonrpc` member (or
+// batches) are answered as JSON-RPC 2.0; otherwise the request object may carry an
+// `id` (any JSON value) that is echoed back on the envelope for correlation. Returns
+// `None` when the line needs no reply (JSON-RPC notifications).
+pub fn handle_json_line(service: &CoreService, line: &str) -> Option<String> {
+    handle_line(service, line).response
+}
+
+pub fn handle_line(service: &CoreService, line: &str) -> LineReply {
+    let mut value = match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(value) => value,
+        Err(error) if line.contains("\"jsonrpc\"") => {
+            return reply(Some(jsonrpc::parse_error_response(error.to_string())));
+        }
+        Err(error) => return reply(Some(encode_line(None, invalid_json(error.to_string())))),
+    };
+    if jsonrpc::is_jsonrpc_message(&value) {
+        return jsonrpc::handle_message(service, value);
+    }
+    let id = value
+        .as_object_mut()
+        .and_then(|object| object.remove("id"))
+        .filter(|id| !id.is_null());
+    let response = match serde_json::from_value::<CoreRequest>(value) {
+        Ok(request) => handle_request(service, request),
+        Err(error) => invalid_json(error.to_string()),
+    };
+    let events = match &response {
+        TransportResponse::Ok {
+            response: CoreResponse::Subscribe(subscribed),
+        } => Some(EventStream::new(
+            service,
+            subscribed.events.clone(),
+            EventFraming::Envelope,
+        )),
+        _ => None,
+    };
+    LineReply {
+        response: Some(encode_line(id, response)),
+        events,
+    }
+}
+
+// `{"status":"event","event":"<name>","data":{...}}`
+pub fn encode_event(event: &CoreEvent) -> String {
+    let mut encoded = serde_json::to_value(event).expect("core event should serialize");
+    if let Some(object) = encoded.as_object_mut() {
+        object.insert("status".to_string(), "event".into());
+    }
+    encoded.to_string()
+}
+
+pub(crate) fn reply(response: Option<String>) -> LineReply {
+    LineReply {
+        response,
+        events: None,
+    }
+}
+
+fn invalid_json(message: String) -> TransportResponse {
+    TransportResponse::Err {
+        error: ErrorResponse {
+            code: ErrorCode::InvalidJson,
+            message,
+        },
+    }
+}
+
+fn encode_line(id: Option<serde_json::Value>, response: TransportResponse) -> String {
+    let mut encoded = serde_json::to_value(&response).expect("transport response should serialize");
+    if let (Some(id), Some(object)) = (id, encoded.as_object_mut()) {
+        object.insert("id".to_string(), id);
+    }
+    encoded.to_string()
+}
+
+pub(crate) fn map_service_error(error: ServiceError) -> ErrorResponse {
     match error {
         ServiceError::InvalidRequest(message) => ErrorResponse {
             code: ErrorCode::InvalidRequest,
@@ -73,6 +187,10 @@ fn map_service_error(error: ServiceError) -> ErrorResponse {
             code: ErrorCode::Config,
             message,
         },
+        ServiceError::ReadOnly(message) => ErrorResponse {
+            code: ErrorCode::ReadOnly,
+            message,
+        },
         ServiceError::Provider(message) => ErrorResponse {
             code: ErrorCode::Provider,
             message: message.to_string(),
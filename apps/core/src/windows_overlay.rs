@@ -39,7 +39,7 @@ mod imp {
         ImageList_GetIcon, DRAWITEMSTRUCT, EM_SETSEL, MEASUREITEMSTRUCT, ODS_SELECTED,
     };
     use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
-        SetFocus, VK_BACK, VK_DOWN, VK_ESCAPE, VK_RETURN, VK_UP,
+        SetFocus, VK_BACK, VK_DOWN, VK_ESCAPE, VK_RETURN, VK_TAB, VK_UP,
     };
     use windows_sys::Win32::UI::Shell::{
         ExtractIconExW, FindExecutableW, HlinkResolveShortcutToString, SHGetFileInfoW,
@@ -147,6 +147,8 @@ mod imp {
     const NEX_WM_EXTERNAL_QUIT: u32 = WM_APP + 17;
     const NEX_WM_TRAY_ICON: u32 = WM_APP + 18;
     const NEX_WM_TRAY_TOGGLE_GAME_MODE: u32 = WM_APP + 19;
+    const NEX_WM_BACKGROUND_TASK_DONE: u32 = WM_APP + 20;
+    const NEX_WM_SHOW_ACTIONS: u32 = WM_APP + 21;
     const EM_GETRECT: u32 = 0x00B2;
     const EM_SETRECTNP: u32 = 0x00B4;
     const TRAY_ICON_ID: u32 = 1;
@@ -368,6 +370,8 @@ mod imp {
         Escape,
         ExternalShow,
         ExternalQuit,
+        BackgroundTaskDone,
+        ShowActions,
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
@@ -386,6 +390,8 @@ mod imp {
         pub title: String,
         pub path: String,
         pub icon_path: String,
+        // A plugin's own icon: a glyph, or an image file of which `.ico` can be drawn.
+        pub icon: String,
     }
 
     pub struct NativeOverlayShell {
@@ -846,7 +852,10 @@ mod imp {
                 let wide = to_wide(&resolved);
                 unsafe {
                     SetWindowTextW(state.mode_strip_hwnd, wide.as_ptr());
-                    ShowWindow(state.mode_strip_hwnd, SW_HIDE);
+                }
+                layout_children(self.hwnd, state);
+                unsafe {
+                    InvalidateRect(self.hwnd, std::ptr::null(), 1);
                 }
             }
         }
@@ -1122,6 +1131,8 @@ mod imp {
                     NEX_WM_ESCAPE => on_event(OverlayEvent::Escape),
                     NEX_WM_EXTERNAL_SHOW => on_event(OverlayEvent::ExternalShow),
                     NEX_WM_EXTERNAL_QUIT => on_event(OverlayEvent::ExternalQuit),
+                    NEX_WM_BACKGROUND_TASK_DONE => on_event(OverlayEvent::BackgroundTaskDone),
+                    NEX_WM_SHOW_ACTIONS => on_event(OverlayEvent::ShowActions),
                     _ => {}
                 }
 
@@ -1197,7 +1208,9 @@ mod imp {
         fn expand_results(&self, visible_row_count: usize) {
             let rows = visible_row_count.max(1) as i32;
             let animate = RESULTS_ANIM_MS;
-            let list_top = COMPACT_HEIGHT + INPUT_TO_LIST_GAP;
+            let strip_reserve =
+                state_for(self.hwnd).map_or(0, |state| mode_strip_reserve(state, true));
+            let list_top = COMPACT_HEIGHT + INPUT_TO_LIST_GAP + strip_reserve;
             // Keep enough vertical space for list rows plus bottom breathing room.
             // This must mirror layout_children() non-inline list bottom reserve.
             let list_bottom_reserve = PANEL_MARGIN_X + FOOTER_HINT_HEIGHT + 4;
@@ -1938,7 +1951,7 @@ mod imp {
             | NEX_WM_QUERY_CHANGED
             | NEX_WM_MOVE_UP
             | NEX_WM_MOVE_DOWN
-            | NEX_WM_SUBMIT => 0,
+            | NEX_WM_SUBMIT | NEX_WM_SHOW_ACTIONS => 0,
             _ => unsafe { DefWindowProcW(hwnd, message, wparam, lparam) },
         }
     }
@@ -2056,7 +2069,7 @@ mod imp {
                 // Suppress default control beep for handled launcher keys.
                 // Enter submits through WM_KEYDOWN -> NEX_WM_SUBMIT.
                 match wparam as u32 {
-                    10 | 13 | 27 => return 0, // '\n', '\r', ESC
+                    9 | 10 | 13 | 27 => return 0, // '\t', '\n', '\r', ESC
                     _ => {}
                 }
             }
@@ -2180,6 +2193,12 @@ mod imp {
                     }
                     return 0;
                 }
+                VK_TAB => {
+                    unsafe {
+                        PostMessageW(parent, NEX_WM_SHOW_ACTIONS, 0, 0);
+                    }
+                    return 0;
+                }
                 _ => {}
             }
         }
@@ -2514,6 +2533,7 @@ mod imp {
                 title: String::new(),
                 path: String::new(),
                 icon_path: String::new(),
+                icon: String::new(),
             });
 
         let content_progress = results_content_progress(state);
@@ -2649,12 +2669,16 @@ mod imp {
                     FillRect(dis.hDC, &icon_rect, state.icon_brush as _);
                     let icon_tint =
                         blend_color(palette.results_bg, palette.icon_text, content_progress);
-                    if !draw_action_icon(dis.hDC, &icon_rect, &row, state, icon_tint) {
+                    let plugin_glyph = plugin_icon_glyph(&row);
+                    if plugin_glyph.is_some()
+                        || !draw_action_icon(dis.hDC, &icon_rect, &row, state, icon_tint)
+                    {
                         let mut icon_text_rect = icon_rect;
                         SetTextColor(dis.hDC, icon_tint);
                         DrawTextW(
                             dis.hDC,
-                            to_wide(icon_glyph_for_row(&row)).as_ptr(),
+                            to_wide(plugin_glyph.unwrap_or_else(|| icon_glyph_for_row(&row)))
+                                .as_ptr(),
                             -1,
                             &mut icon_text_rect,
                             DT_CENTER | DT_SINGLELINE | DT_VCENTER,
@@ -3034,11 +3058,27 @@ mod imp {
             "C"
         } else if kind.eq_ignore_ascii_case("folder") {
             "D"
+        } else if kind.eq_ignore_ascii_case("bookmark") {
+            "B"
+        } else if kind.eq_ignore_ascii_case("snippet") {
+            "S"
+        } else if kind.eq_ignore_ascii_case("command") {
+            "$"
         } else {
             "F"
         }
     }
 
+    // Plugin icons that name an image file are loaded as icons instead.
+    fn plugin_icon_glyph(row: &OverlayRow) -> Option<&str> {
+        let icon = row.icon.trim();
+        if icon.is_empty() || Path::new(icon).extension().is_some() {
+            None
+        } else {
+            Some(icon)
+        }
+    }
+
     fn icon_glyph_for_row(row: &OverlayRow) -> &'static str {
         if !row.kind.eq_ignore_ascii_case("action") {
             return icon_glyph_for_kind(&row.kind);
@@ -3219,7 +3259,11 @@ mod imp {
 
     fn icon_cache_key(row: &OverlayRow) -> String {
         let kind = row.kind.to_ascii_lowercase();
-        let source = row.icon_path.trim().to_ascii_lowercase();
+        let source = if is_plugin_icon_file(row) {
+            row.icon.trim().to_ascii_lowercase()
+        } else {
+            row.icon_path.trim().to_ascii_lowercase()
+        };
         if source.is_empty() {
             format!("kind:{kind}")
         } else {
@@ -3227,7 +3271,20 @@ mod imp {
         }
     }
 
+    // GDI only draws icons, so rows with other image formats keep their usual icon.
+    fn is_plugin_icon_file(row: &OverlayRow) -> bool {
+        Path::new(row.icon.trim())
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("ico"))
+    }
+
     fn load_shell_icon_for_row(row: &OverlayRow) -> Option<isize> {
+        if is_plugin_icon_file(row) {
+            if let Some(icon) = extract_icon_from_path(row.icon.trim(), 0) {
+                return Some(icon);
+            }
+        }
         let kind = row.kind.to_ascii_lowercase();
         let source = row.icon_path.trim();
         let is_app_shortcut = kind == "app" && source.to_ascii_lowercase().ends_with(".lnk");
@@ -3958,6 +4015,15 @@ mod imp {
         schedule_icon_cache_idle_cleanup(hwnd);
     }
 
+    // The strip only appears for a plugin scope; the default mode list stays hidden.
+    fn mode_strip_reserve(state: &OverlayShellState, results_visible: bool) -> i32 {
+        if results_visible && state.mode_strip_text != MODE_STRIP_DEFAULT_TEXT {
+            MODE_STRIP_HEIGHT
+        } else {
+            0
+        }
+    }
+
     fn layout_children(hwnd: HWND, state: &mut OverlayShellState) {
         let mut rect: RECT = unsafe { std::mem::zeroed() };
         unsafe {
@@ -3985,7 +4051,7 @@ mod imp {
             && state.results_content_anim_start.is_none()
             && !footer_status_mode
             && !no_results_inline;
-        let mode_strip_visible = false;
+        let mode_strip_visible = mode_strip_reserve(state, state.results_visible) > 0;
         // Keep input exactly centered in compact mode and stable across states.
         let input_top = INPUT_TOP.max(0);
         let status_top = if footer_status_mode {
@@ -3998,7 +4064,8 @@ mod imp {
         let status_height = STATUS_HEIGHT;
 
         let mode_strip_top = COMPACT_HEIGHT + DIVIDER_TOP_SPACING + 1;
-        let list_top = COMPACT_HEIGHT + INPUT_TO_LIST_GAP;
+        let list_top =
+            COMPACT_HEIGHT + INPUT_TO_LIST_GAP + mode_strip_reserve(state, state.results_visible);
         let list_left = PANEL_MARGIN_X + 1;
         let list_width = (input_width - 2).max(0);
         let list_bottom_reserved = if footer_status_mode {
@@ -5511,12 +5578,29 @@ mod imp {
         }
         Ok(true)
     }
+
+    // Wakes the message loop from a worker thread so it picks up finished work.
+    pub fn signal_background_task_done() -> Result<bool, String> {
+        let hwnd = unsafe { FindWindowW(class_name_wide().as_ptr(), std::ptr::null()) };
+        if hwnd.is_null() {
+            return Ok(false);
+        }
+
+        let ok = unsafe { PostMessageW(hwnd, NEX_WM_BACKGROUND_TASK_DONE, 0, 0) };
+        if ok == 0 {
+            let error = unsafe { GetLastError() };
+            return Err(format!(
+                "PostMessageW(background) failed with error {error}"
+            ));
+        }
+        Ok(true)
+    }
 }
 
 #[cfg(target_os = "windows")]
 pub use imp::{
-    is_instance_window_present, signal_existing_instance_quit, signal_existing_instance_show,
-    NativeOverlayShell, OverlayEvent, OverlayRow, OverlayRowRole,
+    is_instance_window_present, signal_background_task_done, signal_existing_instance_quit,
+    signal_existing_instance_show, NativeOverlayShell, OverlayEvent, OverlayRow, OverlayRowRole,
 };
 
 #[cfg(not(target_os = "windows"))]
@@ -5533,3 +5617,8 @@ pub fn signal_existing_instance_show() -> Result<bool, String> {
 pub fn signal_existing_instance_quit() -> Result<bool, String> {
     Ok(false)
 }
+
+#[cfg(not(target_os = "windows"))]
+pub fn signal_background_task_done() -> Result<bool, String> {
+    Ok(false)
+}
@@ -2,17 +2,17 @@ use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use nex_core::action_executor::{launch_open_target, launch_path, LaunchError};
+use nex_core::action_executor::{
+    command_args_from_query, launch_command, launch_open_target, launch_path, run_command_capture,
+    LaunchError,
+};
 
 fn unique_temp_path(label: &str) -> PathBuf {
     let unique = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("clock should be after unix epoch")
         .as_nanos();
-    std::env::temp_dir().join(format!(
-        "nex-{label}-{}-{unique}.tmp",
-        std::process::id()
-    ))
+    std::env::temp_dir().join(format!("nex-{label}-{}-{unique}.tmp", std::process::id()))
 }
 
 #[test]
@@ -59,3 +59,46 @@ fn accepts_shell_open_target_via_launch_path() {
     let result = launch_path("shell:AppsFolder\\Microsoft.WindowsCalculator_8wekyb3d8bbwe!App");
     assert!(result.is_ok());
 }
+
+#[test]
+fn rejects_missing_command_path() {
+    let missing = unique_temp_path("missing-command");
+    let result = launch_command(&missing.to_string_lossy(), &[]);
+
+    assert_eq!(result, Err(LaunchError::MissingPath(missing)));
+}
+
+#[test]
+fn command_args_follow_command_name() {
+    assert_eq!(command_args_from_query("code .", "code"), vec!["."]);
+    assert_eq!(
+        command_args_from_query("Code --goto \"my file.rs\"", "code"),
+        vec!["--goto", "my file.rs"]
+    );
+    assert!(command_args_from_query("htop", "htop").is_empty());
+    assert!(command_args_from_query("cod .", "code").is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn captures_command_stdout() {
+    let args = vec!["-c".to_string(), "printf 'first\\nsecond\\n'".to_string()];
+    let output = run_command_capture("sh", &args, std::time::Duration::from_secs(5))
+        .expect("command should run");
+    assert_eq!(output, "first\nsecond");
+}
+
+#[cfg(unix)]
+#[test]
+fn capture_reports_failure_and_timeout() {
+    let failing = vec!["-c".to_string(), "exit 3".to_string()];
+    match run_command_capture("sh", &failing, std::time::Duration::from_secs(5)) {
+        Err(LaunchError::LaunchFailed { code, .. }) => assert_eq!(code, Some(3)),
+        other => panic!("expected launch failure, got {other:?}"),
+    }
+
+    let slow = vec!["-c".to_string(), "sleep 5".to_string()];
+    let error = run_command_capture("sh", &slow, std::time::Duration::from_millis(100))
+        .expect_err("slow command should time out");
+    assert!(error.to_string().contains("did not finish"));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::temp_root;
+use nex_core::client::CoreClient;
+use nex_core::config::{Config, SearchMode};
+use nex_core::core_service::CoreService;
+use nex_core::index_store;
+use nex_core::model::SearchItem;
+
+fn seeded_config(root: &std::path::Path) -> Config {
+    let cfg = Config {
+        index_db_path: root.join("index.sqlite3"),
+        ..Config::default()
+    };
+    let writer = CoreService::new(cfg.clone()).unwrap();
+    let notes = root.join("notes.txt");
+    std::fs::write(&notes, b"").unwrap();
+    writer
+        .upsert_item(&SearchItem::new(
+            "file:notes",
+            "file",
+            "notes.txt",
+            notes.to_str().unwrap(),
+        ))
+        .unwrap();
+    writer
+        .upsert_item(&SearchItem::new(
+            "app:gone",
+            "app",
+            "Notes Gone",
+            root.join("missing.exe").to_str().unwrap(),
+        ))
+        .unwrap();
+    cfg
+}
+
+#[test]
+fn falls_back_to_read_only_index_without_running_instance() {
+    let root = temp_root("client-local");
+    let cfg = seeded_config(&root);
+    let endpoint = root.join("absent.sock").to_string_lossy().into_owned();
+
+    let mut client = CoreClient::connect_or_open_at(cfg.clone(), &endpoint).unwrap();
+    assert!(!client.is_remote());
+
+    let files = client
+        .search("notes", Some(SearchMode::Files), Some(5))
+        .unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].id, "file:notes");
+
+    // Stale pruning is skipped, so the missing app entry is still on disk.
+    client.search("notes", None, Some(5)).unwrap();
+    let db = index_store::open_file_read_only(&cfg.index_db_path).unwrap();
+    assert!(index_store::get_item(&db, "app:gone").unwrap().is_some());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[cfg(unix)]
+#[test]
+fn uses_running_instance_when_endpoint_answers() {
+    let root = temp_root("client-remote");
+    let cfg = seeded_config(&root);
+    let endpoint = root.join("nex.sock").to_string_lossy().into_owned();
+    let server_cfg = cfg.clone();
+    let listener = nex_core::ipc::bind(&endpoint).unwrap();
+    nex_core::ipc::serve(listener, &endpoint, move || CoreService::new(server_cfg)).unwrap();
+
+    let mut client = CoreClient::connect_or_open_at(cfg, &endpoint).unwrap();
+    assert!(client.is_remote());
+    let files = client
+        .search("notes", Some(SearchMode::Files), Some(5))
+        .unwrap();
+    assert_eq!(files[0].id, "file:notes");
+
+    let error = client.open("zzzz-no-such-item").unwrap_err();
+    assert!(error.to_string().contains("no match"));
+
+    let _ = std::fs::remove_dir_all(root);
+}
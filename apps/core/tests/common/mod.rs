@@ -0,0 +1,34 @@
+// Helpers shared by the integration tests; each test crate uses only some of them.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use nex_core::core_service::CoreService;
+
+// A new, empty directory under the system temp dir.
+pub fn temp_root(label: &str) -> PathBuf {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-{label}-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+// An in-memory service holding one app, `seed` ("Code").
+pub fn service_with_seed_item() -> CoreService {
+    let config = nex_core::config::Config::default();
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(config, db).unwrap();
+    service
+        .upsert_item(&nex_core::model::SearchItem::new(
+            "seed",
+            "app",
+            "Code",
+            "C:\\Code.exe",
+        ))
+        .unwrap();
+    service
+}
@@ -1,6 +1,10 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use nex_core::contract::{CoreRequest, CoreResponse, LaunchRequest, SearchRequest};
+use nex_core::config::SearchMode;
+use nex_core::contract::{
+    CoreRequest, CoreResponse, ItemDto, ItemIdRequest, LaunchRequest, RebuildIndexRequest,
+    RecordSelectionRequest, SearchRequest, SubscribeRequest, UpsertItemRequest,
+};
 use nex_core::core_service::CoreService;
 
 #[test]
@@ -8,6 +12,7 @@ fn serializes_and_deserializes_search_request() {
     let request = CoreRequest::Search(SearchRequest {
         query: "code".to_string(),
         limit: Some(5),
+        mode: None,
     });
 
     let encoded = serde_json::to_string(&request).unwrap();
@@ -42,6 +47,7 @@ fn handles_search_command_and_serializes_response() {
         .handle_command(CoreRequest::Search(SearchRequest {
             query: "code".into(),
             limit: Some(5),
+            mode: None,
         }))
         .unwrap();
 
@@ -87,3 +93,108 @@ fn handles_launch_command_by_path() {
         CoreResponse::Launch(nex_core::contract::LaunchResponse { launched: true })
     );
 }
+
+#[test]
+fn search_results_carry_icon_and_secondary_actions_when_present() {
+    let plain = nex_core::contract::SearchResultDto::from(nex_core::model::SearchItem::new(
+        "app:code",
+        "app",
+        "Code",
+        "C:\\Code.exe",
+    ));
+    let encoded = serde_json::to_value(&plain).unwrap();
+    assert!(encoded.get("icon").is_none());
+    assert!(encoded.get("secondary_actions").is_none());
+
+    let item = nex_core::model::SearchItem::new("plugin:gh:action:repo", "action", "Repo", "")
+        .with_icon("\u{e8a7}")
+        .with_secondary_actions(vec![nex_core::model::ItemAction {
+            id: "plugin:gh:action:repo:secondary:copy".to_string(),
+            title: "Copy link".to_string(),
+            icon: String::new(),
+        }]);
+    let encoded = serde_json::to_value(nex_core::contract::SearchResultDto::from(item)).unwrap();
+    assert_eq!(encoded["icon"], "\u{e8a7}");
+    assert_eq!(
+        encoded["secondary_actions"],
+        serde_json::json!([{ "id": "plugin:gh:action:repo:secondary:copy", "title": "Copy link" }])
+    );
+}
+
+// Yields one request of every variant, each arm linking to the next. The match has no
+// wildcard arm, so a new variant does not compile until it is chained in here.
+fn next_request_sample(previous: Option<&CoreRequest>) -> Option<CoreRequest> {
+    let Some(previous) = previous else {
+        return Some(CoreRequest::Search(SearchRequest {
+            query: "code".to_string(),
+            limit: Some(5),
+            mode: None,
+        }));
+    };
+    match previous {
+        CoreRequest::Search(_) => Some(CoreRequest::Launch(LaunchRequest {
+            id: Some("item-1".to_string()),
+            path: None,
+        })),
+        CoreRequest::Launch(_) => Some(CoreRequest::Subscribe(SubscribeRequest {
+            events: vec!["item_launched".to_string()],
+        })),
+        CoreRequest::Subscribe(_) => Some(CoreRequest::RebuildIndex(RebuildIndexRequest {
+            incremental: true,
+        })),
+        CoreRequest::RebuildIndex(_) => Some(CoreRequest::GetItem(ItemIdRequest {
+            id: "item-1".to_string(),
+        })),
+        CoreRequest::GetItem(_) => Some(CoreRequest::UpsertItem(UpsertItemRequest {
+            item: ItemDto {
+                id: "item-1".to_string(),
+                kind: "file".to_string(),
+                title: "Notes".to_string(),
+                path: "C:\\Notes.txt".to_string(),
+                subtitle: String::new(),
+                use_count: 0,
+                last_accessed_epoch_secs: 0,
+            },
+        })),
+        CoreRequest::UpsertItem(_) => Some(CoreRequest::DeleteItem(ItemIdRequest {
+            id: "item-1".to_string(),
+        })),
+        CoreRequest::DeleteItem(_) => Some(CoreRequest::RecordSelection(RecordSelectionRequest {
+            query: "notes".to_string(),
+            mode: SearchMode::All,
+            id: "item-1".to_string(),
+        })),
+        CoreRequest::RecordSelection(_) => Some(CoreRequest::ListProviders),
+        CoreRequest::ListProviders => Some(CoreRequest::GetStatus),
+        CoreRequest::GetStatus => None,
+    }
+}
+
+#[test]
+fn request_kinds_list_every_variant_in_wire_form() {
+    let mut kinds = Vec::new();
+    let mut sample = next_request_sample(None);
+    while let Some(request) = sample {
+        let encoded = serde_json::to_value(&request).unwrap();
+        let kind = encoded["kind"].as_str().unwrap().to_string();
+        assert!(
+            CoreRequest::KINDS.contains(&kind.as_str()),
+            "CoreRequest::KINDS is missing {kind}"
+        );
+
+        // Rebuilt the way the JSON-RPC bridge does for a method named after a `KINDS` entry;
+        // methods without params send `null`.
+        let payload = encoded
+            .get("payload")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        let decoded: CoreRequest =
+            serde_json::from_value(serde_json::json!({ "kind": kind, "payload": payload }))
+                .unwrap_or_else(|error| panic!("{kind} does not decode: {error}"));
+        assert_eq!(decoded, request);
+
+        kinds.push(kind);
+        sample = next_request_sample(Some(&request));
+    }
+    assert_eq!(kinds, CoreRequest::KINDS);
+}
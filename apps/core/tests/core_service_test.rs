@@ -5,6 +5,7 @@ use nex_core::config::SearchMode;
 use nex_core::core_service::{CoreService, LaunchTarget, ServiceError};
 use nex_core::discovery::{DiscoveryProvider, ProviderError};
 use nex_core::model::SearchItem;
+use nex_core::search::SearchFilter;
 
 fn test_config() -> nex_core::config::Config {
     nex_core::config::Config::default()
@@ -559,3 +560,390 @@ fn incremental_rebuild_skips_unchanged_provider_with_stamp() {
 
     std::fs::remove_file(stable_path).unwrap();
 }
+
+#[test]
+fn content_index_matches_file_bodies_with_snippet() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-content-index-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    let notes = root.join("runbook.md");
+    let script = root.join("deploy.sh");
+    let binary = root.join("blob.txt");
+    std::fs::write(
+        &notes,
+        "# Runbook\n\nRoll back with `make rollback` when health fails.\n",
+    )
+    .unwrap();
+    std::fs::write(&script, "#!/bin/sh\necho deploying\n").unwrap();
+    std::fs::write(&binary, b"rollback\0\x01\x02").unwrap();
+
+    let items = Arc::new(Mutex::new(vec![
+        SearchItem::new("notes", "file", "runbook.md", &notes.to_string_lossy()),
+        SearchItem::new("script", "file", "deploy.sh", &script.to_string_lossy()),
+        SearchItem::new("binary", "file", "blob.txt", &binary.to_string_lossy()),
+    ]));
+    let config = nex_core::config::Config {
+        content_indexing_enabled: true,
+        ..test_config()
+    };
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(config, db)
+        .unwrap()
+        .with_providers(vec![Box::new(MutableProvider::new(
+            "filesystem",
+            items.clone(),
+        ))]);
+    service.rebuild_index_with_report().unwrap();
+
+    let filter = SearchFilter {
+        content_query: Some("rollback health".to_string()),
+        ..SearchFilter::default()
+    };
+    let results = service.search_with_filter("", 10, &filter).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, "notes");
+    assert_eq!(
+        results[0].snippet,
+        "Roll back with make rollback when health fails."
+    );
+
+    items.lock().unwrap().retain(|item| item.id != "notes");
+    service.rebuild_index_with_report().unwrap();
+    assert!(service
+        .search_with_filter("", 10, &filter)
+        .unwrap()
+        .is_empty());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+struct FlakyProvider {
+    failing: Arc<Mutex<bool>>,
+    items: Vec<SearchItem>,
+}
+
+impl DiscoveryProvider for FlakyProvider {
+    fn provider_name(&self) -> &'static str {
+        "flaky"
+    }
+
+    fn discover(&self) -> Result<Vec<SearchItem>, ProviderError> {
+        if *self.failing.lock().expect("provider lock poisoned") {
+            return Err(ProviderError::new("source offline"));
+        }
+        Ok(self.items.clone())
+    }
+}
+
+#[test]
+fn failing_provider_is_tracked_and_backed_off() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("nex-flaky-{unique}.tmp"));
+    std::fs::write(&path, b"ok").unwrap();
+
+    let failing = Arc::new(Mutex::new(false));
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(test_config(), db)
+        .unwrap()
+        .with_providers(vec![Box::new(FlakyProvider {
+            failing: failing.clone(),
+            items: vec![SearchItem::new(
+                "flaky-item",
+                "file",
+                "Flaky Notes",
+                &path.to_string_lossy(),
+            )],
+        })]);
+
+    service.rebuild_index_with_report().unwrap();
+    assert!(service.degraded_providers().unwrap().is_empty());
+
+    *failing.lock().unwrap() = true;
+    let report = service.rebuild_index_with_report().unwrap();
+    assert_eq!(report.providers[0].error.as_deref(), Some("source offline"));
+    assert_eq!(service.search("flaky", 10).unwrap().len(), 1);
+
+    let health = service.provider_health().unwrap();
+    assert_eq!(health.len(), 1);
+    assert_eq!(health[0].provider, "flaky");
+    assert_eq!(health[0].consecutive_failures, 1);
+    assert_eq!(health[0].last_error, "source offline");
+    assert!(health[0].last_success_epoch_secs > 0);
+    assert!(health[0].backoff_until_epoch_secs > health[0].last_error_epoch_secs);
+    assert_eq!(service.degraded_providers().unwrap(), vec!["flaky"]);
+
+    *failing.lock().unwrap() = false;
+    let report = service.rebuild_index_incremental_with_report().unwrap();
+    assert!(report.providers[0].skipped);
+    assert_eq!(service.degraded_providers().unwrap(), vec!["flaky"]);
+
+    let report = service.rebuild_index_with_report().unwrap();
+    assert!(!report.providers[0].skipped);
+    assert!(service.degraded_providers().unwrap().is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn event_bus_reports_refreshes_provider_failures_and_config_reloads() {
+    use nex_core::events::CoreEvent;
+
+    let db = nex_core::index_store::open_memory().unwrap();
+    let failing = Arc::new(Mutex::new(true));
+    let service = CoreService::with_connection(test_config(), db)
+        .unwrap()
+        .with_providers(vec![Box::new(FlakyProvider {
+            failing: failing.clone(),
+            items: Vec::new(),
+        })]);
+    let events = service.subscribe();
+    // A worker's own service shares subscribers through the cloned bus.
+    let worker =
+        CoreService::with_connection(test_config(), nex_core::index_store::open_memory().unwrap())
+            .unwrap()
+            .with_event_bus(service.events().clone());
+
+    let report = service.rebuild_index_with_report().unwrap();
+    worker.apply_config(&test_config()).unwrap();
+
+    let received: Vec<CoreEvent> = events.try_iter().collect();
+    assert_eq!(
+        received[0],
+        CoreEvent::IndexRefreshStarted { incremental: false }
+    );
+    assert_eq!(
+        received[1],
+        CoreEvent::ProviderFailed {
+            provider: "flaky".to_string(),
+            error: "source offline".to_string(),
+        }
+    );
+    assert_eq!(received[2], CoreEvent::IndexRefreshFinished(report));
+    assert!(matches!(received[3], CoreEvent::ConfigReloaded { .. }));
+    assert_eq!(received.len(), 4);
+
+    drop(events);
+    service.rebuild_index_with_report().unwrap();
+    assert_eq!(service.events().subscriber_count(), 0);
+}
+
+#[test]
+fn plugin_items_are_indexed_and_owned_by_their_plugin_provider() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-plugin-provider-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    let target = root.join("report.txt");
+    std::fs::write(&target, b"r").unwrap();
+    let manifest_path = root.join("reports.json");
+    let manifest = serde_json::json!({
+        "id": "reports",
+        "provider_items": [
+            { "id": "weekly", "kind": "file", "title": "Weekly Report", "path": target.to_string_lossy() }
+        ]
+    });
+    std::fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+    let plugin_provider =
+        nex_core::plugin_sdk::PluginItemsProvider::new("reports", manifest_path.clone());
+    assert!(plugin_provider.change_stamp().is_some());
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(test_config(), db)
+        .unwrap()
+        .with_providers(vec![
+            Box::new(MutableProvider::new(
+                "filesystem",
+                Arc::new(Mutex::new(Vec::new())),
+            )),
+            Box::new(plugin_provider),
+        ]);
+
+    // The filesystem provider owns kind=file but must not prune plugin files.
+    let report = service.rebuild_index_with_report().unwrap();
+    assert_eq!(report.providers[1].provider, "plugin:reports");
+    assert_eq!(report.providers[1].discovered, 1);
+    let results = service.search("weekly", 5).unwrap();
+    assert_eq!(results[0].id, "plugin:reports:item:weekly");
+
+    std::fs::write(
+        &manifest_path,
+        serde_json::json!({ "id": "reports", "provider_items": [] }).to_string(),
+    )
+    .unwrap();
+    let report = service.rebuild_index_with_report().unwrap();
+    assert_eq!(report.providers[1].removed, 1);
+    assert!(service.search("weekly", 5).unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn items_of_unregistered_plugins_are_pruned_on_refresh() {
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(test_config(), db)
+        .unwrap()
+        .with_providers(vec![Box::new(MutableProvider::new(
+            "filesystem",
+            Arc::new(Mutex::new(Vec::new())),
+        ))]);
+    service
+        .upsert_item(&SearchItem::new(
+            "plugin:gone:item:one",
+            "app",
+            "Gone Plugin Item",
+            "C:\\Gone.exe",
+        ))
+        .unwrap();
+
+    let report = service.rebuild_index_with_report().unwrap();
+    assert_eq!(report.removed_total, 1);
+    assert_eq!(report.indexed_total, 0);
+}
+
+#[test]
+fn items_of_plugins_with_broken_manifests_are_kept() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-plugin-broken-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    let manifest_path = root.join("reports.json");
+    let config = nex_core::config::Config {
+        plugin_paths: vec![root.clone()],
+        ..test_config()
+    };
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(config.clone(), db)
+        .unwrap()
+        .with_providers(vec![Box::new(MutableProvider::new(
+            "filesystem",
+            Arc::new(Mutex::new(Vec::new())),
+        ))]);
+    service
+        .upsert_item(&SearchItem::new(
+            "plugin:reports:item:weekly",
+            "file",
+            "Weekly Report",
+            "C:\\Weekly.txt",
+        ))
+        .unwrap();
+
+    // Invalid, but still naming its id: the plugin gets a provider that reports the error.
+    std::fs::write(&manifest_path, r#"{"id": "reports", "unknown": 1}"#).unwrap();
+    let providers = nex_core::plugin_sdk::item_providers_from_config(&config);
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0].provider_name(), "plugin:reports");
+    assert!(providers[0].discover().is_err());
+    let report = service.rebuild_index_with_report().unwrap();
+    assert_eq!(report.removed_total, 0);
+
+    // Half-written, so it could be any plugin.
+    std::fs::write(&manifest_path, r#"{"id": "rep"#).unwrap();
+    assert!(nex_core::plugin_sdk::item_providers_from_config(&config).is_empty());
+    let report = service.rebuild_index_with_report().unwrap();
+    assert_eq!(report.removed_total, 0);
+
+    std::fs::remove_file(&manifest_path).unwrap();
+    let report = service.rebuild_index_with_report().unwrap();
+    assert_eq!(report.removed_total, 1);
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn service_requests_present_plugin_items_and_route_secondary_actions() {
+    use nex_core::contract::{CoreRequest, CoreResponse, LaunchRequest, SearchRequest};
+
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-plugin-secondary-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    let target = root.join("report.txt");
+    std::fs::write(&target, b"r").unwrap();
+    let manifest_path = root.join("reports.json");
+    let manifest = serde_json::json!({
+        "id": "reports",
+        "capabilities": ["run_commands"],
+        "provider_items": [{
+            "id": "weekly",
+            "kind": "file",
+            "title": "Weekly Report",
+            "path": target.to_string_lossy(),
+            "icon": "\u{2605}",
+            "secondary_actions": [
+                { "id": "mail", "title": "Mail report", "type": "command", "command": "mail-report" },
+                { "id": "preview", "title": "Preview", "type": "command", "command": "preview", "output": "result" }
+            ]
+        }]
+    });
+    std::fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+    let cfg = nex_core::config::Config {
+        plugins_enabled: true,
+        plugins_safe_mode: false,
+        plugin_paths: vec![root.clone()],
+        ..test_config()
+    };
+    let plugins = nex_core::plugin_sdk::PluginRegistry::load_from_config(&cfg);
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(cfg, db)
+        .unwrap()
+        .with_providers(vec![Box::new(
+            nex_core::plugin_sdk::PluginItemsProvider::new("reports", manifest_path),
+        )])
+        .with_plugins(plugins);
+    service.rebuild_index_with_report().unwrap();
+
+    let response = service
+        .handle_command(CoreRequest::Search(SearchRequest {
+            query: "weekly".to_string(),
+            limit: Some(5),
+            mode: None,
+        }))
+        .unwrap();
+    let CoreResponse::Search(response) = response else {
+        panic!("unexpected response: {response:?}");
+    };
+    assert_eq!(response.results[0].icon, "\u{2605}");
+    let action_ids: Vec<&str> = response.results[0]
+        .secondary_actions
+        .iter()
+        .map(|action| action.id.as_str())
+        .collect();
+    assert_eq!(
+        action_ids,
+        [
+            "plugin:reports:item:weekly:secondary:mail",
+            "plugin:reports:item:weekly:secondary:preview"
+        ]
+    );
+
+    // Secondary ids are not indexed; launching one runs the plugin action, which still
+    // needs its capability granted.
+    let launch = |id: &str| {
+        service.handle_command(CoreRequest::Launch(LaunchRequest {
+            id: Some(id.to_string()),
+            path: None,
+        }))
+    };
+    let error = launch(action_ids[0]).unwrap_err();
+    assert!(
+        matches!(&error, ServiceError::Launch(_)) && error.to_string().contains("plugin_grants"),
+        "{error}"
+    );
+    let error = launch(action_ids[1]).unwrap_err();
+    assert!(matches!(error, ServiceError::InvalidRequest(_)), "{error}");
+
+    let _ = std::fs::remove_dir_all(root);
+}
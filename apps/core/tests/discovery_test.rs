@@ -4,7 +4,9 @@ use nex_core::core_service::CoreService;
 #[cfg(not(target_os = "windows"))]
 use nex_core::discovery::StartMenuAppDiscoveryProvider;
 use nex_core::discovery::{
-    AppProvider, DiscoveryProvider, FileProvider, FileSystemDiscoveryProvider,
+    AppProvider, BrowserBookmarksDiscoveryProvider, DiscoveryProvider, FileProvider,
+    FileSystemDiscoveryProvider, PathExecutablesDiscoveryProvider,
+    RecentDocumentsDiscoveryProvider,
 };
 
 #[test]
@@ -315,3 +317,317 @@ fn runtime_provider_reconfigure_applies_new_roots() {
     std::fs::remove_dir_all(&root_a).unwrap();
     std::fs::remove_dir_all(&root_b).unwrap();
 }
+
+#[test]
+fn browser_bookmarks_provider_reads_chromium_profiles_under_data_root() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let user_data = std::env::temp_dir().join(format!("nex-chromium-bookmarks-{unique}"));
+    let profile = user_data.join("Default");
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(
+        profile.join("Bookmarks"),
+        r#"{
+  "roots": {
+    "bookmark_bar": {
+      "name": "Bookmarks bar",
+      "type": "folder",
+      "children": [
+        {
+          "name": "Dashboards",
+          "type": "folder",
+          "children": [
+            { "name": "Ops Grafana", "type": "url", "url": "https://grafana.internal/d/ops" },
+            { "name": "Bookmarklet", "type": "url", "url": "javascript:alert(1)" }
+          ]
+        }
+      ]
+    },
+    "other": { "name": "Other bookmarks", "type": "folder", "children": [] }
+  }
+}"#,
+    )
+    .unwrap();
+
+    let provider = BrowserBookmarksDiscoveryProvider::new(vec![user_data.clone()]);
+    let items = provider.discover().unwrap();
+
+    assert_eq!(provider.provider_name(), "browser-bookmarks");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, "bookmark:https://grafana.internal/d/ops");
+    assert_eq!(items[0].kind, "bookmark");
+    assert_eq!(items[0].title, "Ops Grafana");
+    assert_eq!(items[0].path, "https://grafana.internal/d/ops");
+    assert_eq!(items[0].subtitle, "Bookmarks bar/Dashboards");
+    assert!(provider.change_stamp().unwrap().contains("bookmarks"));
+
+    std::fs::remove_dir_all(&user_data).unwrap();
+}
+
+#[test]
+fn browser_bookmarks_provider_reads_firefox_places_copy() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let profile = std::env::temp_dir().join(format!("nex-firefox-bookmarks-{unique}"));
+    std::fs::create_dir_all(&profile).unwrap();
+    let places = profile.join("places.sqlite");
+    {
+        let conn = rusqlite::Connection::open(&places).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+             CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, parent INTEGER, title TEXT);
+             INSERT INTO moz_places (id, url, title) VALUES (1, 'https://ci.internal/builds', 'CI');
+             INSERT INTO moz_places (id, url, title) VALUES (2, 'place:sort=8', NULL);
+             INSERT INTO moz_bookmarks (id, type, fk, parent, title) VALUES (1, 2, NULL, 0, 'toolbar');
+             INSERT INTO moz_bookmarks (id, type, fk, parent, title) VALUES (2, 1, 1, 1, 'Build Status');
+             INSERT INTO moz_bookmarks (id, type, fk, parent, title) VALUES (3, 1, 2, 1, 'Recent Tags');",
+        )
+        .unwrap();
+    }
+
+    let provider = BrowserBookmarksDiscoveryProvider::new(vec![profile.clone()]);
+    let items = provider.discover().unwrap();
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, "bookmark:https://ci.internal/builds");
+    assert_eq!(items[0].title, "Build Status");
+    assert_eq!(items[0].subtitle, "toolbar");
+
+    std::fs::remove_dir_all(&profile).unwrap();
+}
+
+#[test]
+fn runtime_providers_index_browser_bookmarks_and_prune_when_disabled() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let profile = std::env::temp_dir().join(format!("nex-runtime-bookmarks-{unique}"));
+    std::fs::create_dir_all(&profile).unwrap();
+    std::fs::write(
+        profile.join("Bookmarks"),
+        r#"{"roots":{"bookmark_bar":{"name":"Bookmarks bar","type":"folder","children":[
+            {"name":"Release Tracker","type":"url","url":"https://tracker.internal/releases"}
+        ]}}}"#,
+    )
+    .unwrap();
+
+    let mut config = nex_core::config::Config {
+        discovery_roots: vec![],
+        browser_profile_paths: vec![profile.clone()],
+        ..nex_core::config::Config::default()
+    };
+
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(config.clone(), db)
+        .unwrap()
+        .with_runtime_providers();
+
+    let _ = service.rebuild_index().unwrap();
+    let before = service.search("release tracker", 10).unwrap();
+    assert!(before.iter().any(|item| item.kind == "bookmark"));
+
+    config.browser_bookmarks_enabled = false;
+    service.reconfigure_runtime_providers(&config).unwrap();
+    let _ = service.rebuild_index().unwrap();
+    let after = service.search("release tracker", 10).unwrap();
+    assert!(after.is_empty());
+
+    std::fs::remove_dir_all(&profile).unwrap();
+}
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn recent_documents_provider_reads_xbel_with_real_access_times() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-recent-docs-{unique}"));
+    let excluded = root.join("private");
+    std::fs::create_dir_all(&excluded).unwrap();
+    let notes = root.join("Meeting Notes.md");
+    let secret = excluded.join("secret.txt");
+    std::fs::write(&notes, b"notes").unwrap();
+    std::fs::write(&secret, b"secret").unwrap();
+    let missing = root.join("deleted.txt");
+
+    let xbel = root.join("recently-used.xbel");
+    std::fs::write(
+        &xbel,
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0" xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks">
+  <bookmark href="file://{}" added="2024-01-01T09:00:00Z" modified="2024-01-01T09:00:00Z" visited="2024-01-02T03:04:05.123456Z">
+    <info><metadata owner="http://freedesktop.org"><bookmark:applications></bookmark:applications></metadata></info>
+  </bookmark>
+  <bookmark href="file://{}" added="2024-01-03T00:00:00Z" modified="2024-01-03T00:00:00Z" visited="2024-01-03T00:00:00Z"/>
+  <bookmark href="file://{}" added="2024-01-04T00:00:00Z" modified="2024-01-04T00:00:00Z" visited="2024-01-04T00:00:00Z"/>
+  <bookmark href="https://example.com/page" added="2024-01-05T00:00:00Z" modified="2024-01-05T00:00:00Z" visited="2024-01-05T00:00:00Z"/>
+</xbel>
+"#,
+            notes.to_string_lossy().replace(' ', "%20"),
+            secret.to_string_lossy(),
+            missing.to_string_lossy(),
+        ),
+    )
+    .unwrap();
+
+    let provider = RecentDocumentsDiscoveryProvider::with_sources(vec![xbel.clone()])
+        .with_excluded_roots(vec![excluded.clone()]);
+    let items = provider.discover().unwrap();
+
+    assert_eq!(provider.provider_name(), "recent-documents");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].kind, "file");
+    assert_eq!(items[0].title, "Meeting Notes.md");
+    assert_eq!(items[0].path, notes.to_string_lossy());
+    assert_eq!(items[0].last_accessed_epoch_secs, 1_704_164_645);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn filesystem_rebuild_keeps_recent_document_entries() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-recent-ownership-{unique}"));
+    let outside = std::env::temp_dir().join(format!("nex-recent-outside-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::create_dir_all(&outside).unwrap();
+    let touched = outside.join("TouchedYesterday.txt");
+    std::fs::write(&touched, b"recent").unwrap();
+
+    let xbel = outside.join("recently-used.xbel");
+    std::fs::write(
+        &xbel,
+        format!(
+            r#"<xbel version="1.0"><bookmark href="file://{}" visited="2024-06-01T12:00:00Z"/></xbel>"#,
+            touched.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    let db = nex_core::index_store::open_memory().unwrap();
+    let service = CoreService::with_connection(nex_core::config::Config::default(), db)
+        .unwrap()
+        .with_providers(vec![
+            Box::new(FileSystemDiscoveryProvider::new(
+                vec![root.clone()],
+                4,
+                vec![],
+            )),
+            Box::new(RecentDocumentsDiscoveryProvider::with_sources(vec![xbel])),
+        ]);
+
+    let _ = service.rebuild_index().unwrap();
+    let _ = service.rebuild_index_with_report().unwrap();
+    let results = service.search("touchedyesterday", 10).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].id.starts_with("recent:"));
+    assert!(results[0].last_accessed_epoch_secs > 0);
+
+    std::fs::remove_dir_all(&root).unwrap();
+    std::fs::remove_dir_all(&outside).unwrap();
+}
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn recent_documents_already_indexed_only_add_recency() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-recent-merge-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    let touched = root.join("TouchedToday.txt");
+    std::fs::write(&touched, b"recent").unwrap();
+    let xbel = std::env::temp_dir().join(format!("nex-recent-merge-{unique}.xbel"));
+    std::fs::write(
+        &xbel,
+        format!(
+            r#"<xbel version="1.0"><bookmark href="file://{}" visited="2024-06-01T12:00:00Z"/></xbel>"#,
+            touched.to_string_lossy()
+        ),
+    )
+    .unwrap();
+
+    // Either provider may refresh first.
+    for recent_first in [false, true] {
+        let filesystem: Box<dyn DiscoveryProvider> = Box::new(FileSystemDiscoveryProvider::new(
+            vec![root.clone()],
+            4,
+            vec![],
+        ));
+        let recent: Box<dyn DiscoveryProvider> =
+            Box::new(RecentDocumentsDiscoveryProvider::with_sources(vec![
+                xbel.clone()
+            ]));
+        let providers = if recent_first {
+            vec![recent, filesystem]
+        } else {
+            vec![filesystem, recent]
+        };
+        let db = nex_core::index_store::open_memory().unwrap();
+        let service = CoreService::with_connection(nex_core::config::Config::default(), db)
+            .unwrap()
+            .with_providers(providers);
+
+        let _ = service.rebuild_index().unwrap();
+        let results = service.search("touchedtoday", 10).unwrap();
+        assert_eq!(results.len(), 1, "recent_first={recent_first}");
+        assert_eq!(results[0].id, format!("file:{}", touched.to_string_lossy()));
+        assert!(results[0].last_accessed_epoch_secs > 0);
+    }
+
+    std::fs::remove_dir_all(&root).unwrap();
+    std::fs::remove_file(&xbel).unwrap();
+}
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn path_executables_provider_dedupes_shadowed_names() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-path-exec-{unique}"));
+    let first = root.join("first");
+    let second = root.join("second");
+    std::fs::create_dir_all(&first).unwrap();
+    std::fs::create_dir_all(&second).unwrap();
+
+    let write_exec = |path: &std::path::Path, mode: u32| {
+        std::fs::write(path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+    };
+    write_exec(&first.join("htop"), 0o755);
+    write_exec(&first.join("notes.txt"), 0o644);
+    write_exec(&second.join("htop"), 0o755);
+    write_exec(&second.join("code"), 0o755);
+
+    let provider = PathExecutablesDiscoveryProvider::with_dirs(vec![
+        first.clone(),
+        second.clone(),
+        first.clone(),
+    ]);
+    let items = provider.discover().unwrap();
+
+    assert_eq!(provider.provider_name(), "path-executables");
+    let ids: Vec<&str> = items.iter().map(|item| item.id.as_str()).collect();
+    assert_eq!(ids, vec!["command:htop", "command:code"]);
+    assert!(items.iter().all(|item| item.kind == "command"));
+    assert_eq!(items[0].path, first.join("htop").to_string_lossy());
+    assert_eq!(items[1].title, "code");
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,14 @@
+#!/bin/sh
+# Stdio plugin fixture: answers every query with one result echoing the query text.
+# "crash" exits without answering and "slow" answers late.
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9][0-9]*\).*/\1/p')
+  query=$(printf '%s' "$line" | sed -n 's/.*"query":"\([^"]*\)".*/\1/p')
+  case "$query" in
+    crash) exit 3 ;;
+    slow) sleep 2 ;;
+  esac
+  printf 'not a protocol line\n'
+  printf '{"id":%s,"results":[{"id":"echo","title":"Echo %s","subtitle":"pid %s","command":"true","args":["%s"]}]}\n' \
+    "$id" "$query" "$$" "$query"
+done
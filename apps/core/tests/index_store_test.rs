@@ -89,3 +89,217 @@ fn stores_and_reads_query_selection_memory() {
     assert_eq!(rows[0].1, 2);
     assert_eq!(rows[0].2, 200);
 }
+
+#[test]
+fn file_store_uses_wal_journal() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-wal-test-{unique}"));
+    let db = nex_core::index_store::open_file(&root.join("index.sqlite3")).unwrap();
+
+    let mode: String = db
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(mode, "wal");
+
+    drop(db);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn corrupt_file_is_quarantined_and_recreated() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-corrupt-test-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    let db_path = root.join("index.sqlite3");
+    std::fs::write(&db_path, vec![0x5A_u8; 8192]).unwrap();
+
+    let (db, report) = nex_core::index_store::open_file_checked(&db_path).unwrap();
+    let report = report.expect("corrupt file should be reported");
+    assert!(!report.problems.is_empty());
+    let quarantined = report.quarantined_path.clone().unwrap();
+    assert!(std::path::Path::new(&quarantined).exists());
+    assert!(nex_core::index_store::list_items(&db).unwrap().is_empty());
+    assert_eq!(
+        nex_core::index_store::last_maintenance(&db).unwrap(),
+        Some(report)
+    );
+
+    drop(db);
+    let (_db, report) = nex_core::index_store::open_file_checked(&db_path).unwrap();
+    assert!(report.is_none());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn repair_compacts_healthy_file_and_honors_scheduled_repair() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-repair-test-{unique}"));
+    let db_path = root.join("index.sqlite3");
+    {
+        let db = nex_core::index_store::open_file(&db_path).unwrap();
+        let item = nex_core::model::SearchItem::new("1", "file", "Notes", "C:\\notes.txt");
+        nex_core::index_store::upsert_item(&db, &item).unwrap();
+    }
+
+    let report = nex_core::index_store::repair_file(&db_path).unwrap();
+    assert!(report.problems.is_empty());
+    assert!(report.vacuumed);
+    assert!(!report.quarantined());
+
+    nex_core::index_store::schedule_repair(&db_path).unwrap();
+    let (db, report) = nex_core::index_store::open_file_checked(&db_path).unwrap();
+    assert!(report.unwrap().quarantined());
+    assert!(nex_core::index_store::list_items(&db).unwrap().is_empty());
+
+    drop(db);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+fn table_exists(db: &rusqlite::Connection, name: &str) -> bool {
+    db.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE name = ?1",
+        [name],
+        |row| row.get::<_, i64>(0),
+    )
+    .unwrap()
+        > 0
+}
+
+fn item_has_subtitle(db: &rusqlite::Connection) -> bool {
+    let mut stmt = db.prepare("PRAGMA table_info(item)").unwrap();
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    columns.iter().any(|name| name == "subtitle")
+}
+
+fn assert_schema_shape(db: &rusqlite::Connection, version: i64) {
+    assert_eq!(table_exists(db, "item"), version >= 1, "item at v{version}");
+    assert_eq!(
+        table_exists(db, "index_meta"),
+        version >= 2,
+        "meta at v{version}"
+    );
+    assert_eq!(
+        table_exists(db, "item_query_memory"),
+        version >= 3,
+        "query memory at v{version}"
+    );
+    if version >= 1 {
+        assert_eq!(
+            item_has_subtitle(db),
+            version >= 4,
+            "subtitle at v{version}"
+        );
+    }
+    assert_eq!(
+        table_exists(db, "item_content") && table_exists(db, "item_content_state"),
+        version >= 5,
+        "content tables at v{version}"
+    );
+}
+
+#[test]
+fn every_schema_version_upgrades_to_current() {
+    use nex_core::index_store::{migrate_schema, schema_version, SCHEMA_VERSION};
+
+    for start in 0..SCHEMA_VERSION {
+        let db = nex_core::index_store::open_memory().unwrap();
+        migrate_schema(&db, start).unwrap();
+        assert_eq!(schema_version(&db).unwrap(), start);
+        assert_schema_shape(&db, start);
+        if start >= 1 {
+            db.execute(
+                "INSERT INTO item (id, kind, title, path, use_count) VALUES ('1', 'app', 'Code', 'C:\\Code.exe', 4)",
+                [],
+            )
+            .unwrap();
+        }
+
+        migrate_schema(&db, SCHEMA_VERSION).unwrap();
+        assert_eq!(schema_version(&db).unwrap(), SCHEMA_VERSION);
+        assert_schema_shape(&db, SCHEMA_VERSION);
+        if start >= 1 {
+            let item = nex_core::index_store::get_item(&db, "1").unwrap().unwrap();
+            assert_eq!(item.use_count, 4);
+            assert_eq!(item.subtitle, "");
+        }
+    }
+}
+
+#[test]
+fn newer_schema_is_refused_but_readable() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-schema-guard-{unique}"));
+    let db_path = root.join("index.sqlite3");
+    {
+        let db = nex_core::index_store::open_file(&db_path).unwrap();
+        let newer = nex_core::index_store::SCHEMA_VERSION + 1;
+        db.pragma_update(None, "user_version", newer).unwrap();
+    }
+
+    match nex_core::index_store::open_file(&db_path) {
+        Err(nex_core::index_store::StoreError::SchemaTooNew { found, supported }) => {
+            assert_eq!(found, supported + 1);
+        }
+        other => panic!("expected schema guard, got {:?}", other.map(|_| ())),
+    }
+    let read_only = nex_core::index_store::open_file_read_only(&db_path).unwrap();
+    assert!(nex_core::index_store::list_items(&read_only)
+        .unwrap()
+        .is_empty());
+
+    drop(read_only);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn schema_changes_back_up_the_database_file() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-schema-backup-{unique}"));
+    let db_path = root.join("index.sqlite3");
+    {
+        let db = nex_core::index_store::open_file(&db_path).unwrap();
+        let item = nex_core::model::SearchItem::new("1", "file", "Notes", "C:\\notes.txt");
+        nex_core::index_store::upsert_item(&db, &item).unwrap();
+    }
+
+    let backup = nex_core::index_store::downgrade_file(&db_path, 3)
+        .unwrap()
+        .expect("downgrade should back up the current schema");
+    let backup_db = nex_core::index_store::open_file_read_only(&backup).unwrap();
+    assert_eq!(
+        nex_core::index_store::schema_version(&backup_db).unwrap(),
+        nex_core::index_store::SCHEMA_VERSION
+    );
+    drop(backup_db);
+
+    let db = nex_core::index_store::open_file(&db_path).unwrap();
+    assert_eq!(
+        nex_core::index_store::schema_version(&db).unwrap(),
+        nex_core::index_store::SCHEMA_VERSION
+    );
+    assert!(nex_core::index_store::get_item(&db, "1").unwrap().is_some());
+    assert!(root.join("index.sqlite3-backup-v3").exists());
+
+    drop(db);
+    std::fs::remove_dir_all(&root).unwrap();
+}
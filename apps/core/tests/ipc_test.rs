@@ -0,0 +1,267 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use common::temp_root;
+use nex_core::config::Config;
+use nex_core::core_service::CoreService;
+use nex_core::index_store;
+use nex_core::ipc::{bind, connect, serve, IpcStream};
+use nex_core::model::SearchItem;
+
+fn start_file_server(root: &std::path::Path) -> (String, PathBuf) {
+    let endpoint = root.join("nex.sock").to_string_lossy().into_owned();
+    let db_path = root.join("index.sqlite3");
+    let server_db_path = db_path.clone();
+    let listener = bind(&endpoint).unwrap();
+    serve(listener, &endpoint, move || {
+        let db = index_store::open_file(&server_db_path)?;
+        CoreService::with_connection(Config::default(), db)
+    })
+    .unwrap();
+    (endpoint, db_path)
+}
+
+fn call(
+    reader: &mut BufReader<Box<dyn IpcStream>>,
+    request: &serde_json::Value,
+) -> serde_json::Value {
+    let stream = reader.get_mut();
+    stream.write_all(request.to_string().as_bytes()).unwrap();
+    stream.write_all(b"\n").unwrap();
+    stream.flush().unwrap();
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+fn search(id: u64, query: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "kind": "Search",
+        "payload": { "query": query, "limit": 5 }
+    })
+}
+
+#[test]
+fn concurrent_clients_get_their_own_correlated_responses() {
+    let root = temp_root("ipc-clients");
+    let (endpoint, db_path) = start_file_server(&root);
+    let writer =
+        CoreService::with_connection(Config::default(), index_store::open_file(&db_path).unwrap())
+            .unwrap();
+    let app_path = root.join("code");
+    std::fs::write(&app_path, b"").unwrap();
+    writer
+        .upsert_item(&SearchItem::new(
+            "code",
+            "app",
+            "Code",
+            app_path.to_str().unwrap(),
+        ))
+        .unwrap();
+
+    let mut first = BufReader::new(connect(&endpoint).unwrap());
+    let mut second = BufReader::new(connect(&endpoint).unwrap());
+
+    let response = call(&mut second, &search(2, "code"));
+    assert_eq!(response["id"], 2);
+    assert_eq!(response["status"], "ok");
+    assert_eq!(response["response"]["payload"]["results"][0]["id"], "code");
+
+    let response = call(&mut first, &search(1, "code"));
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["status"], "ok");
+
+    let response = call(
+        &mut first,
+        &serde_json::json!({ "id": "bad", "kind": "nope" }),
+    );
+    assert_eq!(response["id"], "bad");
+    assert_eq!(response["status"], "err");
+    assert_eq!(response["error"]["code"], "invalid_json");
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn second_server_on_live_endpoint_is_refused_and_stale_socket_is_replaced() {
+    let root = temp_root("ipc-endpoint");
+    let (endpoint, _) = start_file_server(&root);
+    assert!(bind(&endpoint).is_err());
+
+    let stale = root.join("stale.sock");
+    drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+    assert!(stale.exists());
+    assert!(bind(stale.to_str().unwrap()).is_ok());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn subscribed_connections_receive_pushed_events() {
+    let root = temp_root("ipc-events");
+    let endpoint = root.join("nex.sock").to_string_lossy().into_owned();
+    let bus = nex_core::events::EventBus::default();
+    let server_bus = bus.clone();
+    let listener = bind(&endpoint).unwrap();
+    serve(listener, &endpoint, move || {
+        let db = index_store::open_memory()?;
+        Ok(CoreService::with_connection(Config::default(), db)?.with_event_bus(server_bus))
+    })
+    .unwrap();
+
+    let mut envelope = BufReader::new(connect(&endpoint).unwrap());
+    let response = call(
+        &mut envelope,
+        &serde_json::json!({ "id": 1, "kind": "Subscribe", "payload": { "events": ["config_reloaded"] } }),
+    );
+    assert_eq!(response["status"], "ok");
+    assert_eq!(
+        response["response"]["payload"]["events"],
+        serde_json::json!(["config_reloaded"])
+    );
+
+    let mut rpc = BufReader::new(connect(&endpoint).unwrap());
+    let response = call(
+        &mut rpc,
+        &serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "subscribe", "params": {} }),
+    );
+    assert!(response["result"]["events"]
+        .as_array()
+        .unwrap()
+        .contains(&"item_launched".into()));
+
+    let rejected = call(
+        &mut BufReader::new(connect(&endpoint).unwrap()),
+        &serde_json::json!({ "id": 2, "kind": "Subscribe", "payload": { "events": ["nope"] } }),
+    );
+    assert_eq!(rejected["error"]["code"], "invalid_request");
+
+    bus.publish(nex_core::events::CoreEvent::ClipboardCaptured {
+        captured_epoch_secs: 7,
+    });
+    let publisher =
+        CoreService::with_connection(Config::default(), index_store::open_memory().unwrap())
+            .unwrap()
+            .with_event_bus(bus.clone());
+    publisher.apply_config(&Config::default()).unwrap();
+
+    let mut line = String::new();
+    envelope.read_line(&mut line).unwrap();
+    let event: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(event["status"], "event");
+    assert_eq!(event["event"], "config_reloaded");
+
+    line.clear();
+    rpc.read_line(&mut line).unwrap();
+    let event: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(event["method"], "event");
+    assert_eq!(event["params"]["event"], "clipboard_captured");
+    assert_eq!(event["params"]["data"]["captured_epoch_secs"], 7);
+    assert!(event.get("id").is_none());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn config_reloads_reach_plugin_launches_over_ipc() {
+    let root = temp_root("ipc-grants");
+    std::fs::write(
+        root.join("tools.json"),
+        serde_json::json!({
+            "id": "tools",
+            "capabilities": ["run_commands"],
+            "actions": [{ "id": "noop", "title": "No-op", "type": "command", "command": "true" }]
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let granted = Config {
+        plugins_safe_mode: false,
+        plugin_paths: vec![root.clone()],
+        plugin_grants: vec!["tools:run_commands".to_string()],
+        ..Config::default()
+    };
+    let plugins = nex_core::plugin_sdk::PluginRegistry::load_from_config(&granted);
+    let endpoint = root.join("nex.sock").to_string_lossy().into_owned();
+    let server_config = granted.clone();
+    let server = serve(bind(&endpoint).unwrap(), &endpoint, move || {
+        let db = index_store::open_memory()?;
+        Ok(CoreService::with_connection(server_config, db)?.with_plugins(plugins))
+    })
+    .unwrap();
+
+    let launch = serde_json::json!({
+        "id": 1,
+        "kind": "Launch",
+        "payload": { "id": "plugin:tools:action:noop" }
+    });
+    let mut client = BufReader::new(connect(&endpoint).unwrap());
+    assert_eq!(call(&mut client, &launch)["status"], "ok");
+
+    server.apply_config(
+        &Config {
+            plugin_grants: Vec::new(),
+            ..granted
+        },
+        false,
+    );
+    let refused = call(&mut client, &launch);
+    assert_eq!(refused["status"], "err");
+    assert!(refused["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("plugin_grants"));
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn plugin_reloads_reach_the_ipc_service() {
+    let root = temp_root("ipc-plugins");
+    let cfg = Config {
+        plugins_safe_mode: false,
+        plugin_paths: vec![root.clone()],
+        plugin_grants: vec!["tools:run_commands".to_string()],
+        ..Config::default()
+    };
+    let endpoint = root.join("nex.sock").to_string_lossy().into_owned();
+    let server_config = cfg.clone();
+    let server = serve(bind(&endpoint).unwrap(), &endpoint, move || {
+        let db = index_store::open_memory()?;
+        CoreService::with_connection(server_config, db)
+    })
+    .unwrap();
+
+    let launch = serde_json::json!({
+        "id": 1,
+        "kind": "Launch",
+        "payload": { "id": "plugin:tools:action:noop" }
+    });
+    let mut client = BufReader::new(connect(&endpoint).unwrap());
+    assert_eq!(
+        call(&mut client, &launch)["error"]["code"],
+        "item_not_found"
+    );
+
+    std::fs::write(
+        root.join("tools.json"),
+        serde_json::json!({
+            "id": "tools",
+            "capabilities": ["run_commands"],
+            "actions": [{ "id": "noop", "title": "No-op", "type": "command", "command": "true" }]
+        })
+        .to_string(),
+    )
+    .unwrap();
+    server.replace_plugins(&nex_core::plugin_sdk::PluginRegistry::load_from_config(
+        &cfg,
+    ));
+    assert_eq!(call(&mut client, &launch)["status"], "ok");
+
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use common::service_with_seed_item;
+use nex_core::core_service::CoreService;
+use nex_core::jsonrpc::{
+    error_code, method_name, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR,
+    PROTOCOL_VERSION,
+};
+use nex_core::transport::{handle_json_line, ErrorCode};
+use serde_json::{json, Value};
+
+fn call(service: &CoreService, request: Value) -> Value {
+    let raw = handle_json_line(service, &request.to_string()).expect("reply expected");
+    serde_json::from_str(&raw).unwrap()
+}
+
+#[test]
+fn initialize_reports_version_kinds_and_capabilities() {
+    let service = service_with_seed_item();
+
+    let response = call(
+        &service,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "protocol_version": 99 } }),
+    );
+
+    assert_eq!(response["jsonrpc"], "2.0");
+    assert_eq!(response["id"], 1);
+    let result = &response["result"];
+    assert_eq!(result["protocol_version"], PROTOCOL_VERSION);
+    assert_eq!(
+        result["request_kinds"],
+        json!([
+            "Search",
+            "Launch",
+            "Subscribe",
+            "RebuildIndex",
+            "GetItem",
+            "UpsertItem",
+            "DeleteItem",
+            "RecordSelection",
+            "ListProviders",
+            "GetStatus"
+        ])
+    );
+    assert_eq!(
+        result["methods"],
+        json!([
+            "initialize",
+            "search",
+            "launch",
+            "subscribe",
+            "rebuild_index",
+            "get_item",
+            "upsert_item",
+            "delete_item",
+            "record_selection",
+            "list_providers",
+            "get_status"
+        ])
+    );
+    assert_eq!(result["capabilities"]["batch"], true);
+    assert!(response.get("error").is_none());
+}
+
+#[test]
+fn methods_map_onto_core_requests() {
+    let service = service_with_seed_item();
+
+    let response = call(
+        &service,
+        json!({ "jsonrpc": "2.0", "id": "q", "method": "search", "params": { "query": "code", "limit": 5 } }),
+    );
+
+    assert_eq!(response["id"], "q");
+    assert!(response["result"]["results"].is_array());
+    assert!(response.get("error").is_none());
+    assert_eq!(method_name("RebuildIndex"), "rebuild_index");
+}
+
+#[test]
+fn index_methods_accept_missing_params() {
+    let service = service_with_seed_item();
+
+    let status = call(
+        &service,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "get_status" }),
+    );
+    assert_eq!(status["result"]["indexed_items"], 1);
+
+    let item = call(
+        &service,
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "get_item", "params": { "id": "seed" } }),
+    );
+    assert_eq!(item["result"]["item"]["title"], "Code");
+}
+
+#[test]
+fn errors_use_json_rpc_codes() {
+    let service = service_with_seed_item();
+
+    let unknown = call(
+        &service,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "nope" }),
+    );
+    assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+    let bad_params = call(
+        &service,
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "search", "params": { "limit": "x" } }),
+    );
+    assert_eq!(bad_params["error"]["code"], INVALID_PARAMS);
+
+    let missing = call(
+        &service,
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "launch", "params": { "id": "missing" } }),
+    );
+    assert_eq!(
+        missing["error"]["code"],
+        error_code(&ErrorCode::ItemNotFound)
+    );
+    assert_eq!(missing["error"]["data"]["code"], "item_not_found");
+
+    let wrong_version = call(
+        &service,
+        json!({ "jsonrpc": "1.0", "id": 4, "method": "search" }),
+    );
+    assert_eq!(wrong_version["error"]["code"], INVALID_REQUEST);
+
+    let raw = handle_json_line(&service, r#"{"jsonrpc": "2.0", "id": 5,"#).unwrap();
+    let parse: Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(parse["error"]["code"], PARSE_ERROR);
+    assert_eq!(parse["id"], Value::Null);
+}
+
+#[test]
+fn notifications_are_silent_and_batches_answer_each_request() {
+    let service = service_with_seed_item();
+
+    let notification =
+        json!({ "jsonrpc": "2.0", "method": "search", "params": { "query": "code" } });
+    assert!(handle_json_line(&service, &notification.to_string()).is_none());
+
+    let batch = json!([
+        { "jsonrpc": "2.0", "id": 1, "method": "initialize" },
+        notification,
+        { "jsonrpc": "2.0", "id": 2, "method": "search", "params": { "query": "code" } }
+    ]);
+    let responses = call(&service, batch);
+    let responses = responses.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[1]["id"], 2);
+}
+
+#[test]
+fn legacy_envelope_is_unchanged_without_jsonrpc_member() {
+    let service = service_with_seed_item();
+
+    let response = call(
+        &service,
+        json!({ "id": 7, "kind": "Search", "payload": { "query": "code", "limit": 1 } }),
+    );
+
+    assert_eq!(response["status"], "ok");
+    assert_eq!(response["id"], 7);
+}
@@ -0,0 +1,4 @@
+include!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../tests/perf/index_batch_write_test.rs"
+));
@@ -0,0 +1,274 @@
+#![cfg(unix)]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use nex_core::config::Config;
+use nex_core::model::SearchItem;
+use nex_core::plugin_host::{PluginHostError, ProcessPlugin, ProcessPluginSpec};
+use nex_core::plugin_sdk::{
+    reload_registry, CommandOutput, PluginActionKind, PluginRegistry, PluginTestHarness,
+    PROCESS_QUERY_BUDGET,
+};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/echo_plugin.sh")
+}
+
+fn echo_plugin(timeout_ms: u64) -> ProcessPlugin {
+    ProcessPlugin::new(ProcessPluginSpec {
+        plugin_id: "echo".to_string(),
+        label: "Echo".to_string(),
+        command: PathBuf::from("sh"),
+        args: vec![fixture_path().to_string_lossy().into_owned()],
+        working_dir: None,
+        env: Vec::new(),
+        timeout: Duration::from_millis(timeout_ms),
+    })
+}
+
+#[test]
+fn answers_queries_and_remembers_result_actions() {
+    let plugin = echo_plugin(2_000);
+
+    let results = plugin.query("hello", 5).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, "plugin:echo:result:echo");
+    assert_eq!(results[0].kind, "action");
+    assert_eq!(results[0].title, "Echo hello");
+
+    let action = plugin.action("plugin:echo:result:echo").unwrap();
+    assert_eq!(
+        action.kind,
+        PluginActionKind::Command {
+            command: "true".to_string(),
+            args: vec!["hello".to_string()],
+            output: CommandOutput::Discard,
+        }
+    );
+    assert!(plugin.is_running());
+}
+
+#[test]
+fn restarts_after_a_crash() {
+    let plugin = echo_plugin(2_000);
+    let first = plugin.query("one", 5).unwrap();
+
+    assert!(matches!(
+        plugin.query("crash", 5),
+        Err(PluginHostError::Exited)
+    ));
+    assert!(!plugin.is_running());
+
+    let second = plugin.query("two", 5).unwrap();
+    assert_eq!(second[0].title, "Echo two");
+    assert_ne!(first[0].path, second[0].path);
+}
+
+#[test]
+fn slow_answers_time_out_without_killing_the_plugin() {
+    let plugin = echo_plugin(200);
+
+    assert!(matches!(
+        plugin.query("slow", 5),
+        Err(PluginHostError::Timeout(_))
+    ));
+    assert!(plugin.is_running());
+}
+
+#[test]
+fn repeated_timeouts_restart_the_plugin() {
+    let plugin = echo_plugin(100);
+
+    for _ in 0..2 {
+        assert!(matches!(
+            plugin.query("slow", 5),
+            Err(PluginHostError::Timeout(_))
+        ));
+        assert!(plugin.is_running());
+    }
+    assert!(matches!(
+        plugin.query("slow", 5),
+        Err(PluginHostError::Timeout(_))
+    ));
+    assert!(!plugin.is_running());
+}
+
+#[test]
+fn a_busy_plugin_only_answers_the_latest_submitted_query() {
+    let plugin = Arc::new(echo_plugin(3_000));
+    let (sender, answers) = std::sync::mpsc::channel();
+    let reply = |query: &'static str| {
+        let sender = sender.clone();
+        move |outcome: Result<Vec<SearchItem>, PluginHostError>| {
+            let _ = sender.send((query, outcome.map(|items| items[0].title.clone())));
+        }
+    };
+
+    plugin.submit("slow", 5, reply("slow"));
+    std::thread::sleep(Duration::from_millis(200));
+    plugin.submit("one", 5, reply("one"));
+    plugin.submit("two", 5, reply("two"));
+    drop(sender);
+
+    let answered: Vec<_> = answers.iter().collect();
+    assert_eq!(answered.len(), 2);
+    assert_eq!(answered[0].0, "slow");
+    assert_eq!(answered[1].0, "two");
+    assert_eq!(answered[1].1.as_deref().unwrap(), "Echo two");
+}
+
+#[test]
+fn a_plugin_that_stops_reading_stdin_is_restarted() {
+    let plugin = ProcessPlugin::new(ProcessPluginSpec {
+        plugin_id: "deaf".to_string(),
+        label: "Deaf".to_string(),
+        command: PathBuf::from("sh"),
+        args: vec!["-c".to_string(), "sleep 30".to_string()],
+        working_dir: None,
+        env: Vec::new(),
+        timeout: Duration::from_millis(100),
+    });
+    // Larger than a pipe buffer, so the write cannot complete.
+    let query = "x".repeat(1 << 20);
+
+    let started = Instant::now();
+    assert!(matches!(
+        plugin.query(&query, 5),
+        Err(PluginHostError::Timeout(_))
+    ));
+    assert!(matches!(
+        plugin.query("again", 5),
+        Err(PluginHostError::Stalled)
+    ));
+    assert!(!plugin.is_running());
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn missing_executables_back_off() {
+    let plugin = ProcessPlugin::new(ProcessPluginSpec {
+        plugin_id: "missing".to_string(),
+        label: "Missing".to_string(),
+        command: PathBuf::from("/nonexistent/nex-plugin"),
+        args: Vec::new(),
+        working_dir: None,
+        env: Vec::new(),
+        timeout: Duration::from_millis(100),
+    });
+
+    assert!(matches!(
+        plugin.query("a", 5),
+        Err(PluginHostError::Spawn(_))
+    ));
+    assert!(matches!(
+        plugin.query("a", 5),
+        Err(PluginHostError::Spawn(_))
+    ));
+    assert!(matches!(
+        plugin.query("a", 5),
+        Err(PluginHostError::BackingOff(_))
+    ));
+}
+
+#[test]
+fn registry_starts_process_plugins_only_outside_safe_mode() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-plugin-host-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::copy(fixture_path(), root.join("echo_plugin.sh")).unwrap();
+    let manifest = serde_json::json!({
+        "id": "echo",
+        "name": "Echo",
+        "capabilities": ["run_commands"],
+        "process": { "command": "sh", "args": ["./echo_plugin.sh"], "timeout_ms": 2000 }
+    });
+    std::fs::write(root.join("echo.json"), manifest.to_string()).unwrap();
+
+    let mut cfg = Config {
+        plugins_enabled: true,
+        plugin_paths: vec![root.clone()],
+        ..Config::default()
+    };
+    let safe = PluginRegistry::load_from_config(&cfg);
+    assert!(safe.process_plugins.is_empty());
+    assert!(safe
+        .load_warnings
+        .iter()
+        .any(|warning| warning.contains("plugins_safe_mode")));
+
+    // Outside safe mode the process still needs `run_commands` granted.
+    cfg.plugins_safe_mode = false;
+    let ungranted = PluginRegistry::load_from_config(&cfg);
+    assert!(ungranted.process_plugins.is_empty());
+    assert!(ungranted.load_warnings.iter().any(|warning| warning
+        .starts_with("plugin 'echo' process not started")
+        && warning.contains("plugin_grants")));
+
+    cfg.plugin_grants = vec!["echo:run_commands".to_string()];
+    let mut registry = PluginRegistry::load_from_config(&cfg);
+    // `timeout_ms` above the query budget is capped to it.
+    assert_eq!(
+        registry.process_plugins[0].spec().timeout,
+        PROCESS_QUERY_BUDGET
+    );
+    let results = registry.query_process_plugins("hi", 3);
+    assert_eq!(results.len(), 1);
+    assert!(registry.action(&results[0].id).is_some());
+
+    // A reload that leaves the process spec alone keeps the running process.
+    let running = Arc::clone(&registry.process_plugins[0]);
+    assert!(reload_registry(&mut registry, &cfg).is_empty());
+    assert!(Arc::ptr_eq(&running, &registry.process_plugins[0]));
+    assert!(running.is_running());
+
+    // A plugin slower than the query budget does not hold up the caller.
+    let started = Instant::now();
+    assert!(registry.query_process_plugins("slow", 3).is_empty());
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn harness_runs_process_plugins() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-plugin-host-harness-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::copy(fixture_path(), root.join("echo_plugin.sh")).unwrap();
+    let manifest = serde_json::json!({
+        "id": "echo",
+        "name": "Echo",
+        "capabilities": ["run_commands"],
+        "process": { "command": "sh", "args": ["./echo_plugin.sh"], "timeout_ms": 2000 }
+    });
+    std::fs::write(root.join("echo.json"), manifest.to_string()).unwrap();
+
+    let harness = PluginTestHarness::load(&root).unwrap();
+    assert!(harness.warnings().is_empty(), "{:?}", harness.warnings());
+    assert_eq!(harness.registry().process_plugins.len(), 1);
+
+    let results = harness.query("hello").unwrap();
+    let echo = results
+        .iter()
+        .find(|result| result.item.id == "plugin:echo:result:echo")
+        .expect("process answer should be listed");
+    assert_eq!(echo.item.title, "Echo hello");
+    assert_eq!(
+        echo.action.as_ref().unwrap().expanded,
+        Ok(PluginActionKind::Command {
+            command: "true".to_string(),
+            args: vec!["hello".to_string()],
+            output: CommandOutput::Discard,
+        })
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -0,0 +1,150 @@
+use nex_core::plugin_placeholders::{
+    expand_args, expand_placeholders, expand_snippet, utc_date, ExpandedSnippet,
+    PlaceholderContext, PlaceholderError,
+};
+
+fn context() -> PlaceholderContext {
+    PlaceholderContext {
+        query: "rust & go".to_string(),
+        selection_path: "/tmp/it's here.txt".to_string(),
+        clipboard: "say \"hi\"\n".to_string(),
+        plugin_dir: "/plugins/notes".to_string(),
+        settings: [("vault".to_string(), "work notes".to_string())].into(),
+        date: "2026-03-14".to_string(),
+        read_env: true,
+    }
+}
+
+#[test]
+fn substitutes_placeholders_with_escapes() {
+    let ctx = context();
+    assert_eq!(
+        expand_placeholders("https://example.com/?q={query:url}", &ctx).unwrap(),
+        "https://example.com/?q=rust%20%26%20go"
+    );
+    assert_eq!(
+        expand_placeholders("{query}|{selection_path:raw}", &ctx).unwrap(),
+        "rust & go|/tmp/it's here.txt"
+    );
+    assert_eq!(
+        expand_placeholders("{\"text\":\"{clipboard:json}\"}", &ctx),
+        Err(PlaceholderError::UnknownPlaceholder(
+            "\"text\":\"{clipboard:json".to_string()
+        ))
+    );
+    assert_eq!(
+        expand_placeholders("{{\"text\":\"{clipboard:json}\"}}", &ctx).unwrap(),
+        "{\"text\":\"say \\\"hi\\\"\\n\"}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn shell_escape_quotes_for_posix_sh() {
+    assert_eq!(
+        expand_placeholders("cat {selection_path:shell}", &context()).unwrap(),
+        "cat '/tmp/it'\\''s here.txt'"
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn shell_escape_is_refused_on_windows() {
+    assert_eq!(
+        expand_placeholders("/C type {selection_path:shell}", &context()),
+        Err(PlaceholderError::ShellEscapeUnsupported)
+    );
+}
+
+#[test]
+fn reads_environment_variables() {
+    std::env::set_var("NEX_PLACEHOLDER_TEST", "a b");
+    let args = expand_args(
+        &[
+            "--name={env:NEX_PLACEHOLDER_TEST}".to_string(),
+            "{env:NEX_PLACEHOLDER_TEST:url}".to_string(),
+            "{env:NEX_PLACEHOLDER_MISSING}".to_string(),
+        ],
+        &context(),
+    )
+    .unwrap();
+    assert_eq!(args, vec!["--name=a b", "a%20b", ""]);
+}
+
+#[test]
+fn environment_needs_read_env() {
+    let ctx = PlaceholderContext {
+        read_env: false,
+        ..context()
+    };
+    assert_eq!(
+        expand_placeholders("{env:HOME}", &ctx),
+        Err(PlaceholderError::EnvNotGranted("HOME".to_string()))
+    );
+    assert_eq!(
+        expand_snippet("token {env:GITHUB_TOKEN}", &ctx),
+        Err(PlaceholderError::EnvNotGranted("GITHUB_TOKEN".to_string()))
+    );
+}
+
+#[test]
+fn rejects_malformed_templates() {
+    let ctx = context();
+    assert!(matches!(
+        expand_placeholders("{query", &ctx),
+        Err(PlaceholderError::Unterminated(_))
+    ));
+    assert_eq!(
+        expand_placeholders("{home}", &ctx),
+        Err(PlaceholderError::UnknownPlaceholder("home".to_string()))
+    );
+    assert_eq!(
+        expand_placeholders("{query:base64}", &ctx),
+        Err(PlaceholderError::UnknownEscape("base64".to_string()))
+    );
+}
+
+#[test]
+fn substitutes_plugin_folder_and_settings() {
+    let ctx = context();
+    assert_eq!(
+        expand_args(
+            &[
+                "{plugin_dir}/bin/open".to_string(),
+                "--vault={setting:vault:url}".to_string(),
+            ],
+            &ctx,
+        )
+        .unwrap(),
+        vec!["/plugins/notes/bin/open", "--vault=work%20notes"]
+    );
+    assert_eq!(
+        expand_placeholders("{setting:missing}", &ctx),
+        Err(PlaceholderError::UnknownPlaceholder(
+            "setting:missing".to_string()
+        ))
+    );
+}
+
+#[test]
+fn snippets_expand_date_and_mark_the_cursor() {
+    let ctx = context();
+    assert_eq!(
+        expand_snippet("Hi {cursor},\nsent {date}. {cursor}", &ctx).unwrap(),
+        ExpandedSnippet {
+            text: "Hi ,\nsent 2026-03-14. ".to_string(),
+            cursor: Some(3),
+        }
+    );
+    assert_eq!(
+        expand_placeholders("{cursor}", &ctx),
+        Err(PlaceholderError::UnknownPlaceholder("cursor".to_string()))
+    );
+}
+
+#[test]
+fn formats_utc_dates() {
+    assert_eq!(utc_date(0), "1970-01-01");
+    assert_eq!(utc_date(951_782_400), "2000-02-29");
+    assert_eq!(utc_date(1_773_446_399), "2026-03-13");
+}
@@ -0,0 +1,780 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use nex_core::config::Config;
+use nex_core::model::SearchItem;
+use nex_core::plugin_placeholders::PlaceholderError;
+use nex_core::plugin_sdk::{
+    manifest_stamps, parse_plugin_grant, reload_registry, validate_plugin_path, CommandOutput,
+    PluginActionKind, PluginCapability, PluginRegistry, PluginTestHarness,
+};
+
+fn plugin_dir(label: &str, manifests: &[serde_json::Value]) -> PathBuf {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-plugin-sdk-{label}-{unique}"));
+    std::fs::create_dir_all(&root).unwrap();
+    for (index, manifest) in manifests.iter().enumerate() {
+        std::fs::write(
+            root.join(format!("plugin-{index}.json")),
+            manifest.to_string(),
+        )
+        .unwrap();
+    }
+    root
+}
+
+fn registry_for(root: &Path) -> PluginRegistry {
+    PluginRegistry::load_from_config(&Config {
+        plugins_enabled: true,
+        plugin_paths: vec![root.to_path_buf()],
+        ..Config::default()
+    })
+}
+
+#[test]
+fn trigger_keywords_route_queries_to_one_plugin() {
+    let root = plugin_dir(
+        "route",
+        &[
+            serde_json::json!({
+                "id": "github",
+                "name": "GitHub",
+                "trigger_keywords": ["gh", "GitHub"],
+                "actions": [
+                    { "id": "issues", "title": "Open issues", "path": "https://github.com/issues" },
+                    { "id": "pulls", "title": "Open pull requests", "path": "https://github.com/pulls" }
+                ]
+            }),
+            serde_json::json!({
+                "id": "notes",
+                "actions": [ { "id": "new", "title": "New note", "path": "notes://new" } ]
+            }),
+        ],
+    );
+    let registry = registry_for(&root);
+
+    // Scoped plugins stay out of unscoped queries unless they are global.
+    assert_eq!(registry.action_items.len(), 1);
+    assert_eq!(registry.action_items[0].id, "plugin:notes:action:new");
+
+    let route = registry.route("GH pull").unwrap();
+    assert_eq!(route.trigger.plugin_id, "github");
+    assert_eq!(route.keyword, "gh");
+    assert_eq!(route.argument, "pull");
+    let results = registry.scoped_results(&route, 5);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, "plugin:github:action:pulls");
+
+    let everything = registry.scoped_results(&registry.route("github ").unwrap(), 5);
+    assert_eq!(everything.len(), 2);
+
+    assert!(registry.route("gh").is_none());
+    assert!(registry.route("ghost town").is_none());
+    assert!(registry.action("plugin:github:action:issues").is_some());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn global_flag_and_keyword_conflicts() {
+    let root = plugin_dir(
+        "global",
+        &[serde_json::json!({
+            "id": "jira",
+            "trigger_keywords": ["jira", "two words"],
+            "global": true,
+            "actions": [ { "id": "board", "title": "Jira board", "path": "https://jira/board" } ]
+        })],
+    );
+    std::fs::write(
+        root.join("zz-duplicate.json"),
+        serde_json::json!({ "id": "other", "trigger_keywords": ["jira"] }).to_string(),
+    )
+    .unwrap();
+    let registry = registry_for(&root);
+
+    assert_eq!(registry.action_items.len(), 1);
+    assert_eq!(registry.triggers.len(), 1);
+    assert!(registry.triggers[0].global);
+    assert_eq!(registry.triggers[0].keywords, vec!["jira".to_string()]);
+    assert_eq!(registry.load_warnings.len(), 2);
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn invalid_manifests_report_every_field_error() {
+    let root = plugin_dir(
+        "invalid",
+        &[serde_json::json!({
+            "id": "broken",
+            "manifest_version": 99,
+            "min_nex_version": "999.0",
+            "trigger_keyword": ["b"],
+            "actions": [
+                { "id": "a", "title": "A", "type": "opne_path", "path": "x" },
+                { "id": "a", "title": "Again", "type": "command", "output": "screen" },
+                { "title": "No id", "path": "y", "args": [1] }
+            ],
+            "process": { "command": "plugin", "timeout_ms": 60000 }
+        })],
+    );
+
+    let reports = validate_plugin_path(&root);
+    assert_eq!(reports.len(), 1);
+    assert!(!reports[0].is_valid());
+    let errors = &reports[0].errors;
+    for expected in [
+        "trigger_keyword: unknown field",
+        "manifest_version: 99 is newer than this nex supports (1)",
+        "actions[0].type: unknown action type 'opne_path' (expected open_path, command or copy)",
+        "actions[1].id: duplicate action id 'a'",
+        "actions[1].command: required",
+        "actions[1].output: unknown output mode 'screen' (expected discard, result, clipboard)",
+        "actions[2].id: required",
+        "actions[2].args: expected a list of strings",
+        "process.timeout_ms: expected 1..=2000",
+    ] {
+        assert!(
+            errors.iter().any(|error| error == expected),
+            "missing '{expected}' in {errors:?}"
+        );
+    }
+    assert!(errors
+        .iter()
+        .any(|error| error.starts_with("min_nex_version: plugin requires nex 999.0")));
+
+    // The runtime skips the manifest and surfaces the same errors as load warnings.
+    let registry = registry_for(&root);
+    assert!(registry.action_items.is_empty());
+    assert_eq!(registry.load_warnings.len(), errors.len());
+    assert!(registry.load_warnings[0].contains("invalid: "));
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn duplicate_plugin_ids_across_paths_keep_the_first() {
+    let first = plugin_dir(
+        "dup-a",
+        &[serde_json::json!({
+            "id": "notes",
+            "manifest_version": 1,
+            "min_nex_version": "0.1",
+            "actions": [ { "id": "new", "title": "New note", "path": "notes://new" } ]
+        })],
+    );
+    let second = plugin_dir(
+        "dup-b",
+        &[serde_json::json!({
+            "id": "notes",
+            "actions": [ { "id": "old", "title": "Old note", "path": "notes://old" } ]
+        })],
+    );
+
+    let registry = PluginRegistry::load_from_config(&Config {
+        plugins_enabled: true,
+        plugin_paths: vec![first.clone(), second.clone()],
+        ..Config::default()
+    });
+    assert_eq!(registry.action_items.len(), 1);
+    assert_eq!(registry.action_items[0].id, "plugin:notes:action:new");
+    assert_eq!(registry.load_warnings.len(), 1);
+    assert!(registry.load_warnings[0].contains("id 'notes' is already loaded from"));
+
+    let reports = validate_plugin_path(&first);
+    assert!(reports[0].is_valid());
+    assert_eq!(reports[0].plugin_id.as_deref(), Some("notes"));
+
+    let _ = std::fs::remove_dir_all(first);
+    let _ = std::fs::remove_dir_all(second);
+}
+
+#[test]
+fn capabilities_must_be_declared_and_granted() {
+    let root = plugin_dir(
+        "capabilities",
+        &[serde_json::json!({
+            "id": "github",
+            "capabilities": ["open_urls", "run_commands"],
+            "actions": [ { "id": "issues", "title": "Open issues", "path": "https://github.com/issues" } ]
+        })],
+    );
+    let mut cfg = Config {
+        plugins_enabled: true,
+        plugin_paths: vec![root.clone()],
+        plugin_grants: vec!["github:open_urls".to_string()],
+        ..Config::default()
+    };
+    let registry = PluginRegistry::load_from_config(&cfg);
+    assert_eq!(
+        registry.load_warnings,
+        vec!["plugin 'github' declares 'run_commands' but plugin_grants does not grant it"]
+    );
+
+    assert!(registry
+        .authorize(&cfg, "github", PluginCapability::OpenUrls)
+        .is_ok());
+    let undeclared = registry
+        .authorize(&cfg, "github", PluginCapability::Filesystem)
+        .unwrap_err();
+    assert!(undeclared.contains("does not declare the 'filesystem' capability"));
+    let ungranted = registry
+        .authorize(&cfg, "github", PluginCapability::RunCommands)
+        .unwrap_err();
+    assert!(ungranted.contains("plugins_safe_mode"));
+
+    cfg.plugins_safe_mode = false;
+    assert!(registry
+        .authorize(&cfg, "github", PluginCapability::RunCommands)
+        .unwrap_err()
+        .contains("\"github:run_commands\""));
+    cfg.plugin_grants = vec!["github:*".to_string()];
+    assert!(registry
+        .authorize(&cfg, "github", PluginCapability::RunCommands)
+        .is_ok());
+    assert!(registry
+        .authorize(&cfg, "unknown", PluginCapability::OpenUrls)
+        .is_err());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn parses_plugin_grants() {
+    assert_eq!(
+        parse_plugin_grant("calc:read_clipboard"),
+        Ok(("calc".to_string(), vec![PluginCapability::ReadClipboard]))
+    );
+    assert_eq!(
+        parse_plugin_grant("calc:*").unwrap().1,
+        PluginCapability::ALL.to_vec()
+    );
+    assert!(parse_plugin_grant("calc").is_err());
+    assert!(parse_plugin_grant(":open_urls").is_err());
+    assert!(parse_plugin_grant("calc:network").is_err());
+
+    let root = plugin_dir(
+        "bad-capability",
+        &[serde_json::json!({ "id": "calc", "capabilities": ["network"] })],
+    );
+    let reports = validate_plugin_path(&root);
+    assert_eq!(
+        reports[0].errors,
+        vec![
+            "capabilities[0]: unknown capability 'network' \
+             (expected run_commands, open_urls, read_clipboard, read_env, filesystem)"
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn reload_reports_manifest_changes() {
+    let root = plugin_dir(
+        "reload",
+        &[serde_json::json!({
+            "id": "notes",
+            "actions": [
+                { "id": "new", "title": "New note", "path": "notes://new" },
+                { "id": "old", "title": "Old note", "path": "notes://old" }
+            ]
+        })],
+    );
+    let cfg = Config {
+        plugins_enabled: true,
+        plugin_paths: vec![root.clone()],
+        ..Config::default()
+    };
+    let mut registry = registry_for(&root);
+    let stamps = manifest_stamps(&cfg, &registry);
+    assert_eq!(stamps.len(), 1);
+
+    std::fs::write(
+        root.join("plugin-0.json"),
+        serde_json::json!({
+            "id": "notes",
+            "actions": [
+                { "id": "new", "title": "New note", "path": "notes://new?draft=1" },
+                { "id": "today", "title": "Today's note", "path": "notes://today" }
+            ]
+        })
+        .to_string(),
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("plugin-1.json"),
+        serde_json::json!({ "id": "todo", "capabilities": ["open_urls"] }).to_string(),
+    )
+    .unwrap();
+    assert_ne!(manifest_stamps(&cfg, &registry), stamps);
+
+    let diff = reload_registry(&mut registry, &cfg);
+    assert_eq!(diff.added_plugins, vec!["todo"]);
+    assert!(diff.removed_plugins.is_empty());
+    assert_eq!(diff.added_actions, vec!["plugin:notes:action:today"]);
+    assert_eq!(diff.removed_actions, vec!["plugin:notes:action:old"]);
+    assert_eq!(diff.changed_actions, vec!["plugin:notes:action:new"]);
+    assert_eq!(diff.new_warnings.len(), 1);
+    assert!(diff.plugins_changed());
+    assert!(registry.action("plugin:notes:action:today").is_some());
+
+    // Reloading unchanged manifests reports nothing.
+    assert!(reload_registry(&mut registry, &cfg).is_empty());
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn loads_toml_and_json5_manifests_and_bundle_folders() {
+    let root = plugin_dir("formats", &[]);
+    std::fs::write(
+        root.join("links.json5"),
+        r#"{
+            // Comments and trailing commas are fine in JSON5.
+            id: "links",
+            actions: [ { id: "docs", title: "Docs", path: "https://example.com/docs" }, ],
+        }"#,
+    )
+    .unwrap();
+    let bundle = root.join("notes");
+    std::fs::create_dir_all(bundle.join("bin")).unwrap();
+    std::fs::write(
+        bundle.join("plugin.toml"),
+        r#"
+id = "notes"
+name = "Notes"
+
+[settings]
+vault = "work"
+limit = 20
+
+[[actions]]
+id = "today"
+title = "Open today's note"
+type = "command"
+command = "bin/open-note"
+args = ["--vault={setting:vault}"]
+
+[[actions]]
+id = "readme"
+title = "Read me"
+path = "./README.md"
+"#,
+    )
+    .unwrap();
+    // Manifests elsewhere in a bundle folder are not picked up on their own.
+    std::fs::write(bundle.join("extra.json"), "{}").unwrap();
+
+    let registry = registry_for(&root);
+    assert!(
+        registry.load_warnings.is_empty(),
+        "{:?}",
+        registry.load_warnings
+    );
+    assert!(registry.action("plugin:links:action:docs").is_some());
+
+    match registry.action("plugin:notes:action:today").unwrap().kind {
+        PluginActionKind::Command { command, args, .. } => {
+            assert_eq!(PathBuf::from(command), bundle.join("bin").join("open-note"));
+            assert_eq!(args, vec!["--vault={setting:vault}"]);
+        }
+        other => panic!("unexpected action kind: {other:?}"),
+    }
+    match registry.action("plugin:notes:action:readme").unwrap().kind {
+        PluginActionKind::OpenPath { path } => {
+            assert_eq!(PathBuf::from(path), bundle.join("README.md"))
+        }
+        other => panic!("unexpected action kind: {other:?}"),
+    }
+    assert_eq!(registry.plugin_dirs["notes"], bundle);
+    let settings = &registry.settings["notes"];
+    assert_eq!(settings["vault"], "work");
+    assert_eq!(settings["limit"], "20");
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn rejects_nested_settings() {
+    let root = plugin_dir(
+        "settings",
+        &[serde_json::json!({ "id": "bad", "settings": { "nested": { "a": 1 } } })],
+    );
+    let reports = validate_plugin_path(&root.join("plugin-0.json"));
+    assert_eq!(
+        reports[0].errors,
+        vec!["settings.nested: expected a string, number or boolean"]
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn icons_and_secondary_actions_ride_on_results() {
+    let root = plugin_dir(
+        "icons",
+        &[serde_json::json!({
+            "id": "github",
+            "provider_items": [{
+                "id": "repo",
+                "title": "nex repository",
+                "path": "https://github.com/nex/nex",
+                "icon": "icons/repo.png",
+                "secondary_actions": [
+                    { "id": "copy", "title": "Copy link", "type": "copy", "text": "https://github.com/nex/nex" }
+                ]
+            }],
+            "actions": [{
+                "id": "issues",
+                "title": "Open issues",
+                "path": "https://github.com/issues",
+                "icon": "\u{e8a7}",
+                "secondary_actions": [
+                    { "id": "mine", "title": "My issues", "path": "https://github.com/issues/assigned", "icon": "mine.ico" }
+                ]
+            }]
+        })],
+    );
+    let registry = registry_for(&root);
+    assert!(
+        registry.load_warnings.is_empty(),
+        "{:?}",
+        registry.load_warnings
+    );
+
+    let action = &registry.action_items[0];
+    assert_eq!(action.icon, "\u{e8a7}");
+    assert_eq!(action.secondary_actions.len(), 1);
+    let secondary = &action.secondary_actions[0];
+    assert_eq!(secondary.id, "plugin:github:action:issues:secondary:mine");
+    assert_eq!(secondary.title, "My issues");
+    assert_eq!(PathBuf::from(&secondary.icon), root.join("mine.ico"));
+    assert_eq!(
+        registry.action(&secondary.id).unwrap().kind,
+        PluginActionKind::OpenPath {
+            path: "https://github.com/issues/assigned".to_string()
+        }
+    );
+
+    // Indexed provider items get their icon and actions back when they show up in results.
+    let mut results = vec![
+        SearchItem::new(
+            "plugin:github:item:repo",
+            "file",
+            "nex repository",
+            "https://github.com/nex/nex",
+        ),
+        SearchItem::new("app:code", "app", "Code", "/usr/bin/code"),
+    ];
+    registry.present(&mut results);
+    assert_eq!(
+        PathBuf::from(&results[0].icon),
+        root.join("icons").join("repo.png")
+    );
+    assert_eq!(
+        results[0].secondary_actions[0].id,
+        "plugin:github:item:repo:secondary:copy"
+    );
+    assert!(results[1].icon.is_empty() && results[1].secondary_actions.is_empty());
+    assert_eq!(
+        registry
+            .action("plugin:github:item:repo:secondary:copy")
+            .unwrap()
+            .kind,
+        PluginActionKind::Copy {
+            text: "https://github.com/nex/nex".to_string()
+        }
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn secondary_actions_are_validated_and_do_not_nest() {
+    let root = plugin_dir(
+        "secondary",
+        &[serde_json::json!({
+            "id": "bad",
+            "actions": [{
+                "id": "a",
+                "title": "A",
+                "path": "https://example.com",
+                "icon": 7,
+                "secondary_actions": [
+                    { "id": "copy", "title": "Copy", "type": "copy" },
+                    { "id": "copy", "title": "Again", "path": "x", "secondary_actions": [] }
+                ]
+            }]
+        })],
+    );
+    let errors = &validate_plugin_path(&root)[0].errors;
+    for expected in [
+        "actions[0].icon: expected a string",
+        "actions[0].secondary_actions[0].text: required",
+        "actions[0].secondary_actions[1].id: duplicate action id 'copy'",
+        "actions[0].secondary_actions[1].secondary_actions: secondary actions cannot have their own",
+    ] {
+        assert!(
+            errors.iter().any(|error| error == expected),
+            "missing '{expected}' in {errors:?}"
+        );
+    }
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn snippets_load_from_manifest_and_folder() {
+    let root = plugin_dir(
+        "snippets",
+        &[serde_json::json!({
+            "id": "replies",
+            "name": "Replies",
+            "trigger_keywords": ["re"],
+            "snippets": [{
+                "id": "thanks",
+                "title": "Thanks",
+                "text": "Thanks, {cursor}\n-- sent {date}",
+                "keywords": ["ty"]
+            }],
+            "snippets_dir": "snippets"
+        })],
+    );
+    std::fs::create_dir_all(root.join("snippets")).unwrap();
+    std::fs::write(
+        root.join("snippets").join("address.txt"),
+        "1 Main St\nSpringfield\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("snippets").join("thanks.txt"), "shadowed").unwrap();
+    std::fs::write(root.join("snippets").join(".hidden"), "skipped").unwrap();
+
+    let registry = registry_for(&root);
+    assert!(
+        registry.load_warnings.is_empty(),
+        "{:?}",
+        registry.load_warnings
+    );
+    let ids: Vec<&str> = registry
+        .snippet_items
+        .iter()
+        .map(|item| item.id.as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            "plugin:replies:snippet:thanks",
+            "plugin:replies:snippet:address"
+        ]
+    );
+    assert!(registry
+        .snippet_items
+        .iter()
+        .all(|item| item.kind == "snippet"));
+    assert_eq!(
+        registry
+            .action("plugin:replies:snippet:address")
+            .unwrap()
+            .kind,
+        PluginActionKind::Snippet {
+            text: "1 Main St\nSpringfield".to_string()
+        }
+    );
+    // Snippets are not scoped behind the plugin's trigger keyword.
+    assert!(registry.triggers[0].action_items.is_empty());
+
+    // Editing a snippet file is noticed like an edited manifest.
+    let cfg = Config {
+        plugins_enabled: true,
+        plugin_paths: vec![root.clone()],
+        ..Config::default()
+    };
+    let stamps = manifest_stamps(&cfg, &registry);
+    assert!(stamps
+        .iter()
+        .any(|stamp| stamp.path.ends_with("address.txt")));
+    std::fs::write(root.join("snippets").join("address.txt"), "2 Elm St").unwrap();
+    assert_ne!(manifest_stamps(&cfg, &registry), stamps);
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn snippet_ids_are_validated() {
+    let root = plugin_dir(
+        "snippet-errors",
+        &[serde_json::json!({
+            "id": "broken",
+            "snippets": [
+                { "id": "a", "title": "A", "text": "x" },
+                { "id": "a", "title": "Again", "text": "y", "shortcut": "aa" },
+                { "id": "b", "text": "z" }
+            ]
+        })],
+    );
+    let reports = validate_plugin_path(&root);
+    let errors = &reports[0].errors;
+    assert!(
+        errors.contains(&"snippets[1].id: duplicate snippet id 'a'".to_string()),
+        "{errors:?}"
+    );
+    assert!(
+        errors
+            .iter()
+            .any(|error| error.starts_with("snippets[1].shortcut")),
+        "{errors:?}"
+    );
+    assert!(
+        errors.contains(&"snippets[2].title: required".to_string()),
+        "{errors:?}"
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn harness_runs_queries_through_routing_and_ranking() {
+    let root = plugin_dir(
+        "harness",
+        &[serde_json::json!({
+            "id": "github",
+            "name": "GitHub",
+            "trigger_keywords": ["gh"],
+            "capabilities": ["run_commands", "read_clipboard"],
+            "settings": { "org": "nex" },
+            "provider_items": [
+                { "id": "repo", "title": "nex repository", "path": "https://github.com/nex/nex" }
+            ],
+            "actions": [
+                {
+                    "id": "search",
+                    "title": "Search issues",
+                    "type": "command",
+                    "command": "gh",
+                    "args": ["issue", "list", "--search={query}", "--repo={setting:org}/nex"]
+                },
+                { "id": "paste", "title": "Paste issue", "type": "copy", "text": "{clipboard} ({date})" },
+                { "id": "broken", "title": "Broken args", "type": "command", "command": "gh", "args": ["{home}"] }
+            ],
+            "snippets": [
+                { "id": "sig", "title": "Signature", "text": "Cheers,{cursor}\n{setting:org} team" }
+            ]
+        })],
+    );
+    let harness = PluginTestHarness::load(&root)
+        .unwrap()
+        .with_clipboard("#42")
+        .with_date("2026-01-02");
+    assert!(harness.warnings().is_empty(), "{:?}", harness.warnings());
+
+    // Scoped queries only reach the plugin; the action sees the text after the keyword.
+    let scoped = harness.query("gh issues").unwrap();
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].item.id, "plugin:github:action:search");
+    assert_eq!(
+        scoped[0].action.as_ref().unwrap().expanded,
+        Ok(PluginActionKind::Command {
+            command: "gh".to_string(),
+            args: vec![
+                "issue".to_string(),
+                "list".to_string(),
+                "--search=issues".to_string(),
+                "--repo=nex/nex".to_string(),
+            ],
+            output: CommandOutput::Discard,
+        })
+    );
+
+    // Indexed provider items rank like any other result and carry no plugin action.
+    let unscoped = harness.query("nex repository").unwrap();
+    assert_eq!(unscoped[0].item.id, "plugin:github:item:repo");
+    assert!(unscoped[0].action.is_none());
+
+    let snippet = harness.query("@snippets signature").unwrap();
+    assert_eq!(snippet[0].item.id, "plugin:github:snippet:sig");
+    let resolved = snippet[0].action.as_ref().unwrap();
+    assert_eq!(
+        resolved.expanded,
+        Ok(PluginActionKind::Snippet {
+            text: "Cheers,\nnex team".to_string()
+        })
+    );
+    assert_eq!(resolved.cursor, Some(7));
+
+    let paste = harness
+        .resolve_action("plugin:github:action:paste", "gh paste")
+        .unwrap();
+    assert_eq!(
+        paste.expanded,
+        Ok(PluginActionKind::Copy {
+            text: "#42 (2026-01-02)".to_string()
+        })
+    );
+    assert_eq!(
+        harness
+            .resolve_action("plugin:github:action:broken", "gh broken")
+            .unwrap()
+            .expanded,
+        Err(PlaceholderError::UnknownPlaceholder("home".to_string()))
+    );
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn harness_drops_the_action_word_from_unrouted_queries() {
+    let root = plugin_dir(
+        "harness-unrouted",
+        &[serde_json::json!({
+            "id": "shout",
+            "capabilities": ["run_commands"],
+            "actions": [{
+                "id": "shout",
+                "title": "Shout",
+                "keywords": ["yell"],
+                "type": "command",
+                "command": "echo",
+                "args": ["{query}"]
+            }]
+        })],
+    );
+    let harness = PluginTestHarness::load(&root).unwrap();
+    let args_for = |query: &str| match harness
+        .resolve_action("plugin:shout:action:shout", query)
+        .unwrap()
+        .expanded
+    {
+        Ok(PluginActionKind::Command { args, .. }) => args,
+        other => panic!("unexpected expansion {other:?}"),
+    };
+
+    assert_eq!(args_for("shout hello there"), vec!["hello there"]);
+    assert_eq!(args_for("YELL hi"), vec!["hi"]);
+    assert_eq!(args_for("shout"), vec![""]);
+    assert_eq!(args_for("say shout"), vec!["say shout"]);
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn harness_rejects_invalid_manifests() {
+    let root = plugin_dir(
+        "harness-invalid",
+        &[serde_json::json!({ "id": "bad", "actions": [{ "id": "a" }] })],
+    );
+    let errors = PluginTestHarness::load(&root).err().unwrap();
+    assert!(
+        errors
+            .iter()
+            .any(|error| error.ends_with("actions[0].title: required")),
+        "{errors:?}"
+    );
+    assert!(PluginTestHarness::load(root.join("missing")).is_err());
+
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -249,3 +249,33 @@ fn visibility_filter_can_hide_folders() {
     assert!(!ids.contains(&"folder"));
     assert!(ids.contains(&"app"));
 }
+
+#[test]
+fn bookmarks_mode_matches_only_bookmark_items() {
+    let items = vec![
+        SearchItem::new(
+            "bookmark:https://grafana.internal/d/ops",
+            "bookmark",
+            "Ops Dashboard",
+            "https://grafana.internal/d/ops",
+        ),
+        SearchItem::new("file", "file", "Dashboard.md", "C:\\Docs\\Dashboard.md"),
+        SearchItem::new("app", "app", "Dashboard Tool", "C:\\Apps\\dash.exe"),
+    ];
+
+    let filter = SearchFilter {
+        mode: nex_core::config::SearchMode::Bookmarks,
+        ..SearchFilter::default()
+    };
+    let results = nex_core::search::search_with_filter(&items, "dashboard", 10, &filter);
+    let ids: Vec<&str> = results.iter().map(|item| item.id.as_str()).collect();
+    assert_eq!(ids, vec!["bookmark:https://grafana.internal/d/ops"]);
+
+    let filter = SearchFilter {
+        kind_filter: Some("bookmarks".to_string()),
+        ..SearchFilter::default()
+    };
+    let results = nex_core::search::search_with_filter(&items, "dashboard", 10, &filter);
+    let ids: Vec<&str> = results.iter().map(|item| item.id.as_str()).collect();
+    assert_eq!(ids, vec!["bookmark:https://grafana.internal/d/ops"]);
+}
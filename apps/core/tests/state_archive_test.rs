@@ -0,0 +1,147 @@
+mod common;
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::temp_root;
+use nex_core::index_store::{self, QuerySelectionRecord};
+use nex_core::model::SearchItem;
+use nex_core::state_archive::{export_state, import_state, PathRewrite};
+
+fn profile_config(root: &Path) -> nex_core::config::Config {
+    let config_path = root.join("config.toml");
+    let cfg = nex_core::config::load(Some(&config_path)).unwrap();
+    nex_core::config::write_user_template(&cfg, &config_path).unwrap();
+    cfg
+}
+
+#[test]
+fn exported_state_imports_with_rewritten_profile_paths() {
+    let source_root = temp_root("state-source");
+    let target_root = temp_root("state-target");
+    let source_cfg = profile_config(&source_root);
+    let target_cfg = profile_config(&target_root);
+    let source_prefix = source_root.to_string_lossy().into_owned();
+    let target_prefix = target_root.to_string_lossy().into_owned();
+
+    let source_db = index_store::open_from_config(&source_cfg).unwrap();
+    let notes_path = source_root.join("notes.txt").to_string_lossy().into_owned();
+    let used = SearchItem::new(
+        &format!("file:{notes_path}"),
+        "file",
+        "notes.txt",
+        &notes_path,
+    )
+    .with_usage(7, 1_700_000_000);
+    index_store::upsert_item(&source_db, &used).unwrap();
+    index_store::upsert_item(
+        &source_db,
+        &SearchItem::new("file:unused", "file", "unused.txt", "/tmp/unused.txt"),
+    )
+    .unwrap();
+    index_store::record_query_selection(&source_db, "no", "all", &used.id, 1_700_000_000).unwrap();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    std::fs::write(
+        source_root.join("clipboard-history.json"),
+        format!(r#"[{{"id":"clip-1","text":"hello","captured_epoch_secs":{now}}}]"#),
+    )
+    .unwrap();
+
+    let archive = source_root.join("state.tar");
+    let exported = export_state(&source_cfg, &source_db, &archive).unwrap();
+    assert_eq!(exported.items, 1);
+    assert_eq!(exported.query_selections, 1);
+    assert_eq!(exported.clipboard_entries, 1);
+    assert!(exported.config_included);
+
+    let target_db = index_store::open_from_config(&target_cfg).unwrap();
+    let rewrites = vec![PathRewrite::new(&source_prefix, &target_prefix)];
+    let imported = import_state(&target_cfg, &target_db, &archive, &rewrites).unwrap();
+    assert_eq!(imported.items, 1);
+    assert_eq!(imported.clipboard_entries, 1);
+    assert!(imported.config_imported);
+
+    let target_notes = target_root.join("notes.txt").to_string_lossy().into_owned();
+    let migrated = index_store::get_item(&target_db, &format!("file:{target_notes}"))
+        .unwrap()
+        .expect("usage should follow the rewritten id");
+    assert_eq!(migrated.path, target_notes);
+    assert_eq!(migrated.use_count, 7);
+    assert_eq!(
+        index_store::list_all_query_selections(&target_db).unwrap(),
+        vec![QuerySelectionRecord {
+            query_norm: "no".to_string(),
+            mode: "all".to_string(),
+            item_id: migrated.id.clone(),
+            selected_count: 1,
+            last_selected_epoch_secs: 1_700_000_000,
+        }]
+    );
+    assert_eq!(
+        nex_core::clipboard_history::load_history(&target_cfg).len(),
+        1
+    );
+
+    assert!(nex_core::config::load(Some(&target_cfg.config_path)).is_ok());
+
+    let _ = std::fs::remove_dir_all(&source_root);
+    let _ = std::fs::remove_dir_all(&target_root);
+}
+
+#[test]
+fn import_keeps_stronger_local_usage() {
+    let root = temp_root("state-merge");
+    let cfg = profile_config(&root);
+    let db = index_store::open_from_config(&cfg).unwrap();
+    let item = SearchItem::new("app:code", "app", "Code", "C:\\Code.exe").with_usage(2, 100);
+    index_store::upsert_item(&db, &item).unwrap();
+
+    let archive = root.join("state.tar");
+    export_state(&cfg, &db, &archive).unwrap();
+    index_store::upsert_item(&db, &item.clone().with_usage(9, 200)).unwrap();
+    import_state(&cfg, &db, &archive, &[]).unwrap();
+
+    let merged = index_store::get_item(&db, "app:code").unwrap().unwrap();
+    assert_eq!(merged.use_count, 9);
+    assert_eq!(merged.last_accessed_epoch_secs, 200);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn import_rewrites_only_config_paths_under_the_source_profile() {
+    let root = temp_root("state-prefix");
+    let source_profile = root.join("al");
+    let target_profile = root.join("bob");
+    let mut source_cfg = profile_config(&source_profile);
+    let target_cfg = profile_config(&target_profile);
+    let sibling_docs = root.join("alice").join("docs");
+    source_cfg.discovery_roots = vec![source_profile.join("docs"), sibling_docs.clone()];
+    source_cfg.hotkey_help = format!("see {}", source_profile.join("help.txt").display());
+    nex_core::config::save(&source_cfg).unwrap();
+
+    let source_db = index_store::open_from_config(&source_cfg).unwrap();
+    let archive = root.join("state.tar");
+    export_state(&source_cfg, &source_db, &archive).unwrap();
+
+    let target_db = index_store::open_from_config(&target_cfg).unwrap();
+    let rewrites = vec![PathRewrite::new(
+        &source_profile.to_string_lossy(),
+        &target_profile.to_string_lossy(),
+    )];
+    let imported = import_state(&target_cfg, &target_db, &archive, &rewrites).unwrap();
+    assert!(imported.config_imported);
+
+    let loaded = nex_core::config::load(Some(&target_cfg.config_path)).unwrap();
+    assert_eq!(
+        loaded.discovery_roots,
+        vec![target_profile.join("docs"), sibling_docs]
+    );
+    assert_eq!(loaded.hotkey_help, source_cfg.hotkey_help);
+    assert_eq!(loaded.config_path, target_cfg.config_path);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
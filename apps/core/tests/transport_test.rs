@@ -1,22 +1,14 @@
-use nex_core::contract::{CoreRequest, LaunchRequest, SearchRequest};
+mod common;
+
+use common::service_with_seed_item;
+use nex_core::config::SearchMode;
+use nex_core::contract::{
+    CoreRequest, CoreResponse, ItemDto, ItemIdRequest, LaunchRequest, RecordSelectionRequest,
+    SearchRequest, UpsertItemRequest,
+};
 use nex_core::core_service::CoreService;
 use nex_core::transport::{handle_json, handle_request, ErrorCode, TransportResponse};
 
-fn service_with_seed_item() -> CoreService {
-    let config = nex_core::config::Config::default();
-    let db = nex_core::index_store::open_memory().unwrap();
-    let service = CoreService::with_connection(config, db).unwrap();
-    service
-        .upsert_item(&nex_core::model::SearchItem::new(
-            "seed",
-            "app",
-            "Code",
-            "C:\\Code.exe",
-        ))
-        .unwrap();
-    service
-}
-
 #[test]
 fn request_handler_returns_ok_transport_response() {
     let service = service_with_seed_item();
@@ -26,6 +18,7 @@ fn request_handler_returns_ok_transport_response() {
         CoreRequest::Search(SearchRequest {
             query: "code".into(),
             limit: Some(5),
+            mode: None,
         }),
     );
 
@@ -84,3 +77,127 @@ fn json_handler_returns_item_not_found_error_code() {
         _ => panic!("expected item not found error"),
     }
 }
+
+fn expect_ok(service: &CoreService, request: CoreRequest) -> CoreResponse {
+    match handle_request(service, request) {
+        TransportResponse::Ok { response } => response,
+        TransportResponse::Err { error } => panic!("unexpected error: {error:?}"),
+    }
+}
+
+#[test]
+fn item_management_requests_round_trip() {
+    let service = service_with_seed_item();
+
+    let upserted = expect_ok(
+        &service,
+        CoreRequest::UpsertItem(UpsertItemRequest {
+            item: ItemDto {
+                id: "custom".into(),
+                kind: "app".into(),
+                title: "Custom Tool".into(),
+                path: "C:\\Custom.exe".into(),
+                subtitle: String::new(),
+                use_count: 0,
+                last_accessed_epoch_secs: 0,
+            },
+        }),
+    );
+    assert!(matches!(upserted, CoreResponse::UpsertItem(ref item) if item.item.id == "custom"));
+
+    match expect_ok(
+        &service,
+        CoreRequest::GetItem(ItemIdRequest {
+            id: "custom".into(),
+        }),
+    ) {
+        CoreResponse::GetItem(response) => assert_eq!(response.item.title, "Custom Tool"),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    match expect_ok(
+        &service,
+        CoreRequest::RecordSelection(RecordSelectionRequest {
+            query: "cust".into(),
+            mode: SearchMode::All,
+            id: "custom".into(),
+        }),
+    ) {
+        CoreResponse::RecordSelection(response) => assert!(response.recorded),
+        other => panic!("unexpected response: {other:?}"),
+    }
+
+    match expect_ok(
+        &service,
+        CoreRequest::DeleteItem(ItemIdRequest {
+            id: "custom".into(),
+        }),
+    ) {
+        CoreResponse::DeleteItem(response) => assert!(response.deleted),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    match handle_request(
+        &service,
+        CoreRequest::GetItem(ItemIdRequest {
+            id: "custom".into(),
+        }),
+    ) {
+        TransportResponse::Err { error } => assert_eq!(error.code, ErrorCode::ItemNotFound),
+        _ => panic!("expected item not found error"),
+    }
+}
+
+#[test]
+fn status_and_provider_requests_accept_bare_kinds() {
+    let service = service_with_seed_item();
+
+    let raw = handle_json(&service, r#"{"kind":"GetStatus"}"#);
+    let parsed: TransportResponse = serde_json::from_str(&raw).unwrap();
+    match parsed {
+        TransportResponse::Ok {
+            response: CoreResponse::GetStatus(status),
+        } => {
+            assert_eq!(status.indexed_items, 1);
+            assert!(!status.read_only);
+            assert!(status.schema_version > 0);
+        }
+        _ => panic!("expected status response"),
+    }
+
+    let raw = handle_json(&service, r#"{"kind":"ListProviders"}"#);
+    let parsed: TransportResponse = serde_json::from_str(&raw).unwrap();
+    assert!(matches!(
+        parsed,
+        TransportResponse::Ok {
+            response: CoreResponse::ListProviders(_)
+        }
+    ));
+}
+
+#[test]
+fn mutating_requests_are_rejected_on_read_only_index() {
+    let root = std::env::temp_dir().join(format!(
+        "nex-transport-read-only-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    let config = nex_core::config::Config {
+        index_db_path: root.join("index.sqlite3"),
+        ..nex_core::config::Config::default()
+    };
+    drop(CoreService::new(config.clone()).unwrap());
+    let service = CoreService::open_read_only(config).unwrap();
+
+    match handle_request(
+        &service,
+        CoreRequest::DeleteItem(ItemIdRequest { id: "seed".into() }),
+    ) {
+        TransportResponse::Err { error } => assert_eq!(error.code, ErrorCode::ReadOnly),
+        _ => panic!("expected read-only error"),
+    }
+
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -37,6 +37,7 @@ fn non_windows_fallback_smoke_still_roundtrips() {
     let request = CoreRequest::Search(SearchRequest {
         query: "code".into(),
         limit: Some(5),
+        mode: None,
     });
     let response = nex_core::transport::handle_request(&service, request);
 
@@ -84,6 +85,7 @@ fn windows_runtime_smoke_registers_hotkey_and_transport_roundtrip() {
     let payload = serde_json::to_string(&CoreRequest::Search(SearchRequest {
         query: "code".into(),
         limit: Some(5),
+        mode: None,
     }))
     .expect("request should serialize");
 
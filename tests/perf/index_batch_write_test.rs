@@ -0,0 +1,85 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use nex_core::index_store::{self, IndexBatchWriter};
+use nex_core::model::SearchItem;
+
+fn bench_items(count: usize, generation: u32) -> Vec<SearchItem> {
+    (0..count)
+        .map(|i| {
+            SearchItem::new(
+                &format!("file:{i}"),
+                "file",
+                &format!("Document_{i:05}_{generation}.txt"),
+                &format!("C:\\Docs\\Document_{i:05}.txt"),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn batched_refresh_of_40k_items_under_budget() {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("nex-batch-bench-{unique}"));
+    let db = index_store::open_file(&root.join("index.sqlite3")).unwrap();
+
+    let mut samples_ms = Vec::with_capacity(2);
+    for generation in 0..2 {
+        let items = bench_items(40_000, generation);
+        let started = Instant::now();
+        let mut writer = IndexBatchWriter::begin(&db).unwrap();
+        for item in &items {
+            writer.upsert_item(item).unwrap();
+        }
+        for i in 0..2_000 {
+            writer.delete_item(&format!("file:{}", 39_999 - i)).unwrap();
+        }
+        assert_eq!(writer.upserted(), 40_000);
+        assert_eq!(writer.deleted(), 2_000);
+        writer.commit().unwrap();
+        samples_ms.push(started.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    assert_eq!(index_store::list_items(&db).unwrap().len(), 38_000);
+    drop(db);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    // Generous enough for unoptimized test builds; row-by-row autocommit on disk is far slower.
+    let worst_ms = samples_ms.iter().cloned().fold(0.0_f64, f64::max);
+    assert!(
+        worst_ms <= 8_000.0,
+        "batched refresh too slow: worst {worst_ms:.1}ms (budget 8000ms); samples={samples_ms:?}",
+    );
+}
+
+#[test]
+fn dropped_batch_writer_rolls_back() {
+    let db = index_store::open_memory().unwrap();
+    index_store::upsert_item(
+        &db,
+        &SearchItem::new("keep", "file", "Keep", "C:\\keep.txt"),
+    )
+    .unwrap();
+
+    {
+        let mut writer = IndexBatchWriter::begin(&db).unwrap();
+        writer
+            .upsert_item(&SearchItem::new(
+                "partial",
+                "file",
+                "Partial",
+                "C:\\partial.txt",
+            ))
+            .unwrap();
+        writer.delete_item("keep").unwrap();
+    }
+
+    let ids: Vec<String> = index_store::list_items(&db)
+        .unwrap()
+        .into_iter()
+        .map(|item| item.id)
+        .collect();
+    assert_eq!(ids, vec!["keep"]);
+}